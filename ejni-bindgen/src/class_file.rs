@@ -0,0 +1,324 @@
+use crate::error::{BindgenError, BindgenResult};
+
+/// `ACC_PUBLIC`
+pub const ACC_PUBLIC: u16 = 0x0001;
+/// `ACC_STATIC`
+pub const ACC_STATIC: u16 = 0x0008;
+/// `ACC_BRIDGE`, set on methods generated by the compiler for generics
+pub const ACC_BRIDGE: u16 = 0x0040;
+/// `ACC_SYNTHETIC`, set on members which do not appear in the source
+pub const ACC_SYNTHETIC: u16 = 0x1000;
+/// `ACC_MODULE`, set on `module-info.class`
+pub const ACC_MODULE: u16 = 0x8000;
+
+/// The parts of a compiled `.class` file needed to generate bindings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassFile {
+    /// The access flags of the class, e.g [ACC_PUBLIC]
+    pub access_flags: u16,
+    /// The binary name of the class in internal form, e.g `com/acme/Order`
+    pub name: String,
+    /// The binary name of the superclass in internal form. None for `java/lang/Object` itself
+    pub super_name: Option<String>,
+    /// The methods declared by the class, including constructors named `<init>`
+    pub methods: Vec<MethodInfo>,
+}
+
+/// A method declared by a class
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MethodInfo {
+    /// The access flags of the method, e.g [ACC_STATIC]
+    pub access_flags: u16,
+    /// The name of the method, e.g `getId`
+    pub name: String,
+    /// The JNI signature of the method, e.g `(I)Ljava/lang/String;`
+    pub descriptor: String,
+    /// The names of the parameters, if the class was compiled with `javac -parameters`
+    pub parameter_names: Option<Vec<String>>,
+}
+
+impl ClassFile {
+    /// Parse the contents of a `.class` file
+    pub fn parse(bytes: &[u8]) -> BindgenResult<Self> {
+        let mut reader = Reader { bytes, pos: 0 };
+        if reader.u4()? != 0xCAFE_BABE {
+            return Err(BindgenError::ClassFormat("Not a class file".to_string()));
+        }
+        // The minor and major version
+        reader.skip(4)?;
+
+        let pool = ConstantPool::parse(&mut reader)?;
+        let access_flags = reader.u2()?;
+        let name = pool.class_name(reader.u2()?)?;
+        let super_name = match reader.u2()? {
+            0 => None,
+            index => Some(pool.class_name(index)?),
+        };
+
+        let interfaces = reader.u2()? as usize;
+        reader.skip(interfaces * 2)?;
+
+        let fields = reader.u2()?;
+        for _ in 0..fields {
+            // The access flags, name and descriptor
+            reader.skip(6)?;
+            skip_attributes(&mut reader)?;
+        }
+
+        let methods = (0..reader.u2()?)
+            .map(|_| MethodInfo::parse(&mut reader, &pool))
+            .collect::<BindgenResult<Vec<_>>>()?;
+
+        Ok(Self {
+            access_flags,
+            name,
+            super_name,
+            methods,
+        })
+    }
+
+    /// Returns true if the class is public
+    pub fn is_public(&self) -> bool {
+        self.access_flags & ACC_PUBLIC != 0
+    }
+
+    /// The binary name of the class, e.g `com.acme.Order` or `com.acme.Order$Line` for nested classes
+    pub fn binary_name(&self) -> String {
+        self.name.replace('/', ".")
+    }
+}
+
+impl MethodInfo {
+    fn parse(reader: &mut Reader<'_>, pool: &ConstantPool) -> BindgenResult<Self> {
+        let access_flags = reader.u2()?;
+        let name = pool.utf8(reader.u2()?)?.to_string();
+        let descriptor = pool.utf8(reader.u2()?)?.to_string();
+
+        let mut parameter_names = None;
+        for _ in 0..reader.u2()? {
+            let attribute = pool.utf8(reader.u2()?)?;
+            let len = reader.u4()? as usize;
+            if attribute != "MethodParameters" {
+                reader.skip(len)?;
+                continue;
+            }
+
+            let names = (0..reader.u1()?)
+                .map(|_| {
+                    let name = match reader.u2()? {
+                        0 => String::new(),
+                        index => pool.utf8(index)?.to_string(),
+                    };
+                    // The access flags of the parameter
+                    reader.skip(2)?;
+                    Ok(name)
+                })
+                .collect::<BindgenResult<Vec<_>>>()?;
+            // Parameters without a name, e.g of synthetic constructors, can not be used
+            if names.iter().all(|name| !name.is_empty()) {
+                parameter_names = Some(names);
+            }
+        }
+
+        Ok(Self {
+            access_flags,
+            name,
+            descriptor,
+            parameter_names,
+        })
+    }
+
+    /// Returns true if the method is public
+    pub fn is_public(&self) -> bool {
+        self.access_flags & ACC_PUBLIC != 0
+    }
+
+    /// Returns true if the method is static
+    pub fn is_static(&self) -> bool {
+        self.access_flags & ACC_STATIC != 0
+    }
+
+    /// Returns true if the method was generated by the compiler, e.g a bridge method
+    pub fn is_synthetic(&self) -> bool {
+        self.access_flags & (ACC_SYNTHETIC | ACC_BRIDGE) != 0
+    }
+
+    /// Returns true if the method is a constructor
+    pub fn is_constructor(&self) -> bool {
+        self.name == "<init>"
+    }
+}
+
+fn skip_attributes(reader: &mut Reader<'_>) -> BindgenResult<()> {
+    for _ in 0..reader.u2()? {
+        reader.skip(2)?;
+        let len = reader.u4()? as usize;
+        reader.skip(len)?;
+    }
+    Ok(())
+}
+
+/// The constant pool of a class file. Only the entries referenced by [ClassFile] are kept
+struct ConstantPool {
+    entries: Vec<Constant>,
+}
+
+enum Constant {
+    Utf8(String),
+    /// A class, holding the index of its name
+    Class(u16),
+    Other,
+}
+
+impl ConstantPool {
+    fn parse(reader: &mut Reader<'_>) -> BindgenResult<Self> {
+        let count = reader.u2()? as usize;
+        // Index 0 is not used
+        let mut entries = vec![Constant::Other];
+        while entries.len() < count {
+            let tag = reader.u1()?;
+            let constant = match tag {
+                1 => {
+                    let len = reader.u2()? as usize;
+                    let bytes = reader.take(len)?;
+                    let string = cesu8::from_java_cesu8(bytes).map_err(|_| {
+                        BindgenError::ClassFormat(
+                            "Invalid modified UTF-8 in the constant pool".to_string(),
+                        )
+                    })?;
+                    Constant::Utf8(string.into_owned())
+                }
+                7 => Constant::Class(reader.u2()?),
+                // MethodType, String, Module and Package
+                8 | 16 | 19 | 20 => {
+                    reader.skip(2)?;
+                    Constant::Other
+                }
+                // MethodHandle
+                15 => {
+                    reader.skip(3)?;
+                    Constant::Other
+                }
+                // Integer, Float, Fieldref, Methodref, InterfaceMethodref, NameAndType, Dynamic and InvokeDynamic
+                3 | 4 | 9 | 10 | 11 | 12 | 17 | 18 => {
+                    reader.skip(4)?;
+                    Constant::Other
+                }
+                // Long and Double, which take up two entries
+                5 | 6 => {
+                    reader.skip(8)?;
+                    entries.push(Constant::Other);
+                    Constant::Other
+                }
+                tag => {
+                    return Err(BindgenError::ClassFormat(format!(
+                        "Unknown constant pool tag {}",
+                        tag
+                    )))
+                }
+            };
+            entries.push(constant);
+        }
+        Ok(Self { entries })
+    }
+
+    fn utf8(&self, index: u16) -> BindgenResult<&str> {
+        match self.entries.get(index as usize) {
+            Some(Constant::Utf8(string)) => Ok(string),
+            _ => Err(BindgenError::ClassFormat(format!(
+                "Constant pool entry {} is not a string",
+                index
+            ))),
+        }
+    }
+
+    fn class_name(&self, index: u16) -> BindgenResult<String> {
+        match self.entries.get(index as usize) {
+            Some(Constant::Class(name)) => Ok(self.utf8(*name)?.to_string()),
+            _ => Err(BindgenError::ClassFormat(format!(
+                "Constant pool entry {} is not a class",
+                index
+            ))),
+        }
+    }
+}
+
+/// Reads the big endian values of a class file
+struct Reader<'b> {
+    bytes: &'b [u8],
+    pos: usize,
+}
+
+impl<'b> Reader<'b> {
+    fn take(&mut self, len: usize) -> BindgenResult<&'b [u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| {
+                BindgenError::ClassFormat("Unexpected end of the class file".to_string())
+            })?;
+        let bytes = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn skip(&mut self, len: usize) -> BindgenResult<()> {
+        self.take(len).map(|_| ())
+    }
+
+    fn u1(&mut self) -> BindgenResult<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u2(&mut self) -> BindgenResult<u16> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u4(&mut self) -> BindgenResult<u32> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ORDER: &[u8] = include_bytes!("../../test-classes/ejni/test/Order.class");
+    const NATIVE_TEST: &[u8] = include_bytes!("../../test-classes/ejni/test/NativeTest.class");
+
+    #[test]
+    fn parse() {
+        let class = ClassFile::parse(ORDER).unwrap();
+        assert_eq!("ejni/test/Order", class.name);
+        assert_eq!("ejni.test.Order", class.binary_name());
+        assert_eq!(Some("java/lang/Object"), class.super_name.as_deref());
+        assert!(class.is_public());
+
+        let method = class.methods.iter().find(|m| m.name == "markPaid").unwrap();
+        assert_eq!("(Z)V", method.descriptor);
+        assert!(method.is_public());
+        assert!(!method.is_static());
+        assert!(class.methods.iter().any(MethodInfo::is_constructor));
+    }
+
+    #[test]
+    fn statics() {
+        let class = ClassFile::parse(NATIVE_TEST).unwrap();
+        let add = class
+            .methods
+            .iter()
+            .filter(|m| m.name == "add")
+            .map(|m| (m.descriptor.as_str(), m.is_static()))
+            .collect::<Vec<_>>();
+        assert_eq!(vec![("(II)I", true), ("(JJ)J", true)], add);
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(ClassFile::parse(b"PK\x03\x04").is_err());
+        assert!(ClassFile::parse(&ORDER[..ORDER.len() / 2]).is_err());
+    }
+}
@@ -0,0 +1,21 @@
+use thiserror::Error;
+
+/// Describes the errors that can occur when generating bindings
+#[derive(Debug, Error)]
+pub enum BindgenError {
+    /// Reading an input or writing the bindings failed
+    #[error("IO Error: {0}")]
+    Io(#[from] std::io::Error),
+    /// A jar could not be read
+    #[error("Invalid jar: {0}")]
+    Jar(#[from] zip::result::ZipError),
+    /// A class file is malformed
+    #[error("Invalid class file: {0}")]
+    ClassFormat(String),
+    /// The descriptor of a method is malformed
+    #[error("Invalid descriptor: {0}")]
+    Descriptor(#[from] ejni::SignatureError),
+}
+
+/// Result returned from ejni-bindgen functions
+pub type BindgenResult<T> = std::result::Result<T, BindgenError>;
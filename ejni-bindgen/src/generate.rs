@@ -0,0 +1,566 @@
+use crate::class_file::{ClassFile, MethodInfo};
+use crate::error::BindgenResult;
+use ejni::{Signature, TypeDescriptor};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+/// Rust keywords, which are prefixed with `r#` when used as an identifier
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "box", "break", "const", "continue", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "macro", "match", "mod", "move",
+    "mut", "priv", "pub", "ref", "return", "static", "struct", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Keywords which can not be raw identifiers, and names used by the generated code. A `_` is appended to them
+const RESERVED: &[&str] = &["self", "Self", "super", "crate", "env", "_"];
+
+/// Generate the bindings of `classes`
+pub fn generate(classes: &[ClassFile]) -> BindgenResult<String> {
+    let mut names = HashMap::new();
+    let mut taken = HashSet::new();
+    for class in classes {
+        let simple = class
+            .name
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .replace('$', "");
+        let name = unique(&simple, &mut taken);
+        names.insert(class.name.clone(), name);
+    }
+
+    let mut code = String::from("// Generated by ejni-bindgen. Do not edit\n");
+    for class in classes {
+        ClassGenerator {
+            class,
+            names: &names,
+            code: &mut code,
+        }
+        .generate()?;
+    }
+    Ok(code)
+}
+
+/// Returns `name`, or `name` followed by a number if it is taken already
+fn unique(name: &str, taken: &mut HashSet<String>) -> String {
+    let mut candidate = name.to_string();
+    let mut i = 2;
+    while !taken.insert(candidate.clone()) {
+        candidate = format!("{}{}", name, i);
+        i += 1;
+    }
+    candidate
+}
+
+/// Convert a Java name into snake case, e.g `getURLHost` into `get_url_host`
+fn snake_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut snake = String::with_capacity(name.len() + 4);
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let previous = chars[i - 1];
+            let next_lower = matches!(chars.get(i + 1), Some(c) if c.is_lowercase());
+            if previous.is_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_uppercase() && next_lower)
+            {
+                snake.push('_');
+            }
+        }
+        if *c == '$' {
+            snake.push('_');
+        } else {
+            snake.extend(c.to_lowercase());
+        }
+    }
+    snake
+}
+
+/// Make `name` a valid Rust identifier
+fn identifier(name: String) -> String {
+    if RESERVED.contains(&name.as_str()) {
+        format!("{}_", name)
+    } else if KEYWORDS.contains(&name.as_str()) {
+        format!("r#{}", name)
+    } else {
+        name
+    }
+}
+
+/// The name of a type in Java source, e.g `int` or `String[]`
+fn java_name(ty: &TypeDescriptor) -> String {
+    match ty {
+        TypeDescriptor::Boolean => "boolean".to_string(),
+        TypeDescriptor::Byte => "byte".to_string(),
+        TypeDescriptor::Char => "char".to_string(),
+        TypeDescriptor::Short => "short".to_string(),
+        TypeDescriptor::Int => "int".to_string(),
+        TypeDescriptor::Long => "long".to_string(),
+        TypeDescriptor::Float => "float".to_string(),
+        TypeDescriptor::Double => "double".to_string(),
+        TypeDescriptor::Void => "void".to_string(),
+        TypeDescriptor::Object(name) => name
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .replace('$', "."),
+        TypeDescriptor::Array(component) => format!("{}[]", java_name(component)),
+    }
+}
+
+/// Distinguishes overloads in their Rust name, e.g `int` or `string_array`
+fn overload_suffix(ty: &TypeDescriptor) -> String {
+    match ty {
+        TypeDescriptor::Boolean => "bool".to_string(),
+        TypeDescriptor::Array(component) => format!("{}_array", overload_suffix(component)),
+        ty => snake_case(&java_name(ty)).replace('.', "_"),
+    }
+}
+
+/// The JValue variant and Rust type of a primitive
+fn primitive(ty: &TypeDescriptor) -> Option<(&'static str, &'static str)> {
+    let primitive = match ty {
+        TypeDescriptor::Boolean => ("Bool", "bool"),
+        TypeDescriptor::Byte => ("Byte", "i8"),
+        TypeDescriptor::Char => ("Char", "u16"),
+        TypeDescriptor::Short => ("Short", "i16"),
+        TypeDescriptor::Int => ("Int", "i32"),
+        TypeDescriptor::Long => ("Long", "i64"),
+        TypeDescriptor::Float => ("Float", "f32"),
+        TypeDescriptor::Double => ("Double", "f64"),
+        _ => return None,
+    };
+    Some(primitive)
+}
+
+/// A method of the wrapper, generated from a Java method or constructor
+struct Method<'c> {
+    info: &'c MethodInfo,
+    signature: Signature,
+    /// The name of the Rust function
+    name: String,
+}
+
+struct ClassGenerator<'c> {
+    class: &'c ClassFile,
+    /// The names of the wrappers, by the internal name of their class
+    names: &'c HashMap<String, String>,
+    code: &'c mut String,
+}
+
+impl<'c> ClassGenerator<'c> {
+    fn generate(mut self) -> BindgenResult<()> {
+        let methods = self.methods()?;
+        let name = &self.names[&self.class.name];
+        let binary_name = self.class.binary_name();
+        let internal = &self.class.name;
+
+        let _ = write!(
+            self.code,
+            r#"
+/// Wrapper around `{binary_name}`
+pub struct {name}<'a> {{
+    /// The {name} itself
+    pub inner: ::ejni::Object<'a>,
+    env: &'a ::ejni::private::jni::JNIEnv<'a>,
+}}
+
+impl<'a> ::ejni::JavaType<'a> for {name}<'a> {{
+    fn class(env: &'a ::ejni::private::jni::JNIEnv<'a>) -> ::ejni::Result<::ejni::Class<'a>> {{
+        ::ejni::private::class(env, "{internal}")
+    }}
+}}
+
+impl<'a> ::ejni::IntoJava<'a> for {name}<'a> {{
+    fn into_java(self, _: &'a ::ejni::private::jni::JNIEnv<'a>) -> ::ejni::Result<::ejni::Object<'a>> {{
+        Ok(self.inner)
+    }}
+}}
+
+impl<'a> ::ejni::FromJava<'a> for {name}<'a> {{
+    fn from_java(object: ::ejni::Object<'a>) -> ::ejni::Result<Self> {{
+        Ok(Self::new(object.env, object))
+    }}
+}}
+
+impl<'a> {name}<'a> {{
+    /// Create a new {name} wrapper. The caller must guarantee that the passed in Object is an instance of `{binary_name}`
+    pub fn new(env: &'a ::ejni::private::jni::JNIEnv<'a>, object: ::ejni::Object<'a>) -> Self {{
+        Self {{ inner: object, env }}
+    }}
+"#,
+            binary_name = binary_name,
+            name = name,
+            internal = internal,
+        );
+
+        for method in &methods {
+            self.method(method);
+        }
+        self.code.push_str("}\n");
+        Ok(())
+    }
+
+    /// Parse the signatures of the methods, and name them. Overloads are distinguished by the types of their parameters
+    fn methods(&self) -> BindgenResult<Vec<Method<'c>>> {
+        let mut methods = self
+            .class
+            .methods
+            .iter()
+            .map(|info| {
+                let base = match info.is_constructor() {
+                    true => "create".to_string(),
+                    false => snake_case(&info.name),
+                };
+                Ok((base, info, Signature::parse(&info.descriptor)?))
+            })
+            .collect::<BindgenResult<Vec<_>>>()?;
+        // The overload with the fewest parameters keeps the plain name
+        methods.sort_by(|(a, a_info, a_sig), (b, b_info, b_sig)| {
+            (a, a_sig.params.len(), &a_info.descriptor).cmp(&(
+                b,
+                b_sig.params.len(),
+                &b_info.descriptor,
+            ))
+        });
+
+        let mut overloads = HashMap::<String, usize>::new();
+        for (base, _, _) in &methods {
+            *overloads.entry(base.clone()).or_default() += 1;
+        }
+
+        let mut taken = HashSet::new();
+        taken.insert("new".to_string());
+        Ok(methods
+            .into_iter()
+            .map(|(base, info, signature)| {
+                let mut name = base.clone();
+                if overloads[&base] > 1 && taken.contains(&identifier(base.clone())) {
+                    for param in &signature.params {
+                        name.push('_');
+                        name.push_str(&overload_suffix(param));
+                    }
+                }
+                let name = unique(&identifier(name), &mut taken);
+                Method {
+                    info,
+                    signature,
+                    name,
+                }
+            })
+            .collect())
+    }
+
+    fn method(&mut self, method: &Method<'_>) {
+        let info = method.info;
+        let is_static = info.is_static() || info.is_constructor();
+        let env = if is_static { "env" } else { "self.env" };
+        let class_name = &self.names[&self.class.name];
+
+        let param_names = (0..method.signature.params.len())
+            .map(|i| {
+                let name = info
+                    .parameter_names
+                    .as_ref()
+                    .and_then(|names| names.get(i))
+                    .map_or_else(|| format!("arg{}", i), |name| snake_case(name));
+                identifier(name)
+            })
+            .collect::<Vec<_>>();
+
+        // The documentation and signature of the Rust function
+        let java_params = method
+            .signature
+            .params
+            .iter()
+            .map(java_name)
+            .collect::<Vec<_>>()
+            .join(", ");
+        let _ = match info.is_constructor() {
+            true => writeln!(
+                self.code,
+                "\n    /// Invokes the constructor `{}({})`",
+                class_name, java_params
+            ),
+            false => writeln!(
+                self.code,
+                "\n    /// Invokes `{}#{}({})`",
+                class_name, info.name, java_params
+            ),
+        };
+
+        let mut params = Vec::new();
+        if is_static {
+            params.push("env: &'a ::ejni::private::jni::JNIEnv<'a>".to_string());
+        } else {
+            params.push("&self".to_string());
+        }
+        for (name, ty) in param_names.iter().zip(&method.signature.params) {
+            params.push(format!("{}: {}", name, self.param_type(ty)));
+        }
+        let ret = match info.is_constructor() {
+            true => "Self".to_string(),
+            false => self.return_type(&method.signature.ret),
+        };
+        let _ = writeln!(
+            self.code,
+            "    pub fn {}({}) -> ::ejni::Result<{}> {{",
+            method.name,
+            params.join(", "),
+            ret
+        );
+
+        // Strings are converted before the call, so the JValues can borrow them
+        for (name, ty) in param_names.iter().zip(&method.signature.params) {
+            if is_string(ty) {
+                let _ = writeln!(
+                    self.code,
+                    "        let {name} = ::ejni::JavaString::from_rust({env}, {name})?;",
+                    name = name,
+                    env = env
+                );
+            }
+        }
+
+        let values = param_names
+            .iter()
+            .zip(&method.signature.params)
+            .map(|(name, ty)| self.param_value(name, ty))
+            .collect::<Vec<_>>();
+        let values = format!("&[{}]", values.join(", "));
+        let signature = format!("::ejni::signature!(\"{}\")", info.descriptor);
+        let internal = &self.class.name;
+
+        if info.is_constructor() {
+            let _ = write!(
+                self.code,
+                r#"        let object = ::ejni::private::construct(
+            env,
+            "{internal}",
+            {signature},
+            {values},
+        )?;
+        Ok(Self::new(env, object))
+    }}
+"#,
+                internal = internal,
+                signature = signature,
+                values = values,
+            );
+            return;
+        }
+
+        let binding = match method.signature.ret {
+            TypeDescriptor::Void => "",
+            _ => "let value = ",
+        };
+        let call = match is_static {
+            true => format!(
+                r#"        {binding}::ejni::private::call_static_method(
+            env,
+            "{internal}",
+            "{name}",
+            {signature},
+            {values},
+        )?;
+"#,
+                binding = binding,
+                internal = internal,
+                name = info.name,
+                signature = signature,
+                values = values,
+            ),
+            false => format!(
+                r#"        {binding}::ejni::private::call_method(
+            self.env,
+            self.inner.inner,
+            "{internal}",
+            "{name}",
+            {signature},
+            {values},
+        )?;
+"#,
+                binding = binding,
+                internal = internal,
+                name = info.name,
+                signature = signature,
+                values = values,
+            ),
+        };
+        self.code.push_str(&call);
+        let conversion = self.return_value(&method.signature.ret, env);
+        self.code.push_str(&conversion);
+        self.code.push_str("    }\n");
+    }
+
+    /// The name of the generated wrapper of a type, if there is one
+    fn wrapper(&self, ty: &TypeDescriptor) -> Option<&'c str> {
+        match ty {
+            TypeDescriptor::Object(name) => self.names.get(name).map(String::as_str),
+            _ => None,
+        }
+    }
+
+    fn param_type(&self, ty: &TypeDescriptor) -> String {
+        if let Some((_, rust)) = primitive(ty) {
+            return rust.to_string();
+        }
+        if is_string(ty) {
+            return "&str".to_string();
+        }
+        match self.wrapper(ty) {
+            Some(wrapper) => format!("&{}<'a>", wrapper),
+            None => "&::ejni::Object<'a>".to_string(),
+        }
+    }
+
+    fn param_value(&self, name: &str, ty: &TypeDescriptor) -> String {
+        match primitive(ty) {
+            Some(("Bool", _)) => format!(
+                "::ejni::private::jni::objects::JValue::Bool({} as u8)",
+                name
+            ),
+            Some((variant, _)) => format!(
+                "::ejni::private::jni::objects::JValue::{}({})",
+                variant, name
+            ),
+            None if is_string(ty) => format!("{}.into()", name),
+            None if self.wrapper(ty).is_some() => format!("(&{}.inner).into()", name),
+            None => format!("{}.into()", name),
+        }
+    }
+
+    fn return_type(&self, ty: &TypeDescriptor) -> String {
+        if let TypeDescriptor::Void = ty {
+            return "()".to_string();
+        }
+        if let Some((_, rust)) = primitive(ty) {
+            return rust.to_string();
+        }
+        if is_string(ty) {
+            return "Option<String>".to_string();
+        }
+        match self.wrapper(ty) {
+            Some(wrapper) => format!("Option<{}<'a>>", wrapper),
+            None => "Option<::ejni::Object<'a>>".to_string(),
+        }
+    }
+
+    /// Convert the JValue `value` returned from Java into the return type
+    fn return_value(&self, ty: &TypeDescriptor, env: &str) -> String {
+        let getter = match ty {
+            TypeDescriptor::Void => return "        Ok(())\n".to_string(),
+            TypeDescriptor::Boolean => "z()?",
+            TypeDescriptor::Byte => "b()?",
+            TypeDescriptor::Char => "c()?",
+            TypeDescriptor::Short => "s()?",
+            TypeDescriptor::Int => "i()?",
+            TypeDescriptor::Long => "j()?",
+            TypeDescriptor::Float => "f()?",
+            TypeDescriptor::Double => "d()?",
+            TypeDescriptor::Object(_) | TypeDescriptor::Array(_) => "",
+        };
+        if !getter.is_empty() {
+            return format!("        Ok(value.{})\n", getter);
+        }
+
+        let mut code = String::from(
+            "        let value = value.l()?;\n        if value.is_null() {\n            return Ok(None);\n        }\n\n",
+        );
+        let _ = match (is_string(ty), self.wrapper(ty)) {
+            (true, _) => write!(
+                code,
+                "        let value = ::ejni::JavaString::new(\n            {env},\n            ::ejni::Object::new({env}, value, ::ejni::Class::String({env})?),\n        );\n        Ok(Some(value.into_rust()?))\n",
+                env = env
+            ),
+            (false, Some(wrapper)) => write!(
+                code,
+                "        let class = ::ejni::private::class({env}, \"{class}\")?;\n        Ok(Some({wrapper}::new(\n            {env},\n            ::ejni::Object::new({env}, value, class),\n        )))\n",
+                env = env,
+                class = class_name(ty),
+                wrapper = wrapper
+            ),
+            (false, None) => write!(
+                code,
+                "        let class = ::ejni::private::class({env}, \"{class}\")?;\n        Ok(Some(::ejni::Object::new({env}, value, class)))\n",
+                env = env,
+                class = class_name(ty)
+            ),
+        };
+        code
+    }
+}
+
+fn is_string(ty: &TypeDescriptor) -> bool {
+    matches!(ty, TypeDescriptor::Object(name) if name == "java/lang/String")
+}
+
+/// The name of a reference type as passed to `FindClass`, e.g `java/lang/String` or `[I`
+fn class_name(ty: &TypeDescriptor) -> String {
+    match ty {
+        TypeDescriptor::Object(name) => name.clone(),
+        ty => ty.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ORDER: &[u8] = include_bytes!("../../test-classes/ejni/test/Order.class");
+    const NATIVE_TEST: &[u8] = include_bytes!("../../test-classes/ejni/test/NativeTest.class");
+
+    fn bindings() -> String {
+        let classes = [
+            ClassFile::parse(NATIVE_TEST).unwrap(),
+            ClassFile::parse(ORDER).unwrap(),
+        ];
+        generate(&classes).unwrap()
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!("get_url_host", snake_case("getURLHost"));
+        assert_eq!("is_paid", snake_case("isPaid"));
+        assert_eq!("value_of2", snake_case("valueOf2"));
+        assert_eq!("r#type", identifier(snake_case("type")));
+        assert_eq!("self_", identifier(snake_case("self")));
+        assert_eq!("env_", identifier(snake_case("env")));
+    }
+
+    #[test]
+    fn statics() {
+        let bindings = bindings();
+        assert!(bindings.contains(
+            "    /// Invokes `NativeTest#add(int, int)`\n    pub fn add(env: &'a ::ejni::private::jni::JNIEnv<'a>, arg0: i32, arg1: i32) -> ::ejni::Result<i32> {"
+        ));
+        assert!(bindings.contains(
+            "    pub fn add_long_long(env: &'a ::ejni::private::jni::JNIEnv<'a>, arg0: i64, arg1: i64) -> ::ejni::Result<i64> {"
+        ));
+        assert!(bindings.contains("::ejni::private::call_static_method(\n            env,\n            \"ejni/test/NativeTest\",\n            \"add\",\n            ::ejni::signature!(\"(JJ)J\"),\n            &[::ejni::private::jni::objects::JValue::Long(arg0), ::ejni::private::jni::objects::JValue::Long(arg1)],\n        )?;"));
+        assert!(bindings.contains(
+            "    pub fn size(env: &'a ::ejni::private::jni::JNIEnv<'a>, arg0: &::ejni::Object<'a>) -> ::ejni::Result<i32> {"
+        ));
+    }
+
+    #[test]
+    fn instance() {
+        let bindings = bindings();
+        assert!(bindings.contains(
+            "    pub fn greet(&self, arg0: &str) -> ::ejni::Result<Option<String>> {\n        let arg0 = ::ejni::JavaString::from_rust(self.env, arg0)?;"
+        ));
+        assert!(bindings.contains("            self.inner.inner,\n            \"ejni/test/Order\",\n            \"markPaid\",\n            ::ejni::signature!(\"(Z)V\"),\n            &[::ejni::private::jni::objects::JValue::Bool(arg0 as u8)],\n        )?;\n        Ok(())"));
+        assert!(bindings.contains("    pub fn is_paid(&self) -> ::ejni::Result<bool> {"));
+    }
+
+    #[test]
+    fn constructors() {
+        let bindings = bindings();
+        assert!(bindings.contains(
+            "    /// Invokes the constructor `NativeTest(String)`\n    pub fn create(env: &'a ::ejni::private::jni::JNIEnv<'a>, arg0: &str) -> ::ejni::Result<Self> {"
+        ));
+        assert!(bindings
+            .contains("    pub fn create(env: &'a ::ejni::private::jni::JNIEnv<'a>) -> ::ejni::Result<Self> {"));
+    }
+}
@@ -0,0 +1,325 @@
+//! Generate eJNI wrappers for Java classes from their compiled `.class` files, offline and without a JVM.
+//!
+//! For every public class a wrapper struct is generated, in the style of the abstractions of eJNI, e.g `ejni::System`.
+//! Public methods become Rust methods calling into Java through cached method IDs,
+//! static methods take the `JNIEnv`, instance methods are called on the wrapped Object.
+//! Classes are resolved like `ejni::Class::for_name`, through the ClassLoader set with `ejni::ClassLoader::set_default` if there is one.
+//!
+//! From a build script:
+//! ```ignore
+//! fn main() {
+//!     let out = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap());
+//!     ejni_bindgen::Builder::new()
+//!         .input("java/build/libs/acme.jar")
+//!         .include_class("com.acme.*")
+//!         .exclude_member("*Internal")
+//!         .generate()
+//!         .unwrap()
+//!         .write_to_file(out.join("acme.rs"))
+//!         .unwrap();
+//! }
+//! ```
+//! And include the bindings with `include!(concat!(env!("OUT_DIR"), "/acme.rs"));`.
+//! The generated code only uses the `ejni` crate, which must be a dependency of the crate including it
+
+#![warn(missing_docs)]
+
+mod class_file;
+pub use class_file::*;
+
+mod error;
+pub use error::*;
+
+mod generate;
+
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Configures which classes bindings are generated for, see the crate documentation
+#[derive(Debug, Clone, Default)]
+pub struct Builder {
+    inputs: Vec<PathBuf>,
+    include_classes: Vec<String>,
+    exclude_classes: Vec<String>,
+    include_members: Vec<String>,
+    exclude_members: Vec<String>,
+}
+
+impl Builder {
+    /// Create a Builder without inputs, including every public class and method
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a `.class` file, a jar, or a directory which is searched for class files recursively
+    pub fn input<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.inputs.push(path.into());
+        self
+    }
+
+    /// Only generate bindings for classes matching `pattern`. Patterns match the binary name of a class,
+    /// e.g `com.acme.Order` or `com.acme.Order$Line`, and `*` matches any number of characters, e.g `com.acme.*`.
+    /// Without any included patterns, all classes are included
+    pub fn include_class<S: Into<String>>(mut self, pattern: S) -> Self {
+        self.include_classes.push(pattern.into());
+        self
+    }
+
+    /// Do not generate bindings for classes matching `pattern`, even if they are included
+    pub fn exclude_class<S: Into<String>>(mut self, pattern: S) -> Self {
+        self.exclude_classes.push(pattern.into());
+        self
+    }
+
+    /// Only generate methods matching `pattern`. Patterns match the name of the Java method, e.g `get*`,
+    /// or if they contain a `.`, the binary name of the class followed by the method, e.g `com.acme.Order.getId`.
+    /// Constructors are named `<init>`. Without any included patterns, all methods are included
+    pub fn include_member<S: Into<String>>(mut self, pattern: S) -> Self {
+        self.include_members.push(pattern.into());
+        self
+    }
+
+    /// Do not generate methods matching `pattern`, even if they are included
+    pub fn exclude_member<S: Into<String>>(mut self, pattern: S) -> Self {
+        self.exclude_members.push(pattern.into());
+        self
+    }
+
+    /// Read the inputs and generate the bindings
+    pub fn generate(&self) -> BindgenResult<Bindings> {
+        let mut classes = Vec::new();
+        for input in &self.inputs {
+            read_input(input, &mut classes)?;
+        }
+
+        let mut classes = classes
+            .into_iter()
+            .filter(|class| {
+                class.is_public()
+                    && class.access_flags & ACC_MODULE == 0
+                    && !is_anonymous(&class.name)
+                    && filter(
+                        &self.include_classes,
+                        &self.exclude_classes,
+                        &class.binary_name(),
+                    )
+            })
+            .map(|mut class| {
+                let binary_name = class.binary_name();
+                class.methods.retain(|method| {
+                    let qualified = format!("{}.{}", binary_name, method.name);
+                    method.is_public()
+                        && !method.is_synthetic()
+                        && method.name != "<clinit>"
+                        && filter_member(
+                            &self.include_members,
+                            &self.exclude_members,
+                            &method.name,
+                            &qualified,
+                        )
+                });
+                class
+            })
+            .collect::<Vec<_>>();
+        classes.sort_by(|a, b| a.name.cmp(&b.name));
+        classes.dedup_by(|a, b| a.name == b.name);
+
+        Ok(Bindings {
+            code: generate::generate(&classes)?,
+        })
+    }
+}
+
+/// The generated bindings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bindings {
+    code: String,
+}
+
+impl Bindings {
+    /// Write the bindings into a file, e.g in `OUT_DIR`
+    pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> BindgenResult<()> {
+        std::fs::write(path, &self.code)?;
+        Ok(())
+    }
+}
+
+impl Display for Bindings {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.code)
+    }
+}
+
+/// Read the class files of a `.class` file, a jar or a directory
+fn read_input(path: &Path, classes: &mut Vec<ClassFile>) -> BindgenResult<()> {
+    if path.is_dir() {
+        let mut entries = std::fs::read_dir(path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        entries.sort();
+        for entry in entries {
+            if entry.is_dir() || entry.extension() == Some("class".as_ref()) {
+                read_input(&entry, classes)?;
+            }
+        }
+        return Ok(());
+    }
+
+    let is_class = path.extension() == Some("class".as_ref());
+    if is_class {
+        classes.push(ClassFile::parse(&std::fs::read(path)?)?);
+        return Ok(());
+    }
+
+    let mut jar = zip::ZipArchive::new(File::open(path)?)?;
+    for i in 0..jar.len() {
+        let mut entry = jar.by_index(i)?;
+        // Classes for other Java versions of multi-release jars are skipped, as they declare the same API
+        if !entry.name().ends_with(".class") || entry.name().starts_with("META-INF/") {
+            continue;
+        }
+
+        let mut bytes = Vec::with_capacity(entry.size() as usize);
+        entry.read_to_end(&mut bytes)?;
+        classes.push(ClassFile::parse(&bytes)?);
+    }
+    Ok(())
+}
+
+/// Returns true for anonymous and local classes, e.g `com/acme/Order$1` or `com/acme/Order$1Local`
+fn is_anonymous(name: &str) -> bool {
+    name.split('$')
+        .skip(1)
+        .any(|part| part.starts_with(|c: char| c.is_ascii_digit()))
+}
+
+fn filter(include: &[String], exclude: &[String], name: &str) -> bool {
+    let included = include.is_empty() || include.iter().any(|pattern| matches(pattern, name));
+    included && !exclude.iter().any(|pattern| matches(pattern, name))
+}
+
+fn filter_member(include: &[String], exclude: &[String], name: &str, qualified: &str) -> bool {
+    let matches_member = |pattern: &String| match pattern.contains('.') {
+        true => matches(pattern, qualified),
+        false => matches(pattern, name),
+    };
+    let included = include.is_empty() || include.iter().any(matches_member);
+    included && !exclude.iter().any(matches_member)
+}
+
+/// Match `name` against a pattern in which `*` matches any number of characters
+fn matches(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let mut rest = match name.strip_prefix(first) {
+        Some(rest) => rest,
+        None => return false,
+    };
+
+    let parts = parts.collect::<Vec<_>>();
+    let (last, middle) = match parts.split_last() {
+        Some(split) => split,
+        // The pattern does not contain a `*`
+        None => return rest.is_empty(),
+    };
+    for part in middle {
+        rest = match rest.find(part) {
+            Some(index) => &rest[index + part.len()..],
+            None => return false,
+        };
+    }
+    rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::Write;
+
+    fn test_classes() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../test-classes")
+    }
+
+    #[test]
+    fn patterns() {
+        assert!(matches("com.acme.*", "com.acme.Order"));
+        assert!(matches("com.acme.Order", "com.acme.Order"));
+        assert!(!matches("com.acme.Order", "com.acme.OrderLine"));
+        assert!(matches("*Line", "com.acme.OrderLine"));
+        assert!(matches("get*Id", "getOrderId"));
+        assert!(!matches("get*Id", "getOrderIdentifier"));
+        assert!(matches("*", ""));
+    }
+
+    #[test]
+    fn anonymous() {
+        assert!(is_anonymous("com/acme/Order$1"));
+        assert!(is_anonymous("com/acme/Order$1Local"));
+        assert!(!is_anonymous("com/acme/Order$Line"));
+        assert!(!is_anonymous("com/acme/Order"));
+    }
+
+    #[test]
+    fn filters() {
+        let bindings = Builder::new()
+            .input(test_classes())
+            .include_class("ejni.test.*")
+            .exclude_class("ejni.test.Plugin")
+            .exclude_member("ejni.test.NativeTest.add")
+            .exclude_member("orDefault")
+            .generate()
+            .unwrap()
+            .to_string();
+        assert!(bindings.contains("pub struct NativeTest<'a>"));
+        assert!(bindings.contains("pub struct Order<'a>"));
+        assert!(!bindings.contains("pub struct Plugin<'a>"));
+        assert!(bindings.contains("pub fn greet("));
+        assert!(!bindings.contains("pub fn add("));
+        assert!(!bindings.contains("pub fn or_default("));
+
+        let bindings = Builder::new()
+            .input(test_classes())
+            .include_member("get*")
+            .generate()
+            .unwrap()
+            .to_string();
+        assert!(bindings.contains("pub fn get_prefix(&self)"));
+        assert!(!bindings.contains("pub fn greet("));
+    }
+
+    #[test]
+    fn jar() {
+        let path = std::env::temp_dir().join(format!("ejni-bindgen-{}.jar", std::process::id()));
+        {
+            let mut jar = zip::ZipWriter::new(File::create(&path).unwrap());
+            let options = zip::write::FileOptions::default();
+            jar.start_file("META-INF/MANIFEST.MF", options).unwrap();
+            jar.write_all(b"Manifest-Version: 1.0\n").unwrap();
+            jar.start_file("ejni/test/Plugin.class", options).unwrap();
+            jar.write_all(&std::fs::read(test_classes().join("ejni/test/Plugin.class")).unwrap())
+                .unwrap();
+            jar.finish().unwrap();
+        }
+
+        let bindings = Builder::new().input(&path).generate();
+        std::fs::remove_file(&path).unwrap();
+        let bindings = bindings.unwrap().to_string();
+        assert!(bindings.contains("/// Wrapper around `ejni.test.Plugin`"));
+        assert!(bindings
+            .contains("pub fn name(env: &'a ::ejni::private::jni::JNIEnv<'a>) -> ::ejni::Result<Option<String>>"));
+    }
+
+    /// The bindings in `test-classes/bindings.rs` are compiled and called by the eJNI tests
+    #[test]
+    fn checked_in() {
+        let bindings = Builder::new()
+            .input(test_classes().join("ejni/test/Order.class"))
+            .input(test_classes().join("ejni/test/Plugin.class"))
+            .generate()
+            .unwrap()
+            .to_string();
+        assert_eq!(include_str!("../../test-classes/bindings.rs"), bindings);
+    }
+}
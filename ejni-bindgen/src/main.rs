@@ -0,0 +1,69 @@
+use ejni_bindgen::Builder;
+use std::process::exit;
+
+const USAGE: &str = "Usage: ejni-bindgen [OPTIONS] INPUT...
+
+Generate eJNI wrappers for the classes in INPUT, which is a .class file, a jar or a directory
+
+Options:
+    --include-class PATTERN     Only generate bindings for classes matching PATTERN, e.g `com.acme.*`
+    --exclude-class PATTERN     Do not generate bindings for classes matching PATTERN
+    --include-member PATTERN    Only generate methods matching PATTERN, e.g `get*` or `com.acme.Order.getId`
+    --exclude-member PATTERN    Do not generate methods matching PATTERN
+    -o, --output FILE           Write the bindings to FILE instead of stdout
+    -h, --help                  Print this help";
+
+fn main() {
+    let mut builder = Builder::new();
+    let mut output = None;
+    let mut has_input = false;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || match args.next() {
+            Some(value) => value,
+            None => fail(&format!("Missing the value of {}", arg)),
+        };
+        builder = match arg.as_str() {
+            "--include-class" => builder.include_class(value()),
+            "--exclude-class" => builder.exclude_class(value()),
+            "--include-member" => builder.include_member(value()),
+            "--exclude-member" => builder.exclude_member(value()),
+            "-o" | "--output" => {
+                output = Some(value());
+                builder
+            }
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            option if option.starts_with('-') => fail(&format!("Unknown option {}", option)),
+            input => {
+                has_input = true;
+                builder.input(input)
+            }
+        };
+    }
+
+    if !has_input {
+        fail("No inputs");
+    }
+
+    let bindings = match builder.generate() {
+        Ok(bindings) => bindings,
+        Err(e) => fail(&e.to_string()),
+    };
+    match output {
+        Some(output) => {
+            if let Err(e) = bindings.write_to_file(&output) {
+                fail(&e.to_string());
+            }
+        }
+        None => print!("{}", bindings),
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("error: {}\n\n{}", message, USAGE);
+    exit(1);
+}
@@ -0,0 +1,392 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{
+    Data, DeriveInput, Field, Fields, GenericParam, Generics, Ident, Lifetime, LifetimeParam,
+    LitStr, Type,
+};
+
+/// The arguments of `#[ejni(...)]` on the struct
+#[derive(Default)]
+struct ContainerArgs {
+    class: Option<LitStr>,
+    record: bool,
+}
+
+impl ContainerArgs {
+    fn parse(input: &DeriveInput) -> syn::Result<Self> {
+        let mut args = Self::default();
+        for attr in input.attrs.iter().filter(|a| a.path().is_ident("ejni")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("class") {
+                    args.class = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("record") {
+                    args.record = true;
+                } else {
+                    return Err(meta.error("expected `class` or `record`"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(args)
+    }
+
+    fn class(&self) -> syn::Result<&LitStr> {
+        self.class.as_ref().ok_or_else(|| {
+            syn::Error::new(
+                Span::call_site(),
+                "the Java class is required, e.g `#[ejni(class = \"com.example.Foo\")]`",
+            )
+        })
+    }
+}
+
+/// The arguments of `#[ejni(...)]` on a field
+#[derive(Default)]
+struct FieldArgs {
+    field: Option<LitStr>,
+    getter: Option<LitStr>,
+    setter: Option<LitStr>,
+}
+
+impl FieldArgs {
+    fn parse(field: &Field) -> syn::Result<Self> {
+        let mut args = Self::default();
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("ejni")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("field") {
+                    args.field = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("getter") {
+                    args.getter = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("setter") {
+                    args.setter = Some(meta.value()?.parse()?);
+                } else {
+                    return Err(meta.error("expected `field`, `getter` or `setter`"));
+                }
+                Ok(())
+            })?;
+        }
+
+        if let (Some(_), Some(getter)) = (&args.field, &args.getter) {
+            return Err(syn::Error::new(
+                getter.span(),
+                "a field is either accessed directly or through a getter, not both",
+            ));
+        }
+        Ok(args)
+    }
+}
+
+/// How a member of the Java class is accessed
+enum Access {
+    /// Read and write the Java field directly
+    Field(String),
+    /// Call a method, a getter when reading or a setter when writing
+    Method(String),
+}
+
+/// A field of the derived struct
+struct Member<'f> {
+    ident: &'f Ident,
+    ty: &'f Type,
+    /// The name of the Java field, or of the record component
+    name: String,
+    args: FieldArgs,
+}
+
+impl<'f> Member<'f> {
+    /// How the member is read. Records are read through their accessors
+    fn read(&self, record: bool) -> Access {
+        match (&self.args.getter, record) {
+            (Some(getter), _) => Access::Method(getter.value()),
+            (None, true) => Access::Method(self.name.clone()),
+            (None, false) => Access::Field(self.name.clone()),
+        }
+    }
+
+    /// How the member of a POJO is written
+    fn write(&self) -> syn::Result<Access> {
+        if let Some(setter) = &self.args.setter {
+            return Ok(Access::Method(setter.value()));
+        }
+        let getter = match &self.args.getter {
+            Some(getter) => getter,
+            None => return Ok(Access::Field(self.name.clone())),
+        };
+
+        let value = getter.value();
+        let property = value
+            .strip_prefix("get")
+            .or_else(|| value.strip_prefix("is"))
+            .filter(|property| property.starts_with(char::is_uppercase));
+        match property {
+            Some(property) => Ok(Access::Method(format!("set{}", property))),
+            None => Err(syn::Error::new(
+                getter.span(),
+                "the setter can not be derived from this getter, provide it with `setter = \"...\"`",
+            )),
+        }
+    }
+}
+
+/// The struct a trait is derived for
+struct Derived<'i> {
+    input: &'i DeriveInput,
+    args: ContainerArgs,
+    members: Vec<Member<'i>>,
+}
+
+impl<'i> Derived<'i> {
+    fn parse(input: &'i DeriveInput, with_members: bool) -> syn::Result<Self> {
+        let args = ContainerArgs::parse(input)?;
+        if let Some(param) = input.generics.type_params().next() {
+            return Err(syn::Error::new(
+                param.span(),
+                "derived Java types can not have type parameters",
+            ));
+        }
+        if !with_members {
+            return Ok(Self {
+                input,
+                args,
+                members: Vec::new(),
+            });
+        }
+
+        let fields = match &input.data {
+            Data::Struct(data) => match &data.fields {
+                Fields::Named(fields) => &fields.named,
+                Fields::Unit => {
+                    return Ok(Self {
+                        input,
+                        args,
+                        members: Vec::new(),
+                    })
+                }
+                Fields::Unnamed(fields) => {
+                    return Err(syn::Error::new(
+                        fields.span(),
+                        "only structs with named fields can be derived",
+                    ))
+                }
+            },
+            _ => {
+                return Err(syn::Error::new(
+                    input.ident.span(),
+                    "only structs with named fields can be derived",
+                ))
+            }
+        };
+
+        let record = args.record;
+        let members = fields
+            .iter()
+            .map(|field| {
+                let ident = field.ident.as_ref().unwrap();
+                let args = FieldArgs::parse(field)?;
+                if args.setter.is_some() && record {
+                    return Err(syn::Error::new(
+                        args.setter.span(),
+                        "records are immutable, they are created through their canonical constructor",
+                    ));
+                }
+                let name = args
+                    .field
+                    .as_ref()
+                    .map(LitStr::value)
+                    .unwrap_or_else(|| camel_case(&ident.to_string()));
+                Ok(Member {
+                    ident,
+                    ty: &field.ty,
+                    name,
+                    args,
+                })
+            })
+            .collect::<syn::Result<Vec<_>>>()?;
+        Ok(Self {
+            input,
+            args,
+            members,
+        })
+    }
+
+    /// The lifetime of the JNIEnv in the generated impl, the first lifetime of the struct if it has one
+    fn lifetime(&self) -> Lifetime {
+        match self.input.generics.lifetimes().next() {
+            Some(param) => param.lifetime.clone(),
+            None => Lifetime::new("'__ejni", Span::call_site()),
+        }
+    }
+
+    /// The generics of the generated impl, which introduce the lifetime of the JNIEnv if the struct does not have one
+    fn impl_generics(&self) -> Generics {
+        let mut generics = self.input.generics.clone();
+        if generics.lifetimes().next().is_none() {
+            generics.params.insert(
+                0,
+                GenericParam::Lifetime(LifetimeParam::new(self.lifetime())),
+            );
+        }
+        generics
+    }
+
+    /// Wrap the body of a trait impl in the impl header
+    fn implement(&self, trait_name: &str, body: TokenStream) -> TokenStream {
+        let ident = &self.input.ident;
+        let lifetime = self.lifetime();
+        let trait_ident = Ident::new(trait_name, Span::call_site());
+        let impl_generics = self.impl_generics();
+        let (impl_generics, _, _) = impl_generics.split_for_impl();
+        let (_, ty_generics, where_clause) = self.input.generics.split_for_impl();
+
+        quote! {
+            impl #impl_generics ::ejni::#trait_ident<#lifetime> for #ident #ty_generics #where_clause {
+                #body
+            }
+        }
+    }
+}
+
+/// Convert a Rust field name into the Java naming convention, e.g `order_id` into `orderId`
+fn camel_case(name: &str) -> String {
+    let name = name.trim_start_matches("r#");
+    let mut camel = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        match c {
+            '_' if !camel.is_empty() => upper = true,
+            c if upper => {
+                camel.extend(c.to_uppercase());
+                upper = false;
+            }
+            c => camel.push(c),
+        }
+    }
+    camel
+}
+
+/// Returns the argument of `Option<T>`, or None if `ty` is not an Option
+fn option_argument(ty: &Type) -> Option<&Type> {
+    let segment = match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != "Option" {
+        return None;
+    }
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+            syn::GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
+pub fn java_type(input: TokenStream) -> syn::Result<TokenStream> {
+    let input: DeriveInput = syn::parse2(input)?;
+    let derived = Derived::parse(&input, false)?;
+    let class = derived.args.class()?;
+    let lifetime = derived.lifetime();
+
+    Ok(derived.implement(
+        "JavaType",
+        quote! {
+            fn class(env: &#lifetime ::ejni::private::jni::JNIEnv<#lifetime>) -> ::ejni::Result<::ejni::Class<#lifetime>> {
+                ::ejni::private::class(env, #class)
+            }
+        },
+    ))
+}
+
+pub fn into_java(input: TokenStream) -> syn::Result<TokenStream> {
+    let input: DeriveInput = syn::parse2(input)?;
+    let derived = Derived::parse(&input, true)?;
+    let lifetime = derived.lifetime();
+
+    let body = if derived.args.record {
+        let args = derived.members.iter().map(|member| {
+            let ident = member.ident;
+            quote_spanned! {member.ty.span()=>
+                ::ejni::Argument::new(env, self.#ident)?
+            }
+        });
+        quote! {
+            ::ejni::private::new_object(env, class, ::std::vec![#(#args),*])
+        }
+    } else {
+        let writes = derived
+            .members
+            .iter()
+            .map(|member| {
+                let ident = member.ident;
+                Ok(match member.write()? {
+                    Access::Field(name) => quote_spanned! {member.ty.span()=>
+                        ::ejni::private::set_field(&object, #name, self.#ident)?;
+                    },
+                    Access::Method(name) => quote_spanned! {member.ty.span()=>
+                        ::ejni::private::call_setter(&object, #name, self.#ident)?;
+                    },
+                })
+            })
+            .collect::<syn::Result<Vec<_>>>()?;
+        quote! {
+            let object = ::ejni::private::new_object(env, class, ::std::vec::Vec::new())?;
+            #(#writes)*
+            Ok(object)
+        }
+    };
+
+    Ok(derived.implement(
+        "IntoJava",
+        quote! {
+            const OWNED: bool = true;
+
+            fn into_java(self, env: &#lifetime ::ejni::private::jni::JNIEnv<#lifetime>) -> ::ejni::Result<::ejni::Object<#lifetime>> {
+                let class = <Self as ::ejni::JavaType<#lifetime>>::class(env)?;
+                #body
+            }
+        },
+    ))
+}
+
+pub fn from_java(input: TokenStream) -> syn::Result<TokenStream> {
+    let input: DeriveInput = syn::parse2(input)?;
+    let derived = Derived::parse(&input, true)?;
+    let lifetime = derived.lifetime();
+
+    let reads = derived.members.iter().map(|member| {
+        let ident = member.ident;
+        let (read, name) = match member.read(derived.args.record) {
+            Access::Field(name) => (quote!(::ejni::private::get_field), name),
+            Access::Method(name) => (quote!(::ejni::private::call_getter), name),
+        };
+        let value = match option_argument(member.ty) {
+            Some(inner) => quote_spanned! {member.ty.span()=>
+                #read::<#inner>(&object, #name)?
+            },
+            None => {
+                let ty = member.ty;
+                quote_spanned! {member.ty.span()=>
+                    ::ejni::private::non_null(#read::<#ty>(&object, #name)?, &class, #name)?
+                }
+            }
+        };
+        quote!(#ident: #value)
+    });
+
+    let construct = match &input.data {
+        Data::Struct(data) if matches!(data.fields, Fields::Unit) => quote!(Self),
+        _ => quote!(Self { #(#reads),* }),
+    };
+    Ok(derived.implement(
+        "FromJava",
+        quote! {
+            fn from_java(object: ::ejni::Object<#lifetime>) -> ::ejni::Result<Self> {
+                let class = <Self as ::ejni::JavaType<#lifetime>>::class(object.env)?;
+                ::ejni::private::check_class(&object, class.clone())?;
+                Ok(#construct)
+            }
+        },
+    ))
+}
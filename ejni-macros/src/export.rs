@@ -0,0 +1,321 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{FnArg, GenericArgument, Ident, ItemFn, LitStr, Pat, PathArguments, ReturnType, Type};
+
+/// The arguments of `#[export(...)]`
+#[derive(Default)]
+struct Args {
+    class: Option<LitStr>,
+    name: Option<LitStr>,
+    signature: Option<LitStr>,
+    overloaded: bool,
+}
+
+impl Args {
+    fn parse(attr: TokenStream) -> syn::Result<Self> {
+        let mut args = Self::default();
+        let parser = syn::meta::parser(|meta| {
+            if meta.path.is_ident("class") {
+                args.class = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("name") {
+                args.name = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("signature") {
+                args.signature = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("overloaded") {
+                args.overloaded = true;
+            } else {
+                return Err(meta.error("expected `class`, `name`, `signature` or `overloaded`"));
+            }
+            Ok(())
+        });
+        syn::parse::Parser::parse2(parser, attr)?;
+        Ok(args)
+    }
+}
+
+/// A Java primitive, passed to and from native functions as its JNI type
+struct Primitive {
+    /// The JNI type, e.g `jint`
+    raw: &'static str,
+    /// The JNI type descriptor, e.g `I`
+    descriptor: char,
+}
+
+/// Map a Rust primitive onto its Java primitive. Returns None for other types
+fn primitive(ty: &Type) -> Option<Primitive> {
+    let (raw, descriptor) = match last_ident(ty)?.to_string().as_str() {
+        "bool" => ("jboolean", 'Z'),
+        "u8" | "i8" => ("jbyte", 'B'),
+        "u16" => ("jchar", 'C'),
+        "i16" => ("jshort", 'S'),
+        "i32" => ("jint", 'I'),
+        "i64" => ("jlong", 'J'),
+        "f32" => ("jfloat", 'F'),
+        "f64" => ("jdouble", 'D'),
+        _ => return None,
+    };
+    Some(Primitive { raw, descriptor })
+}
+
+/// The last identifier of a path type, e.g `List` for `ejni::List<'a, String>`
+fn last_ident(ty: &Type) -> Option<&Ident> {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last().map(|s| &s.ident),
+        _ => None,
+    }
+}
+
+/// The first generic type argument of a path type, e.g `String` for `Option<String>`
+fn type_argument(ty: &Type) -> Option<&Type> {
+    let segment = match ty {
+        Type::Path(path) => path.path.segments.last()?,
+        _ => return None,
+    };
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
+/// Returns the argument of `Option<T>`, or None if `ty` is not an Option
+fn option_argument(ty: &Type) -> Option<&Type> {
+    match last_ident(ty) {
+        Some(ident) if ident == "Option" => type_argument(ty),
+        _ => None,
+    }
+}
+
+/// The JNI type descriptor of a parameter, used for the long symbol of overloaded methods
+fn descriptor(ty: &Type) -> syn::Result<String> {
+    if let Some(primitive) = primitive(ty) {
+        return Ok(primitive.descriptor.to_string());
+    }
+    if let Some(inner) = option_argument(ty) {
+        if primitive(inner).is_none() {
+            return descriptor(inner);
+        }
+    }
+
+    let descriptor = match last_ident(ty).map(|ident| ident.to_string()).as_deref() {
+        Some("String") | Some("JavaString") => "Ljava/lang/String;".to_string(),
+        Some("Object") | Some("GlobalObject") => "Ljava/lang/Object;".to_string(),
+        Some("List") => "Ljava/util/List;".to_string(),
+        Some("Map") => "Ljava/util/Map;".to_string(),
+        Some("Set") => "Ljava/util/Set;".to_string(),
+        Some("ByteBuffer") => "Ljava/nio/ByteBuffer;".to_string(),
+        Some("JavaArray") => match type_argument(ty).and_then(primitive) {
+            Some(primitive) => format!("[{}", primitive.descriptor),
+            None => return Err(unsupported(ty)),
+        },
+        _ => return Err(unsupported(ty)),
+    };
+    Ok(descriptor)
+}
+
+fn unsupported(ty: &Type) -> syn::Error {
+    syn::Error::new(
+        ty.span(),
+        "the Java type of this parameter can not be derived, provide the method signature with `signature = \"...\"`",
+    )
+}
+
+/// A parameter of the exported function
+struct Param {
+    /// The name of the parameter in the generated function
+    raw_name: Ident,
+    /// The JNI type of the parameter in the generated function
+    raw_ty: TokenStream,
+    /// Converts `raw_name` into the Rust type
+    conversion: TokenStream,
+}
+
+impl Param {
+    fn new(raw_name: Ident, ty: &Type) -> Self {
+        if let Some(primitive) = primitive(ty) {
+            let raw = format_ident!("{}", primitive.raw);
+            let conversion = match last_ident(ty).unwrap().to_string().as_str() {
+                "bool" => quote!(#raw_name != 0),
+                "u8" => quote!(#raw_name as u8),
+                _ => quote!(#raw_name),
+            };
+            return Self {
+                raw_ty: quote!(::ejni::private::jni::sys::#raw),
+                raw_name,
+                conversion,
+            };
+        }
+
+        let conversion = match option_argument(ty) {
+            Some(_) => quote_spanned! {ty.span()=>
+                ::ejni::private::from_nullable_arg(__env, #raw_name).map_err(::ejni::private::thrown)?
+            },
+            None => quote_spanned! {ty.span()=>
+                ::ejni::private::from_arg(__env, #raw_name).map_err(::ejni::private::thrown)?
+            },
+        };
+        Self {
+            raw_ty: quote!(::ejni::private::jni::sys::jobject),
+            raw_name,
+            conversion,
+        }
+    }
+}
+
+pub fn expand(attr: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    let args = Args::parse(attr)?;
+    let function: ItemFn = syn::parse2(item)?;
+    let sig = &function.sig;
+
+    let class = args.class.as_ref().ok_or_else(|| {
+        syn::Error::new(
+            Span::call_site(),
+            "the Java class is required, e.g `#[export(class = \"com.example.Foo\")]`",
+        )
+    })?;
+    if let Some(param) = sig.generics.type_params().next() {
+        return Err(syn::Error::new(
+            param.span(),
+            "exported functions can not have type parameters",
+        ));
+    }
+    if let Some(asyncness) = &sig.asyncness {
+        return Err(syn::Error::new(
+            asyncness.span(),
+            "exported functions can not be async",
+        ));
+    }
+
+    let mut inputs = sig.inputs.iter();
+    match inputs.next() {
+        Some(FnArg::Typed(_)) => {}
+        Some(FnArg::Receiver(receiver)) => {
+            return Err(syn::Error::new(
+                receiver.span(),
+                "exported functions can not take `self`",
+            ))
+        }
+        None => {
+            return Err(syn::Error::new(
+                sig.span(),
+                "the first parameter of an exported function must be the `&JNIEnv`",
+            ))
+        }
+    }
+
+    let mut receiver = None;
+    let mut params = Vec::new();
+    let mut descriptors = String::new();
+    for (i, input) in inputs.enumerate() {
+        let input = match input {
+            FnArg::Typed(input) => input,
+            FnArg::Receiver(receiver) => {
+                return Err(syn::Error::new(
+                    receiver.span(),
+                    "exported functions can not take `self`",
+                ))
+            }
+        };
+
+        let is_this = matches!(&*input.pat, Pat::Ident(pat) if pat.ident == "this");
+        if i == 0 && is_this {
+            if primitive(&input.ty).is_some() {
+                return Err(syn::Error::new(
+                    input.ty.span(),
+                    "`this` must be an Object type",
+                ));
+            }
+            receiver = Some(Param::new(format_ident!("__this"), &input.ty));
+            continue;
+        }
+
+        if args.overloaded && args.signature.is_none() {
+            descriptors.push_str(&descriptor(&input.ty)?);
+        }
+        params.push(Param::new(format_ident!("__arg{}", i), &input.ty));
+    }
+
+    let method = args
+        .name
+        .as_ref()
+        .map(LitStr::value)
+        .unwrap_or_else(|| sig.ident.to_string().trim_start_matches("r#").to_string());
+    let symbol = match (&args.signature, args.overloaded) {
+        (Some(signature), _) => ejni_mangle::long_name(&class.value(), &method, &signature.value()),
+        (None, true) => ejni_mangle::long_name(&class.value(), &method, &descriptors),
+        (None, false) => ejni_mangle::short_name(&class.value(), &method),
+    };
+    let symbol = Ident::new(&symbol, sig.ident.span());
+
+    let (ret_ty, fallible) = match &sig.output {
+        ReturnType::Default => (None, false),
+        ReturnType::Type(_, ty) => match last_ident(ty) {
+            Some(ident) if ident == "Result" => (type_argument(ty), true),
+            _ => (Some(ty.as_ref()), false),
+        },
+    };
+    let ret_ty = ret_ty.filter(|ty| !matches!(ty, Type::Tuple(tuple) if tuple.elems.is_empty()));
+    let (raw_ret, ret_conversion) = match ret_ty {
+        None => (quote!(()), quote!(())),
+        Some(ty) => match primitive(ty) {
+            Some(primitive) => {
+                let raw = format_ident!("{}", primitive.raw);
+                let conversion = match last_ident(ty).unwrap().to_string().as_str() {
+                    "bool" => quote!(__ret as ::ejni::private::jni::sys::jboolean),
+                    "u8" => quote!(__ret as ::ejni::private::jni::sys::jbyte),
+                    _ => quote!(__ret),
+                };
+                (quote!(::ejni::private::jni::sys::#raw), conversion)
+            }
+            None => (
+                quote!(::ejni::private::jni::sys::jobject),
+                quote_spanned! {ty.span()=>
+                    ::ejni::private::into_return(__env, __ret).map_err(::ejni::private::thrown)?
+                },
+            ),
+        },
+    };
+
+    let ident = &sig.ident;
+    let this_name = format_ident!("__this");
+    let receiver_arg = receiver.as_ref().map(|receiver| {
+        let conversion = &receiver.conversion;
+        quote!(#conversion,)
+    });
+    let raw_names: Vec<_> = params.iter().map(|param| &param.raw_name).collect();
+    let raw_tys: Vec<_> = params.iter().map(|param| &param.raw_ty).collect();
+    let conversions: Vec<_> = params.iter().map(|param| &param.conversion).collect();
+    let call = if fallible {
+        quote!(#ident(__env, #receiver_arg #(#conversions),*).map_err(::ejni::private::thrown)?)
+    } else {
+        quote!(#ident(__env, #receiver_arg #(#conversions),*))
+    };
+
+    Ok(quote! {
+        #function
+
+        #[no_mangle]
+        #[doc(hidden)]
+        #[allow(non_snake_case, clippy::unnecessary_cast, clippy::not_unsafe_ptr_arg_deref)]
+        pub extern "system" fn #symbol<'local>(
+            __env: ::ejni::private::jni::JNIEnv<'local>,
+            #this_name: ::ejni::private::jni::sys::jobject,
+            #(#raw_names: #raw_tys),*
+        ) -> #raw_ret {
+            #[allow(unused_variables)]
+            fn __body<'a>(
+                __env: &'a ::ejni::private::jni::JNIEnv<'a>,
+                #this_name: ::ejni::private::jni::sys::jobject,
+                #(#raw_names: #raw_tys),*
+            ) -> ::std::result::Result<#raw_ret, ::ejni::private::Thrown<'a>> {
+                let __ret = #call;
+                Ok(#ret_conversion)
+            }
+
+            ::ejni::throw_on_err(&__env, || __body(&__env, #this_name, #(#raw_names),*))
+        }
+    })
+}
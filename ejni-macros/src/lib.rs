@@ -0,0 +1,95 @@
+//! Procedural macros for eJNI. These are re-exported by the `ejni` crate, use them through it, e.g `#[ejni::export(class = "com.example.Foo")]`
+
+#![warn(missing_docs)]
+
+mod derive;
+mod export;
+
+use proc_macro::TokenStream;
+
+/// Export a Rust function as the implementation of a Java `native` method.
+///
+/// The function is kept as is, and an `extern "system"` function with the mangled JNI symbol is generated next to it.
+/// The first parameter of the function receives the `&JNIEnv`. If the second parameter is named `this`,
+/// it receives the Object the method was invoked on, or the Class for static methods. The other parameters are the Java parameters.
+///
+/// Parameters and the return value are converted as follows:
+/// - `bool`, `u8`, `i8`, `u16`, `i16`, `i32`, `i64`, `f32` and `f64` are passed as their Java primitive
+/// - `Option<T>` is None for `null`, other types are converted with `FromJava` and `IntoJava` after checking the Class of the Object
+/// - A returned `Result` is unwrapped, its error is thrown into Java through `ThrowableError`
+///
+/// Attributes:
+/// - `class = "com.example.Foo"`: the Class declaring the native method. Required
+/// - `name = "doSomething"`: the name of the Java method. Defaults to the name of the Rust function
+/// - `overloaded`: use the long symbol, which includes the parameter types. They are derived from the Rust types of the parameters
+/// - `signature = "(ILjava/lang/String;)V"`: use the long symbol, with the parameter types of this signature
+///
+/// ```ignore
+/// #[ejni::export(class = "com.example.Foo", name = "greet")]
+/// fn greet(env: &JNIEnv<'_>, name: String) -> ejni::Result<String> {
+///     Ok(format!("Hello {}", name))
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn export(attr: TokenStream, item: TokenStream) -> TokenStream {
+    export::expand(attr.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implement `JavaType` for a struct mapped onto a Java class, given by `#[ejni(class = "com.example.Foo")]`.
+/// The Class is looked up with `Class::for_name`, so classes of the default ClassLoader can be used.
+///
+/// This is derived separately from `IntoJava` and `FromJava`, as both require it
+#[proc_macro_derive(JavaType, attributes(ejni))]
+pub fn derive_java_type(input: TokenStream) -> TokenStream {
+    derive::java_type(input.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implement `IntoJava` for a struct mapped onto a Java class, creating an instance and writing each field of the struct into it.
+///
+/// POJOs are created through their no-arg constructor. Each field is then written into the Java field of the same name in camelCase,
+/// or through a setter. Java records, marked with `#[ejni(record)]`, are created through their canonical constructor,
+/// which takes the fields of the struct in the order they are declared.
+///
+/// Field attributes:
+/// - `field = "orderId"`: the name of the Java field, or of the record component
+/// - `getter = "getOrderId"`: access the value through a getter. It is written through the matching setter, e.g `setOrderId`
+/// - `setter = "updateOrderId"`: write the value through this setter
+///
+/// The fields must implement `IntoJava`, and their `JavaType::descriptor` must match the parameter types of setters and record constructors.
+/// Requires `JavaType` to be derived as well
+///
+/// ```ignore
+/// #[derive(JavaType, IntoJava, FromJava)]
+/// #[ejni(class = "com.acme.Order")]
+/// struct Order {
+///     #[ejni(field = "orderId")]
+///     id: i64,
+///     #[ejni(getter = "getCustomer")]
+///     customer: String,
+///     note: Option<String>,
+/// }
+/// ```
+#[proc_macro_derive(IntoJava, attributes(ejni))]
+pub fn derive_into_java(input: TokenStream) -> TokenStream {
+    derive::into_java(input.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implement `FromJava` for a struct mapped onto a Java class, reading each field of the struct from an instance.
+///
+/// Fields are read from the Java field of the same name in camelCase, or through a getter. The components of Java records,
+/// marked with `#[ejni(record)]`, are read through their accessor methods. Takes the same attributes as [IntoJava](derive@IntoJava).
+///
+/// `null` values are read into `Option` fields as None. For other fields they result in an `ejni::Error::NullMember`, thrown into Java as a `NullPointerException`.
+/// Requires `JavaType` to be derived as well
+#[proc_macro_derive(FromJava, attributes(ejni))]
+pub fn derive_from_java(input: TokenStream) -> TokenStream {
+    derive::from_java(input.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,110 @@
+//! JNI native method name mangling.
+//!
+//! The JVM links a `native` method to the exported symbol `Java_<class>_<method>`, or `Java_<class>_<method>__<parameters>`
+//! for overloaded methods, with the names escaped as described by the
+//! [JNI specification](https://docs.oracle.com/javase/8/docs/technotes/guides/jni/spec/design.html#resolving_native_method_names).
+//! This crate has no dependencies, so it can be used from build scripts as well as from `ejni`'s `export` macro.
+
+#![warn(missing_docs)]
+
+/// Escape a class name, method name or parameter descriptors for use in a native method symbol.
+///
+/// | Character             | Escape    |
+/// |-----------------------|-----------|
+/// | `/` or `.`            | `_`       |
+/// | `_`                   | `_1`      |
+/// | `;`                   | `_2`      |
+/// | `[`                   | `_3`      |
+/// | Not ASCII alphanumeric | `_0xxxx`, with the UTF-16 code unit in lowercase hex |
+pub fn escape(name: &str) -> String {
+    let mut escaped = String::with_capacity(name.len());
+    for c in name.chars() {
+        match c {
+            '/' | '.' => escaped.push('_'),
+            '_' => escaped.push_str("_1"),
+            ';' => escaped.push_str("_2"),
+            '[' => escaped.push_str("_3"),
+            c if c.is_ascii_alphanumeric() => escaped.push(c),
+            c => {
+                let mut units = [0; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    escaped.push_str(&format!("_0{:04x}", unit));
+                }
+            }
+        }
+    }
+    escaped
+}
+
+/// The short symbol of a native method, e.g `Java_com_example_Foo_bar` for `com.example.Foo#bar`.
+/// `class` may be in the format `com.example.Foo` or `com/example/Foo`. Nested classes are separated with `$`, e.g `com.example.Foo$Bar`
+pub fn short_name(class: &str, method: &str) -> String {
+    format!("Java_{}_{}", escape(class), escape(method))
+}
+
+/// The long symbol of a native method, which includes the parameter types to distinguish overloads,
+/// e.g `Java_com_example_Foo_bar__ILjava_lang_String_2` for `com.example.Foo#bar(int, String)`.
+///
+/// `signature` is either a method signature, e.g `(ILjava/lang/String;)V`, or only the parameter descriptors, e.g `ILjava/lang/String;`
+pub fn long_name(class: &str, method: &str, signature: &str) -> String {
+    format!(
+        "{}__{}",
+        short_name(class, method),
+        escape(parameters(signature))
+    )
+}
+
+/// Get the parameter descriptors of a method signature. Returns `signature` itself if it does not start with `(`
+fn parameters(signature: &str) -> &str {
+    match signature.strip_prefix('(') {
+        Some(rest) => rest.split(')').next().unwrap_or(rest),
+        None => signature,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn escape_special() {
+        assert_eq!("my_1method", escape("my_method"));
+        assert_eq!("_3Ljava_lang_String_2", escape("[Ljava/lang/String;"));
+        assert_eq!("Foo_00024Bar", escape("Foo$Bar"));
+        assert_eq!("caf_000e9", escape("café"));
+        // Outside of the Basic Multilingual Plane, both UTF-16 surrogates are escaped
+        assert_eq!("_0d83d_0de00", escape("😀"));
+    }
+
+    #[test]
+    fn short() {
+        assert_eq!(
+            "Java_com_example_Foo_bar",
+            short_name("com.example.Foo", "bar")
+        );
+        assert_eq!(
+            "Java_com_example_Foo_bar",
+            short_name("com/example/Foo", "bar")
+        );
+        assert_eq!(
+            "Java_com_example_my_1lib_Foo_00024Inner_do_1it",
+            short_name("com.example.my_lib.Foo$Inner", "do_it")
+        );
+    }
+
+    #[test]
+    fn long() {
+        assert_eq!(
+            "Java_com_example_Foo_bar__Ljava_lang_String_2",
+            long_name("com.example.Foo", "bar", "(Ljava/lang/String;)V")
+        );
+        assert_eq!(
+            "Java_com_example_Foo_bar__I_3_3J",
+            long_name("com.example.Foo", "bar", "I[[J")
+        );
+        assert_eq!(
+            "Java_com_example_Foo_bar__",
+            long_name("com.example.Foo", "bar", "()I")
+        );
+    }
+}
@@ -0,0 +1,84 @@
+package ejni;
+
+import java.lang.ref.Cleaner;
+import java.lang.ref.Reference;
+import java.lang.reflect.InvocationHandler;
+import java.lang.reflect.InvocationTargetException;
+import java.lang.reflect.Method;
+import java.lang.reflect.Proxy;
+
+/**
+ * Invocation handler of the proxies created by eJNI's {@code Closure}.
+ * The abstract method of the functional interface calls a boxed Rust closure,
+ * which is dropped once the proxy is no longer reachable.
+ *
+ * This class is defined at runtime by eJNI, which also registers its native methods.
+ * Compile with {@code javac --release 9 java/ejni/RustClosure.java}
+ */
+final class RustClosure implements InvocationHandler {
+    private static final Cleaner CLEANER = Cleaner.create();
+    /** {@code InvocationHandler#invokeDefault}, available since Java 16 */
+    private static final Method INVOKE_DEFAULT = findInvokeDefault();
+
+    private final Class<?> iface;
+    private final long ptr;
+
+    private RustClosure(Class<?> iface, long ptr) {
+        this.iface = iface;
+        this.ptr = ptr;
+        // The action must not reference this handler, or it would never become unreachable
+        CLEANER.register(this, () -> drop(ptr));
+    }
+
+    /**
+     * Create a proxy implementing {@code iface}. Ownership of the closure moves to the proxy
+     */
+    static Object create(Class<?> iface, long ptr) {
+        RustClosure handler = new RustClosure(iface, ptr);
+        return Proxy.newProxyInstance(iface.getClassLoader(), new Class<?>[] { iface }, handler);
+    }
+
+    @Override
+    public Object invoke(Object proxy, Method method, Object[] args) throws Throwable {
+        if (method.getDeclaringClass() == Object.class) {
+            switch (method.getName()) {
+                case "equals":
+                    return proxy == args[0];
+                case "hashCode":
+                    return System.identityHashCode(proxy);
+                default:
+                    return "RustClosure[" + iface.getName() + "]";
+            }
+        }
+
+        if (method.isDefault()) {
+            if (INVOKE_DEFAULT == null) {
+                throw new UnsupportedOperationException("Default methods of Rust closures require Java 16 or newer");
+            }
+            try {
+                return INVOKE_DEFAULT.invoke(null, proxy, method, args);
+            } catch (InvocationTargetException e) {
+                throw e.getCause();
+            }
+        }
+
+        try {
+            return call(ptr, args == null ? new Object[0] : args);
+        } finally {
+            // The closure must not be dropped while it is running
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    private static Method findInvokeDefault() {
+        try {
+            return InvocationHandler.class.getMethod("invokeDefault", Object.class, Method.class, Object[].class);
+        } catch (NoSuchMethodException e) {
+            return null;
+        }
+    }
+
+    private static native Object call(long ptr, Object[] args);
+
+    private static native void drop(long ptr);
+}
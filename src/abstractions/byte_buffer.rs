@@ -0,0 +1,583 @@
+use crate::abstractions::JavaString;
+use crate::cache::Cache;
+use crate::class::Class;
+use crate::error::{Error, JniResultExt, Result};
+use crate::java_type::{FromJava, IntoJava, JavaType};
+use crate::object::Object;
+use crate::primitives::Primitive;
+use crate::signature;
+use jni::objects::{JByteBuffer, JObject, JValue};
+use jni::sys::_jobject;
+use jni::JNIEnv;
+use std::convert::TryFrom;
+
+/// The byte order of a [ByteBuffer], mirroring `java.nio.ByteOrder`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteOrder {
+    /// `ByteOrder.BIG_ENDIAN`, the initial order of every ByteBuffer
+    BigEndian,
+    /// `ByteOrder.LITTLE_ENDIAN`
+    LittleEndian,
+}
+
+impl ByteOrder {
+    /// The native byte order of the platform, like `ByteOrder.nativeOrder()`
+    pub fn native() -> Self {
+        if cfg!(target_endian = "big") {
+            Self::BigEndian
+        } else {
+            Self::LittleEndian
+        }
+    }
+
+    fn field_name(&self) -> &'static str {
+        match self {
+            Self::BigEndian => "BIG_ENDIAN",
+            Self::LittleEndian => "LITTLE_ENDIAN",
+        }
+    }
+}
+
+/// Wrapper around `java.nio.ByteBuffer`.
+///
+/// A direct buffer can be backed by memory allocated by Rust, see [ByteBuffer::from_boxed].
+/// Its contents can be accessed without a copy through [ByteBuffer::as_slice] and [ByteBuffer::as_mut_slice].
+/// Heap buffers are backed by a `byte[]`, their contents are copied by [ByteBuffer::to_vec] and [ByteBuffer::put]
+pub struct ByteBuffer<'a> {
+    /// The ByteBuffer itself
+    pub inner: Object<'a>,
+    env: &'a JNIEnv<'a>,
+}
+
+#[allow(clippy::from_over_into)]
+impl<'a> Into<*mut _jobject> for ByteBuffer<'a> {
+    fn into(self) -> *mut _jobject {
+        // The local reference is handed to the caller, so it must not be deleted by Drop
+        let this = std::mem::ManuallyDrop::new(self);
+        this.inner.inner.into_inner()
+    }
+}
+
+impl<'a> Drop for ByteBuffer<'a> {
+    fn drop(&mut self) {
+        let _ = self.env.delete_local_ref(self.inner.inner);
+    }
+}
+
+impl<'a> JavaType<'a> for ByteBuffer<'a> {
+    fn class(env: &'a JNIEnv<'a>) -> Result<Class<'a>> {
+        Class::ByteBuffer(env)
+    }
+}
+
+impl<'a> IntoJava<'a> for ByteBuffer<'a> {
+    const OWNED: bool = true;
+
+    fn into_java(self, _: &'a JNIEnv<'a>) -> Result<Object<'a>> {
+        // The local reference is handed to the Object, so it must not be deleted by Drop
+        let this = std::mem::ManuallyDrop::new(self);
+        Ok(this.inner.clone())
+    }
+}
+
+impl<'a> FromJava<'a> for ByteBuffer<'a> {
+    fn from_java(object: Object<'a>) -> Result<Self> {
+        Ok(Self::new(object.env, object))
+    }
+}
+
+impl<'a> ByteBuffer<'a> {
+    /// Create a new ByteBuffer wrapper. The caller must guarantee that the passed in Object is a ByteBuffer and is not null
+    pub fn new(env: &'a JNIEnv<'a>, object: Object<'a>) -> Self {
+        Self { inner: object, env }
+    }
+
+    /// Allocates a new heap buffer of `capacity` bytes, initialized to zero. Invokes `ByteBuffer#allocate(int)`
+    pub fn allocate(env: &'a JNIEnv<'a>, capacity: usize) -> Result<Self> {
+        Self::allocate_with(env, "allocate", capacity)
+    }
+
+    /// Allocates a new direct buffer of `capacity` bytes, owned by the JVM. Invokes `ByteBuffer#allocateDirect(int)`
+    pub fn allocate_direct(env: &'a JNIEnv<'a>, capacity: usize) -> Result<Self> {
+        Self::allocate_with(env, "allocateDirect", capacity)
+    }
+
+    fn allocate_with(env: &'a JNIEnv<'a>, method: &str, capacity: usize) -> Result<Self> {
+        let capacity = to_int(capacity)?;
+        let buffer = Cache::call_static_method(
+            env,
+            "java/nio/ByteBuffer",
+            method,
+            signature!("(I)Ljava/nio/ByteBuffer;"),
+            &[JValue::Int(capacity)],
+        )?
+        .l()?;
+        Ok(Self::new(
+            env,
+            Object::new(env, buffer, Class::ByteBuffer(env)?),
+        ))
+    }
+
+    /// Create a new heap buffer holding a copy of `bytes`
+    pub fn wrap(env: &'a JNIEnv<'a>, bytes: &[u8]) -> Result<Self> {
+        let array = Primitive::new_byte_array(env, bytes)?;
+        let buffer = Cache::call_static_method(
+            env,
+            "java/nio/ByteBuffer",
+            "wrap",
+            signature!("([B)Ljava/nio/ByteBuffer;"),
+            &[JObject::from(array).into()],
+        );
+        env.delete_local_ref(array.into()).catch(env)?;
+        Ok(Self::new(
+            env,
+            Object::new(env, buffer?.l()?, Class::ByteBuffer(env)?),
+        ))
+    }
+
+    /// Create a direct buffer backed by `data`, without copying it.
+    ///
+    /// Ownership of the allocation moves to the Java buffer: Rust will not free it while Java may still use it.
+    /// The allocation is leaked, unless it is reclaimed with [ByteBuffer::into_boxed] once Java no longer references the buffer
+    pub fn from_boxed(env: &'a JNIEnv<'a>, data: Box<[u8]>) -> Result<Self> {
+        let data = Box::leak(data);
+        let (ptr, len) = (data.as_mut_ptr(), data.len());
+        // Safety: the allocation is leaked, so it is valid for as long as the buffer exists
+        let buffer = unsafe { Self::from_raw_parts(env, ptr, len) };
+        if buffer.is_err() {
+            // Safety: Java failed to create the buffer, so `data` is not referenced anywhere
+            drop(unsafe { Box::from_raw(std::ptr::slice_from_raw_parts_mut(ptr, len)) });
+        }
+        buffer
+    }
+
+    /// Create a direct buffer backed by `data`, without copying it. See [ByteBuffer::from_boxed].
+    /// Excess capacity of the Vec is freed first, so the allocation matches the capacity of the buffer
+    pub fn from_vec(env: &'a JNIEnv<'a>, data: Vec<u8>) -> Result<Self> {
+        Self::from_boxed(env, data.into_boxed_slice())
+    }
+
+    /// Create a direct buffer backed by `len` bytes at `ptr`. Invokes `NewDirectByteBuffer`
+    ///
+    /// # Safety
+    /// `ptr` must be valid for reads and writes of `len` bytes for as long as Java may use the buffer,
+    /// and must not be accessed through another reference while Java does
+    pub unsafe fn from_raw_parts(env: &'a JNIEnv<'a>, ptr: *mut u8, len: usize) -> Result<Self> {
+        let data = std::slice::from_raw_parts_mut(ptr, len);
+        let buffer = env.new_direct_byte_buffer(data).catch(env)?;
+        Ok(Self::new(
+            env,
+            Object::new(env, buffer.into(), Class::ByteBuffer(env)?),
+        ))
+    }
+
+    /// Take back the allocation of a buffer created by [ByteBuffer::from_boxed] or [ByteBuffer::from_vec]
+    ///
+    /// # Safety
+    /// The buffer must have been created by [ByteBuffer::from_boxed] or [ByteBuffer::from_vec], not through a slice or duplicate of it,
+    /// its allocation must not have been reclaimed before, and Java must no longer use the buffer or any view of it
+    pub unsafe fn into_boxed(self) -> Result<Box<[u8]>> {
+        let data = self
+            .env
+            .get_direct_buffer_address(self.raw())
+            .catch(self.env)?;
+        Ok(Box::from_raw(data as *mut [u8]))
+    }
+
+    /// Returns true if the buffer is direct. Invokes `ByteBuffer#isDirect()`
+    pub fn is_direct(&self) -> Result<bool> {
+        let is_direct = self.call("java/nio/ByteBuffer", "isDirect", signature!("()Z"), &[])?;
+        Ok(is_direct.z()?)
+    }
+
+    /// Returns true if the buffer is read-only. Invokes `Buffer#isReadOnly()`
+    pub fn is_read_only(&self) -> Result<bool> {
+        let is_read_only = self.call("java/nio/Buffer", "isReadOnly", signature!("()Z"), &[])?;
+        Ok(is_read_only.z()?)
+    }
+
+    /// Returns the capacity of the buffer. Invokes `Buffer#capacity()`
+    pub fn capacity(&self) -> Result<usize> {
+        let capacity = self.call("java/nio/Buffer", "capacity", signature!("()I"), &[])?;
+        Ok(capacity.i()? as usize)
+    }
+
+    /// Returns the position of the buffer. Invokes `Buffer#position()`
+    pub fn position(&self) -> Result<usize> {
+        let position = self.call("java/nio/Buffer", "position", signature!("()I"), &[])?;
+        Ok(position.i()? as usize)
+    }
+
+    /// Set the position of the buffer. Invokes `Buffer#position(int)`,
+    /// which fails with an `IllegalArgumentException` if `position` is larger than the limit
+    pub fn set_position(&self, position: usize) -> Result<()> {
+        self.call_self(
+            "position",
+            signature!("(I)Ljava/nio/Buffer;"),
+            &[JValue::Int(to_int(position)?)],
+        )
+    }
+
+    /// Returns the limit of the buffer. Invokes `Buffer#limit()`
+    pub fn limit(&self) -> Result<usize> {
+        let limit = self.call("java/nio/Buffer", "limit", signature!("()I"), &[])?;
+        Ok(limit.i()? as usize)
+    }
+
+    /// Set the limit of the buffer. Invokes `Buffer#limit(int)`,
+    /// which fails with an `IllegalArgumentException` if `limit` is larger than the capacity
+    pub fn set_limit(&self, limit: usize) -> Result<()> {
+        self.call_self(
+            "limit",
+            signature!("(I)Ljava/nio/Buffer;"),
+            &[JValue::Int(to_int(limit)?)],
+        )
+    }
+
+    /// Returns the number of bytes between the position and the limit. Invokes `Buffer#remaining()`
+    pub fn remaining(&self) -> Result<usize> {
+        let remaining = self.call("java/nio/Buffer", "remaining", signature!("()I"), &[])?;
+        Ok(remaining.i()? as usize)
+    }
+
+    /// Returns the byte order used by the multi-byte accessors of the buffer. Invokes `ByteBuffer#order()`
+    pub fn order(&self) -> Result<ByteOrder> {
+        let order = self
+            .call(
+                "java/nio/ByteBuffer",
+                "order",
+                signature!("()Ljava/nio/ByteOrder;"),
+                &[],
+            )?
+            .l()?;
+        let name = Cache::call_method(
+            self.env,
+            order,
+            "java/lang/Object",
+            "toString",
+            signature!("()Ljava/lang/String;"),
+            &[],
+        )?
+        .l()?;
+        let name = JavaString::new(
+            self.env,
+            Object::new(self.env, name, Class::String(self.env)?),
+        )
+        .into_rust()?;
+        self.env.delete_local_ref(order).catch(self.env)?;
+
+        match name.as_str() {
+            "BIG_ENDIAN" => Ok(ByteOrder::BigEndian),
+            _ => Ok(ByteOrder::LittleEndian),
+        }
+    }
+
+    /// Set the byte order used by the multi-byte accessors of the buffer. Invokes `ByteBuffer#order(ByteOrder)`
+    pub fn set_order(&self, order: ByteOrder) -> Result<()> {
+        let order = self
+            .env
+            .get_static_field(
+                Class::ByteOrder(self.env)?,
+                order.field_name(),
+                crate::descriptor!("Ljava/nio/ByteOrder;"),
+            )
+            .catch(self.env)?
+            .l()?;
+        let result = self.call_self(
+            "order",
+            signature!("(Ljava/nio/ByteOrder;)Ljava/nio/ByteBuffer;"),
+            &[order.into()],
+        );
+        self.env.delete_local_ref(order).catch(self.env)?;
+        result
+    }
+
+    /// Borrow the memory of a direct buffer. The slice covers the whole capacity, regardless of the position and limit.
+    /// Returns None for heap buffers
+    ///
+    /// # Safety
+    /// The memory is shared with Java. It must not be written by Java, or through another ByteBuffer viewing it,
+    /// for as long as the slice is used
+    pub unsafe fn as_slice(&self) -> Result<Option<&[u8]>> {
+        if !self.is_direct()? {
+            return Ok(None);
+        }
+
+        let data = self
+            .env
+            .get_direct_buffer_address(self.raw())
+            .catch(self.env)?;
+        Ok(Some(data))
+    }
+
+    /// Mutably borrow the memory of a direct buffer. The slice covers the whole capacity, regardless of the position and limit.
+    /// Returns None for heap buffers and read-only buffers
+    ///
+    /// # Safety
+    /// The memory is shared with Java. It must not be accessed by Java, or through another ByteBuffer viewing it,
+    /// for as long as the slice is used
+    pub unsafe fn as_mut_slice(&mut self) -> Result<Option<&mut [u8]>> {
+        if !self.is_direct()? || self.is_read_only()? {
+            return Ok(None);
+        }
+
+        let data = self
+            .env
+            .get_direct_buffer_address(self.raw())
+            .catch(self.env)?;
+        Ok(Some(data))
+    }
+
+    /// Copy the bytes between the position and the limit to a Vec. The position is not changed.
+    /// Direct buffers are read from their memory, other buffers are copied through Java
+    pub fn to_vec(&self) -> Result<Vec<u8>> {
+        let (position, limit) = (self.position()?, self.limit()?);
+        // Safety: the slice is dropped right after the bytes are copied out
+        if let Some(data) = unsafe { self.as_slice()? } {
+            return Ok(data[position..limit].to_vec());
+        }
+
+        // Read through a duplicate, so the position of this buffer is not changed
+        let duplicate = self
+            .call(
+                "java/nio/ByteBuffer",
+                "duplicate",
+                signature!("()Ljava/nio/ByteBuffer;"),
+                &[],
+            )?
+            .l()?;
+        let array = Primitive::new_byte_array(self.env, &vec![0; limit - position])?;
+        let result = Cache::call_method(
+            self.env,
+            duplicate,
+            "java/nio/ByteBuffer",
+            "get",
+            signature!("([B)Ljava/nio/ByteBuffer;"),
+            &[JObject::from(array).into()],
+        )
+        .and_then(|value| Ok(value.l()?))
+        .and_then(|value| Ok(self.env.delete_local_ref(value)?))
+        .and_then(|_| Primitive::get_byte_array(self.env, array));
+
+        self.env.delete_local_ref(duplicate).catch(self.env)?;
+        self.env.delete_local_ref(array.into()).catch(self.env)?;
+        result
+    }
+
+    /// Copy `bytes` into the buffer at its position, and advance the position by `bytes.len()`, like `ByteBuffer#put(byte[])`.
+    /// Fails with a `BufferOverflowException` if fewer than `bytes.len()` bytes remain, or a `ReadOnlyBufferException` if the buffer is read-only
+    pub fn put(&mut self, bytes: &[u8]) -> Result<()> {
+        let (position, remaining) = (self.position()?, self.remaining()?);
+        if bytes.len() <= remaining {
+            // Safety: the slice is dropped right after the bytes are copied in
+            if let Some(data) = unsafe { self.as_mut_slice()? } {
+                data[position..position + bytes.len()].copy_from_slice(bytes);
+                return self.set_position(position + bytes.len());
+            }
+        }
+
+        // Java copies heap buffers, and throws the appropriate exception when the bytes do not fit
+        let array = Primitive::new_byte_array(self.env, bytes)?;
+        let result = self.call_self(
+            "put",
+            signature!("([B)Ljava/nio/ByteBuffer;"),
+            &[JObject::from(array).into()],
+        );
+        self.env.delete_local_ref(array.into()).catch(self.env)?;
+        result
+    }
+
+    fn call(&self, class: &str, name: &str, sig: &str, args: &[JValue]) -> Result<JValue<'a>> {
+        Cache::call_method(self.env, self.inner.inner, class, name, sig, args)
+    }
+
+    /// Call a method which returns the buffer itself, and delete the returned reference
+    fn call_self(&self, name: &str, sig: &str, args: &[JValue]) -> Result<()> {
+        let class = if sig.ends_with("Ljava/nio/Buffer;") {
+            "java/nio/Buffer"
+        } else {
+            "java/nio/ByteBuffer"
+        };
+        let this = self.call(class, name, sig, args)?.l()?;
+        self.env.delete_local_ref(this).catch(self.env)
+    }
+
+    fn raw(&self) -> JByteBuffer<'a> {
+        JByteBuffer::from(self.inner.inner)
+    }
+}
+
+fn to_int(value: usize) -> Result<i32> {
+    i32::try_from(value).map_err(|_| Error::Conversion {
+        from: "usize",
+        to: "int",
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::error::Error;
+    use crate::test::JVM;
+
+    #[test]
+    fn allocate() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let heap = ByteBuffer::allocate(&env, 16).unwrap();
+        assert!(!heap.is_direct().unwrap());
+        assert_eq!(16, heap.capacity().unwrap());
+        assert!(unsafe { heap.as_slice() }.unwrap().is_none());
+
+        let direct = ByteBuffer::allocate_direct(&env, 16).unwrap();
+        assert!(direct.is_direct().unwrap());
+        assert_eq!(Some(&[0u8; 16][..]), unsafe { direct.as_slice() }.unwrap());
+    }
+
+    #[test]
+    fn from_boxed() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let data = vec![1u8, 2, 3, 4].into_boxed_slice();
+        let ptr = data.as_ptr();
+        let mut buffer = ByteBuffer::from_boxed(&env, data).unwrap();
+        assert!(buffer.is_direct().unwrap());
+        assert_eq!(4, buffer.capacity().unwrap());
+
+        // Java reads and writes the Rust allocation
+        let value = Cache::call_method(
+            &env,
+            buffer.inner.inner,
+            "java/nio/ByteBuffer",
+            "get",
+            signature!("(I)B"),
+            &[JValue::Int(2)],
+        )
+        .unwrap();
+        assert_eq!(3, value.b().unwrap());
+        unsafe { buffer.as_mut_slice() }.unwrap().unwrap()[0] = 10;
+
+        let data = unsafe { buffer.into_boxed() }.unwrap();
+        assert_eq!(ptr, data.as_ptr());
+        assert_eq!(&[10, 2, 3, 4], &data[..]);
+    }
+
+    #[test]
+    fn position_limit() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let buffer = ByteBuffer::from_vec(&env, (0..10).collect()).unwrap();
+        assert_eq!(0, buffer.position().unwrap());
+        assert_eq!(10, buffer.limit().unwrap());
+
+        buffer.set_position(2).unwrap();
+        buffer.set_limit(5).unwrap();
+        assert_eq!(3, buffer.remaining().unwrap());
+        assert_eq!(vec![2, 3, 4], buffer.to_vec().unwrap());
+        assert_eq!(2, buffer.position().unwrap());
+
+        let err = buffer.set_position(6).unwrap_err();
+        assert!(
+            matches!(err, Error::JavaException(e) if e.class == "java.lang.IllegalArgumentException")
+        );
+        drop(unsafe { buffer.into_boxed() }.unwrap());
+    }
+
+    #[test]
+    fn heap_fallback() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let mut buffer = ByteBuffer::wrap(&env, &[1, 2, 3, 4, 5]).unwrap();
+        assert!(unsafe { buffer.as_mut_slice() }.unwrap().is_none());
+        buffer.set_position(1).unwrap();
+        assert_eq!(vec![2, 3, 4, 5], buffer.to_vec().unwrap());
+
+        buffer.put(&[20, 30]).unwrap();
+        assert_eq!(3, buffer.position().unwrap());
+        buffer.set_position(0).unwrap();
+        assert_eq!(vec![1, 20, 30, 4, 5], buffer.to_vec().unwrap());
+
+        buffer.set_position(4).unwrap();
+        let err = buffer.put(&[1, 2]).unwrap_err();
+        assert!(
+            matches!(err, Error::JavaException(e) if e.class == "java.nio.BufferOverflowException")
+        );
+    }
+
+    #[test]
+    fn direct_put() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let mut buffer = ByteBuffer::allocate_direct(&env, 4).unwrap();
+        buffer.put(&[1, 2]).unwrap();
+        assert_eq!(2, buffer.position().unwrap());
+        assert_eq!(
+            &[1, 2, 0, 0],
+            unsafe { buffer.as_slice() }.unwrap().unwrap()
+        );
+
+        let err = buffer.put(&[1, 2, 3]).unwrap_err();
+        assert!(
+            matches!(err, Error::JavaException(e) if e.class == "java.nio.BufferOverflowException")
+        );
+    }
+
+    #[test]
+    fn read_only() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let buffer = ByteBuffer::allocate_direct(&env, 4).unwrap();
+        let read_only = Cache::call_method(
+            &env,
+            buffer.inner.inner,
+            "java/nio/ByteBuffer",
+            "asReadOnlyBuffer",
+            signature!("()Ljava/nio/ByteBuffer;"),
+            &[],
+        )
+        .unwrap()
+        .l()
+        .unwrap();
+        let mut read_only = ByteBuffer::new(
+            &env,
+            Object::new(&env, read_only, Class::ByteBuffer(&env).unwrap()),
+        );
+
+        assert!(read_only.is_read_only().unwrap());
+        assert!(unsafe { read_only.as_slice() }.unwrap().is_some());
+        assert!(unsafe { read_only.as_mut_slice() }.unwrap().is_none());
+        let err = read_only.put(&[1]).unwrap_err();
+        assert!(
+            matches!(err, Error::JavaException(e) if e.class == "java.nio.ReadOnlyBufferException")
+        );
+    }
+
+    #[test]
+    fn order() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let buffer = ByteBuffer::allocate(&env, 8).unwrap();
+        assert_eq!(ByteOrder::BigEndian, buffer.order().unwrap());
+        buffer.set_order(ByteOrder::LittleEndian).unwrap();
+        assert_eq!(ByteOrder::LittleEndian, buffer.order().unwrap());
+
+        Cache::call_method(
+            &env,
+            buffer.inner.inner,
+            "java/nio/ByteBuffer",
+            "putInt",
+            signature!("(I)Ljava/nio/ByteBuffer;"),
+            &[JValue::Int(1)],
+        )
+        .unwrap();
+        buffer.set_position(0).unwrap();
+        assert_eq!(vec![1, 0, 0, 0, 0, 0, 0, 0], buffer.to_vec().unwrap());
+    }
+}
@@ -0,0 +1,342 @@
+use crate::abstractions::JavaString;
+use crate::cache::Cache;
+use crate::class::Class;
+use crate::error::{JniResultExt, Result};
+use crate::java_type::{FromJava, IntoJava, JavaType};
+use crate::object::Object;
+use crate::signature;
+use jni::objects::{GlobalRef, JClass, JObject};
+use jni::sys::_jobject;
+use jni::JNIEnv;
+use std::sync::{PoisonError, RwLock};
+
+/// The ClassLoader used by [Class::for_name], see [ClassLoader::set_default]
+static DEFAULT: RwLock<Option<GlobalRef>> = RwLock::new(None);
+
+/// Wrapper around `java.lang.ClassLoader`.
+///
+/// `FindClass`, and thus [Class::for_name], resolves classes through the class loader of the Java method calling into native code,
+/// or through the system class loader for threads attached from native code.
+/// Classes loaded by a plugin or application class loader can only be found through that loader, see [ClassLoader::load_class] and [Class::for_name_in]
+pub struct ClassLoader<'a> {
+    /// The ClassLoader itself
+    pub inner: Object<'a>,
+    env: &'a JNIEnv<'a>,
+}
+
+#[allow(clippy::from_over_into)]
+impl<'a> Into<*mut _jobject> for ClassLoader<'a> {
+    fn into(self) -> *mut _jobject {
+        // The local reference is handed to the caller, so it must not be deleted by Drop
+        let this = std::mem::ManuallyDrop::new(self);
+        this.inner.inner.into_inner()
+    }
+}
+
+impl<'a> Drop for ClassLoader<'a> {
+    fn drop(&mut self) {
+        let _ = self.env.delete_local_ref(self.inner.inner);
+    }
+}
+
+impl<'a> JavaType<'a> for ClassLoader<'a> {
+    fn class(env: &'a JNIEnv<'a>) -> Result<Class<'a>> {
+        Class::ClassLoader(env)
+    }
+}
+
+impl<'a> IntoJava<'a> for ClassLoader<'a> {
+    const OWNED: bool = true;
+
+    fn into_java(self, _: &'a JNIEnv<'a>) -> Result<Object<'a>> {
+        // The local reference is handed to the Object, so it must not be deleted by Drop
+        let this = std::mem::ManuallyDrop::new(self);
+        Ok(this.inner.clone())
+    }
+}
+
+impl<'a> FromJava<'a> for ClassLoader<'a> {
+    fn from_java(object: Object<'a>) -> Result<Self> {
+        Ok(Self::new(object.env, object))
+    }
+}
+
+impl<'a> ClassLoader<'a> {
+    /// Create a new ClassLoader wrapper. The caller must guarantee that the passed in Object is a ClassLoader and is not null
+    pub fn new(env: &'a JNIEnv<'a>, object: Object<'a>) -> Self {
+        Self { inner: object, env }
+    }
+
+    /// Get the system class loader. Invokes `ClassLoader#getSystemClassLoader()`
+    pub fn system(env: &'a JNIEnv<'a>) -> Result<Self> {
+        let loader = Cache::call_static_method(
+            env,
+            "java/lang/ClassLoader",
+            "getSystemClassLoader",
+            signature!("()Ljava/lang/ClassLoader;"),
+            &[],
+        )?
+        .l()?;
+        Ok(Self::new(
+            env,
+            Object::new(env, loader, Class::ClassLoader(env)?),
+        ))
+    }
+
+    /// Get the context class loader of the current thread. Returns None if it is not set.
+    /// Invokes `Thread#getContextClassLoader()` on `Thread#currentThread()`
+    pub fn context(env: &'a JNIEnv<'a>) -> Result<Option<Self>> {
+        let thread = Cache::call_static_method(
+            env,
+            "java/lang/Thread",
+            "currentThread",
+            signature!("()Ljava/lang/Thread;"),
+            &[],
+        )?
+        .l()?;
+        let loader = Cache::call_method(
+            env,
+            thread,
+            "java/lang/Thread",
+            "getContextClassLoader",
+            signature!("()Ljava/lang/ClassLoader;"),
+            &[],
+        )?
+        .l()?;
+        env.delete_local_ref(thread)?;
+
+        if loader.is_null() {
+            return Ok(None);
+        }
+        Ok(Some(Self::new(
+            env,
+            Object::new(env, loader, Class::ClassLoader(env)?),
+        )))
+    }
+
+    /// Get the ClassLoader used by [Class::for_name], if one has been set with [ClassLoader::set_default]
+    pub fn get_default(env: &'a JNIEnv<'a>) -> Result<Option<Self>> {
+        let default = DEFAULT.read().unwrap_or_else(PoisonError::into_inner);
+        let global = match default.as_ref() {
+            Some(global) => global,
+            None => return Ok(None),
+        };
+
+        let loader = env
+            .new_local_ref::<JObject>(JObject::from(global.as_obj().into_inner()))
+            .catch(env)?;
+        Ok(Some(Self::new(
+            env,
+            Object::new(env, loader, Class::ClassLoader(env)?),
+        )))
+    }
+
+    /// Make [Class::for_name] resolve classes through `loader` instead of `FindClass`, for every thread.
+    /// The loader is kept alive with a global reference until it is replaced. Pass None to restore the use of `FindClass`.
+    ///
+    /// This is intended for libraries loaded by a plugin or application class loader, which can set their loader from `JNI_OnLoad`
+    pub fn set_default(loader: Option<&ClassLoader<'_>>) -> Result<()> {
+        let global = match loader {
+            Some(loader) => Some(
+                loader
+                    .env
+                    .new_global_ref(loader.inner.inner)
+                    .catch(loader.env)?,
+            ),
+            None => None,
+        };
+        *DEFAULT.write().unwrap_or_else(PoisonError::into_inner) = global;
+        Ok(())
+    }
+
+    /// Load a class by it's binary name, e.g `com.example.Foo` or `com/example/Foo`. Invokes `ClassLoader#loadClass(String)`
+    pub fn load_class<S: AsRef<str>>(&self, name: S) -> Result<Class<'a>> {
+        let name = JavaString::from_rust(self.env, name.as_ref().replace('/', "."))?;
+        let class = Cache::call_method(
+            self.env,
+            self.inner.inner,
+            "java/lang/ClassLoader",
+            "loadClass",
+            signature!("(Ljava/lang/String;)Ljava/lang/Class;"),
+            &[name.into()],
+        )?
+        .l()?;
+        Ok(Class::new(self.env, JClass::from(class)))
+    }
+
+    /// Define a class in this ClassLoader from the bytes of a `.class` file, e.g embedded with `include_bytes!`.
+    /// `name` must match the name in the class file, e.g `com/example/Foo` or `com.example.Foo`.
+    ///
+    /// A class can only be defined once per ClassLoader, defining it again results in a `java.lang.LinkageError`
+    pub fn define_class<S: AsRef<str>>(&self, name: S, bytes: &[u8]) -> Result<Class<'a>> {
+        let name = name.as_ref().replace('.', "/");
+        let class = self
+            .env
+            .define_class(name, self.inner.inner, bytes)
+            .catch(self.env)?;
+        Ok(Class::new(self.env, class))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::JVM;
+
+    const PLUGIN: &[u8] = include_bytes!("../../test-classes/ejni/test/Plugin.class");
+
+    /// Create a class loader of its own, delegating to the system class loader
+    fn isolated<'a>(env: &'a JNIEnv<'a>) -> ClassLoader<'a> {
+        let urls = env
+            .new_object_array(0, "java/net/URL", JObject::null())
+            .unwrap();
+        let system = ClassLoader::system(env).unwrap();
+        let loader = env
+            .new_object(
+                "java/net/URLClassLoader",
+                "([Ljava/net/URL;Ljava/lang/ClassLoader;)V",
+                &[JObject::from(urls).into(), system.inner.inner.into()],
+            )
+            .unwrap();
+        ClassLoader::new(
+            env,
+            Object::new(env, loader, Class::ClassLoader(env).unwrap()),
+        )
+    }
+
+    #[test]
+    fn system() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let loader = ClassLoader::system(&env).unwrap();
+        let class = loader.load_class("java/lang/String").unwrap();
+        assert_eq!("java.lang.String", class.get_name().unwrap());
+        assert!(loader.load_class("ejni.test.DoesNotExist").is_err());
+    }
+
+    #[test]
+    fn context() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let thread = env
+            .call_static_method(
+                "java/lang/Thread",
+                "currentThread",
+                "()Ljava/lang/Thread;",
+                &[],
+            )
+            .unwrap()
+            .l()
+            .unwrap();
+        let set_context = |loader: JObject| {
+            env.call_method(
+                thread,
+                "setContextClassLoader",
+                "(Ljava/lang/ClassLoader;)V",
+                &[loader.into()],
+            )
+            .unwrap();
+        };
+
+        let loader = isolated(&env);
+        set_context(loader.inner.inner);
+        let context = ClassLoader::context(&env).unwrap().unwrap();
+        assert!(env
+            .is_same_object(context.inner.inner, loader.inner.inner)
+            .unwrap());
+
+        set_context(JObject::null());
+        assert!(ClassLoader::context(&env).unwrap().is_none());
+    }
+
+    #[test]
+    fn define_class() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let loader = isolated(&env);
+        let class = loader.define_class("ejni.test.Plugin", PLUGIN).unwrap();
+        assert_eq!("ejni.test.Plugin", class.get_name().unwrap());
+
+        let loaded = loader.load_class("ejni.test.Plugin").unwrap();
+        assert!(env.is_same_object(class.class, loaded.class).unwrap());
+        let defined_by = class.class_loader().unwrap().unwrap();
+        assert!(env
+            .is_same_object(defined_by.inner.inner, loader.inner.inner)
+            .unwrap());
+
+        // The class is already defined in this loader
+        assert!(loader.define_class("ejni/test/Plugin", PLUGIN).is_err());
+    }
+
+    #[test]
+    fn default() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let loader = isolated(&env);
+        loader.define_class("ejni/test/Plugin", PLUGIN).unwrap();
+        assert!(Class::for_name(&env, "ejni/test/Plugin").is_err());
+        assert!(Class::for_name_in(&env, "ejni/test/Plugin", &loader).is_ok());
+
+        ClassLoader::set_default(Some(&loader)).unwrap();
+        assert!(ClassLoader::get_default(&env).unwrap().is_some());
+        let class = Class::for_name(&env, "ejni.test.Plugin");
+        let string = Class::for_name(&env, "java/lang/String");
+        ClassLoader::set_default(None).unwrap();
+
+        assert_eq!("ejni.test.Plugin", class.unwrap().get_name().unwrap());
+        assert_eq!("java.lang.String", string.unwrap().get_name().unwrap());
+        assert!(ClassLoader::get_default(&env).unwrap().is_none());
+        assert!(Class::for_name(&env, "ejni/test/Plugin").is_err());
+    }
+
+    #[test]
+    fn default_derived() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let (first, second) = (isolated(&env), isolated(&env));
+        let first_class = first.define_class("ejni/test/Plugin", PLUGIN).unwrap();
+        let second_class = second.define_class("ejni/test/Plugin", PLUGIN).unwrap();
+
+        // The class must be resolved again once the default loader changes
+        ClassLoader::set_default(Some(&first)).unwrap();
+        let from_first = crate::private::class(&env, "ejni.test.Plugin");
+        ClassLoader::set_default(Some(&second)).unwrap();
+        let from_second = crate::private::class(&env, "ejni.test.Plugin");
+        ClassLoader::set_default(None).unwrap();
+
+        let from_first = from_first.unwrap();
+        let from_second = from_second.unwrap();
+        assert!(env
+            .is_same_object(first_class.class, from_first.class)
+            .unwrap());
+        assert!(env
+            .is_same_object(second_class.class, from_second.class)
+            .unwrap());
+        assert!(crate::private::class(&env, "ejni.test.Plugin").is_err());
+    }
+
+    #[test]
+    fn default_bindings() {
+        use crate::test::bindings::Plugin;
+
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let loader = isolated(&env);
+        loader.define_class("ejni/test/Plugin", PLUGIN).unwrap();
+
+        ClassLoader::set_default(Some(&loader)).unwrap();
+        let name = Plugin::name(&env);
+        let plugin = Plugin::create(&env).map(|plugin| plugin.inner.class.get_name());
+        ClassLoader::set_default(None).unwrap();
+
+        assert_eq!(Some("plugin".to_string()), name.unwrap());
+        assert_eq!("ejni.test.Plugin", plugin.unwrap().unwrap());
+        assert!(Plugin::name(&env).is_err());
+    }
+}
@@ -1,77 +1,94 @@
-use crate::class::Class;
+use crate::cache::Cache;
+use crate::error::{Error, Result};
+use crate::frame::with_local_frame;
+use crate::java_type::{from_nullable, FromJava};
 use crate::object::Object;
-use jni::errors::Result;
+use crate::signature;
 use jni::sys::_jobject;
 use jni::JNIEnv;
+use std::convert::TryFrom;
+use std::marker::PhantomData;
+
+/// The number of local references reserved per element by the chunked iteration helpers:
+/// the element itself, its Class and room for the conversion to `T`
+const REFS_PER_ELEMENT: usize = 4;
 
 /// Wrapper around `java.util.Iterator`
-pub struct Iterator<'a> {
+pub struct Iterator<'a, T = Object<'a>> {
     /// The iterator itself
     pub inner: Object<'a>,
-
-    /// The Class the iterator iterates over
-    pub class: Class<'a>,
-
     env: &'a JNIEnv<'a>,
+    _type: PhantomData<T>,
 }
 
 #[allow(clippy::from_over_into)]
-impl<'a> Into<*mut _jobject> for Iterator<'a> {
+impl<'a, T> Into<*mut _jobject> for Iterator<'a, T> {
     fn into(self) -> *mut _jobject {
-        self.inner.inner.into_inner()
+        // The local reference is handed to the caller, so it must not be deleted by Drop
+        let this = std::mem::ManuallyDrop::new(self);
+        this.inner.inner.into_inner()
     }
 }
 
-impl<'a> Drop for Iterator<'a> {
+impl<'a, T> Drop for Iterator<'a, T> {
     fn drop(&mut self) {
         let _ = self.env.delete_local_ref(self.inner.inner);
     }
 }
 
-impl<'a> std::iter::Iterator for Iterator<'a> {
-    type Item = Result<Object<'a>>;
+impl<'a, T: FromJava<'a>> std::iter::Iterator for Iterator<'a, T> {
+    type Item = Result<T>;
 
     fn next(&mut self) -> Option<Self::Item> {
         Iterator::next(self).transpose()
     }
 }
 
-impl<'a> Iterator<'a> {
-    /// Create a new instance of Iterator. The caller must guarantee that the passed in Object implements Iterator and is not null.
-    pub fn new(env: &'a JNIEnv<'a>, object: Object<'a>, class: Class<'a>) -> Self {
+impl<'a, T> Iterator<'a, T> {
+    /// Create a new instance of Iterator. The caller must guarantee that the passed in Object implements Iterator, that it iterates over elements of type `T` and is not null.
+    pub fn new(env: &'a JNIEnv<'a>, object: Object<'a>) -> Self {
         Self {
             inner: object,
-            class,
             env,
+            _type: PhantomData,
         }
     }
 
     /// Returns true if the iteration has more elements.
     pub fn has_next(&self) -> Result<bool> {
-        let has_next = self
-            .env
-            .call_method(self.inner.inner, "hasNext", "()Z", &[])?;
-        has_next.z()
+        let has_next = Cache::call_method(
+            self.env,
+            self.inner.inner,
+            "java/util/Iterator",
+            "hasNext",
+            signature!("()Z"),
+            &[],
+        )?;
+        Ok(has_next.z()?)
     }
+}
 
+impl<'a, T: FromJava<'a>> Iterator<'a, T> {
     /// Returns the next element in the iteration.
-    pub fn next(&self) -> Result<Option<Object<'a>>> {
+    pub fn next(&self) -> Result<Option<T>> {
         if !self.has_next()? {
             return Ok(None);
         }
 
-        let next = self
-            .env
-            .call_method(self.inner.inner, "next", "()Ljava/lang/Object;", &[])?;
-        let object = Object::new(self.env, next.l()?, self.class.clone());
-        match object.inner.is_null() {
-            true => Ok(None),
-            false => Ok(Some(object)),
-        }
+        let next = Cache::call_method(
+            self.env,
+            self.inner.inner,
+            "java/util/Iterator",
+            "next",
+            signature!("()Ljava/lang/Object;"),
+            &[],
+        )?;
+        from_nullable(self.env, next.l()?)
     }
 
-    /// Convert the java.util.Iterator to a Vec
-    pub fn to_vec(&self) -> Result<Vec<Object>> {
+    /// Convert the java.util.Iterator to a Vec.
+    /// Every element holds at least one local reference, use [Iterator::to_vec_chunked] for large collections
+    pub fn to_vec(&self) -> Result<Vec<T>> {
         let mut objects = Vec::new();
         while let Some(i) = self.next()? {
             objects.push(i);
@@ -81,26 +98,90 @@ impl<'a> Iterator<'a> {
     }
 }
 
+impl<'a, T: FromJava<'a> + 'static> Iterator<'a, T> {
+    /// Convert the java.util.Iterator to a Vec, converting `chunk_size` elements per local frame.
+    /// Unlike [Iterator::to_vec] the number of live local references does not grow with the number of elements.
+    /// `T` must not hold local references, e.g `String` or [GlobalObject](crate::GlobalObject)
+    pub fn to_vec_chunked(&self, chunk_size: usize) -> Result<Vec<T>> {
+        let mut objects = Vec::new();
+        self.for_each_chunked(chunk_size, |chunk| {
+            objects.extend(chunk);
+            Ok(())
+        })?;
+
+        Ok(objects)
+    }
+
+    /// Call `f` with the remaining elements, in chunks of at most `chunk_size` elements.
+    /// Each chunk is converted in its own local frame (see [with_local_frame]), which is popped after `f` returns.
+    /// `T` must not hold local references, so the elements stay valid once the frame is popped.
+    /// An iterator over Objects can not be consumed this way:
+    /// ```compile_fail
+    /// # use ejni::{Iterator, Object};
+    /// # fn leak<'a>(iterator: Iterator<'a, Object<'a>>) {
+    /// let mut objects = Vec::new();
+    /// iterator.for_each_chunked(16, |chunk| {
+    ///     objects.extend(chunk);
+    ///     Ok(())
+    /// });
+    /// # }
+    /// ```
+    /// A `chunk_size` of 0 is treated as 1
+    pub fn for_each_chunked<F>(&self, chunk_size: usize, mut f: F) -> Result<()>
+    where
+        F: FnMut(Vec<T>) -> Result<()>,
+    {
+        let chunk_size = chunk_size.max(1);
+        let capacity =
+            i32::try_from(chunk_size.saturating_mul(REFS_PER_ELEMENT)).unwrap_or(i32::MAX);
+
+        loop {
+            let mut done = false;
+            with_local_frame(self.env, capacity, |_| {
+                let mut chunk = Vec::with_capacity(chunk_size);
+                while chunk.len() < chunk_size {
+                    match self.next()? {
+                        Some(element) => chunk.push(element),
+                        None => {
+                            done = true;
+                            break;
+                        }
+                    }
+                }
+
+                if !chunk.is_empty() {
+                    f(chunk)?;
+                }
+                Ok::<_, Error>(None)
+            })?;
+
+            if done {
+                return Ok(());
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::class::Class;
     use crate::test::JVM;
     use crate::List;
+    use jni::objects::JObject;
 
     #[test]
     fn has_next() {
         let jvm = JVM.lock().unwrap();
         let env = jvm.attach_current_thread().unwrap();
-        let int_class = Class::Integer(&env).unwrap();
-        let list = List::arraylist(&env, int_class).unwrap();
+        let list = List::<i32>::arraylist(&env).unwrap();
 
         let iterator = list.iterator().unwrap();
         let has_next = iterator.has_next();
         assert!(has_next.is_ok());
         assert!(!has_next.unwrap());
 
-        list.add(&Object::new_integer_object(&env, 10).unwrap())
-            .unwrap();
+        list.add(10).unwrap();
         let iterator = list.iterator().unwrap();
         let has_next = iterator.has_next();
         assert!(has_next.is_ok());
@@ -111,15 +192,14 @@ mod test {
     fn next() {
         let jvm = JVM.lock().unwrap();
         let env = jvm.attach_current_thread().unwrap();
-        let list = List::arraylist(&env, Class::Integer(&env).unwrap()).unwrap();
+        let list = List::<i32>::arraylist(&env).unwrap();
 
         let iterator = list.iterator().unwrap();
         let next = iterator.next();
         assert!(next.is_ok());
         assert!(next.unwrap().is_none());
 
-        let value = Object::new_integer_object(&env, 10).unwrap();
-        list.add(&value).unwrap();
+        list.add(10).unwrap();
 
         let iterator = list.iterator().unwrap();
         let next = iterator.next();
@@ -127,16 +207,14 @@ mod test {
 
         let next = next.unwrap();
         assert!(next.is_some());
-
-        let next = next.unwrap();
-        assert_eq!(value.get_integer().unwrap(), next.get_integer().unwrap());
+        assert_eq!(10, next.unwrap());
     }
 
     #[test]
     fn to_vec() {
         let jvm = JVM.lock().unwrap();
         let env = jvm.attach_current_thread().unwrap();
-        let list = List::arraylist(&env, Class::Integer(&env).unwrap()).unwrap();
+        let list = List::<i32>::arraylist(&env).unwrap();
 
         let iterator = list.iterator().unwrap();
         let vec = iterator.to_vec();
@@ -145,8 +223,7 @@ mod test {
         let vec = vec.unwrap();
         assert!(vec.is_empty());
 
-        let value = Object::new_integer_object(&env, 10).unwrap();
-        list.add(&value).unwrap();
+        list.add(10).unwrap();
         let iterator = list.iterator().unwrap();
 
         let vec = iterator.to_vec();
@@ -156,8 +233,89 @@ mod test {
         assert!(!vec.is_empty());
         let front = vec.first();
         assert!(front.is_some());
+        assert_eq!(10, *front.unwrap());
+    }
+
+    #[test]
+    fn std_iterator() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        let list = List::<String>::arraylist(&env).unwrap();
+        list.add("Foo".to_string()).unwrap();
+        list.add("Bar".to_string()).unwrap();
 
-        let front = front.unwrap();
-        assert_eq!(value.get_integer().unwrap(), front.get_integer().unwrap());
+        let strings = list
+            .iterator()
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(vec!["Foo", "Bar"], strings);
+    }
+
+    #[test]
+    fn to_vec_chunked() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let set = with_local_frame(&env, 2048, |frame| {
+            let set = crate::Set::<i32>::hashset(frame.env())?;
+            for i in 0..1000 {
+                set.add(i)?;
+            }
+
+            let inner: jni::sys::jobject = set.into();
+            Ok::<_, crate::Error>(Some(Object::new(
+                frame.env(),
+                inner.into(),
+                Class::Set(frame.env())?,
+            )))
+        })
+        .unwrap()
+        .unwrap();
+        let set = crate::Set::<i32>::new(&env, set);
+
+        let mut vec = set.iterator().unwrap().to_vec_chunked(64).unwrap();
+        vec.sort_unstable();
+        assert_eq!((0..1000).collect::<Vec<_>>(), vec);
+    }
+
+    #[test]
+    fn for_each_chunked() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        let list = List::<String>::arraylist(&env).unwrap();
+        for i in 0..10 {
+            list.add(i.to_string()).unwrap();
+        }
+
+        let mut sizes = Vec::new();
+        list.iterator()
+            .unwrap()
+            .for_each_chunked(4, |chunk| {
+                sizes.push(chunk.len());
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(vec![4, 4, 2], sizes);
+    }
+
+    #[test]
+    fn chunked_class_mismatch() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        let list = List::<String>::arraylist(&env).unwrap();
+        list.add("Foo".to_string()).unwrap();
+
+        // The same list, viewed with the wrong element type
+        let local = env.new_local_ref::<JObject>(list.inner.inner).unwrap();
+        let list = List::<i32>::new(&env, Object::new(&env, local, list.inner.class.clone()));
+        let err = list.iterator().unwrap().to_vec_chunked(4).unwrap_err();
+        match err {
+            Error::ClassMismatch { expected, found } => {
+                assert_eq!("java.lang.Integer", expected);
+                assert_eq!("java.lang.String", found);
+            }
+            e => panic!("Unexpected error: {:?}", e),
+        }
     }
 }
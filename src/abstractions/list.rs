@@ -1,221 +1,278 @@
+use crate::cache::Cache;
 use crate::class::Class;
+use crate::error::{Error, Result};
+use crate::java_type::{from_nullable, Converted, FromJava, IntoJava, JavaType};
 use crate::object::Object;
+use crate::signature;
 use jni::objects::JValue;
 use jni::sys::_jobject;
-use jni::{errors::Result, JNIEnv};
+use jni::JNIEnv;
+use std::convert::TryFrom;
+use std::marker::PhantomData;
 
 /// Wrapper around `java.util.List`
-pub struct List<'a> {
+pub struct List<'a, T = Object<'a>> {
     /// The list itself
     pub inner: Object<'a>,
-
-    /// The type contained in the List
-    pub class: Class<'a>,
-
     env: &'a JNIEnv<'a>,
+    _type: PhantomData<T>,
 }
 
-impl<'a> From<Object<'a>> for List<'a> {
-    fn from(obj: Object<'a>) -> Self {
-        Self {
-            inner: obj.clone(),
-            class: obj.class,
-            env: obj.env,
-        }
+impl<'a, T> TryFrom<Object<'a>> for List<'a, T> {
+    type Error = Error;
+
+    /// Fails if the Object is null or does not implement `java.util.List`, see [Object::cast]
+    fn try_from(object: Object<'a>) -> Result<Self> {
+        object.cast()
     }
 }
 
 #[allow(clippy::from_over_into)]
-impl<'a> Into<*mut _jobject> for List<'a> {
+impl<'a, T> Into<*mut _jobject> for List<'a, T> {
     fn into(self) -> *mut _jobject {
-        self.inner.inner.into_inner()
+        // The local reference is handed to the caller, so it must not be deleted by Drop
+        let this = std::mem::ManuallyDrop::new(self);
+        this.inner.inner.into_inner()
     }
 }
 
-impl<'a> Drop for List<'a> {
+impl<'a, T> Drop for List<'a, T> {
     fn drop(&mut self) {
         let _ = self.env.delete_local_ref(self.inner.inner);
     }
 }
 
-impl<'a> List<'a> {
-    /// Create a List abstraction from it's raw components. The caller must guarantee that `object` implements `java.util.List` and that `class` is the correct Class
-    pub fn new(env: &'a JNIEnv<'a>, object: Object<'a>, class: Class<'a>) -> Self {
+impl<'a, T> JavaType<'a> for List<'a, T> {
+    fn class(env: &'a JNIEnv<'a>) -> Result<Class<'a>> {
+        Class::List(env)
+    }
+}
+
+impl<'a, T> IntoJava<'a> for List<'a, T> {
+    const OWNED: bool = true;
+
+    fn into_java(self, _: &'a JNIEnv<'a>) -> Result<Object<'a>> {
+        // The local reference is handed to the Object, so it must not be deleted by Drop
+        let this = std::mem::ManuallyDrop::new(self);
+        Ok(this.inner.clone())
+    }
+}
+
+impl<'a, T> FromJava<'a> for List<'a, T> {
+    fn from_java(object: Object<'a>) -> Result<Self> {
+        Ok(Self::new(object.env, object))
+    }
+}
+
+impl<'a, T> List<'a, T> {
+    /// Create a List abstraction from it's raw components. The caller must guarantee that `object` implements `java.util.List` and that it contains elements of type `T`
+    pub fn new(env: &'a JNIEnv<'a>, object: Object<'a>) -> Self {
         Self {
             inner: object,
-            class,
             env,
+            _type: PhantomData,
         }
     }
 
     /// Create a new `java.util.ArrayList`
-    pub fn arraylist(env: &'a JNIEnv<'a>, v_class: Class<'a>) -> Result<Self> {
-        let arraylist = env.new_object("java/util/ArrayList", "()V", &[])?;
-        Ok(Self {
-            inner: Object::new(env, arraylist, Class::ArrayList(env)?),
-            class: v_class,
+    pub fn arraylist(env: &'a JNIEnv<'a>) -> Result<Self> {
+        let arraylist = Cache::new_object(env, "java/util/ArrayList", signature!("()V"), &[])?;
+        Ok(Self::new(
             env,
-        })
+            Object::new(env, arraylist, Class::ArrayList(env)?),
+        ))
     }
 
+    /// Removes all of the elements from this list (optional operation).
+    pub fn clear(&self) -> Result<()> {
+        Cache::call_method(
+            self.env,
+            self.inner.inner,
+            "java/util/List",
+            "clear",
+            signature!("()V"),
+            &[],
+        )?;
+        Ok(())
+    }
+
+    /// Returns true if this list contains no elements.
+    pub fn is_empty(&self) -> Result<bool> {
+        let is_empty = Cache::call_method(
+            self.env,
+            self.inner.inner,
+            "java/util/List",
+            "isEmpty",
+            signature!("()Z"),
+            &[],
+        )?;
+        Ok(is_empty.z()?)
+    }
+
+    /// Returns the number of elements in this list.
+    pub fn size(&self) -> Result<i32> {
+        let size = Cache::call_method(
+            self.env,
+            self.inner.inner,
+            "java/util/List",
+            "size",
+            signature!("()I"),
+            &[],
+        )?;
+        Ok(size.i()?)
+    }
+
+    /// Returns a view of the portion of this list between the specified from index, inclusive, and to index, exclusive.
+    pub fn sublist(&self, from: i32, to: i32) -> Result<List<'a, T>> {
+        let sublist = Cache::call_method(
+            self.env,
+            self.inner.inner,
+            "java/util/List",
+            "subList",
+            signature!("(II)Ljava/util/List;"),
+            &[JValue::Int(from), JValue::Int(to)],
+        )?;
+        Ok(Self::new(
+            self.env,
+            Object::new(self.env, sublist.l()?, self.inner.class.clone()),
+        ))
+    }
+
+    /// Returns an iterator over the elements in this list in proper sequence.
+    pub fn iterator(&self) -> Result<crate::Iterator<'a, T>> {
+        let iterator = Cache::call_method(
+            self.env,
+            self.inner.inner,
+            "java/util/List",
+            "iterator",
+            signature!("()Ljava/util/Iterator;"),
+            &[],
+        )?;
+        Ok(crate::Iterator::new(
+            self.env,
+            Object::new(self.env, iterator.l()?, Class::Iterator(self.env)?),
+        ))
+    }
+}
+
+impl<'a, T: IntoJava<'a>> List<'a, T> {
     /// Appends the specified element to the end of this list (optional operation).
-    pub fn add(&self, object: &Object<'a>) -> Result<bool> {
-        let object = self.env.call_method(
+    pub fn add(&self, value: T) -> Result<bool> {
+        let object = Converted::new(self.env, value)?;
+        let added = Cache::call_method(
+            self.env,
             self.inner.inner,
+            "java/util/List",
             "add",
-            "(Ljava/lang/Object;)Z",
-            &[object.into()],
+            signature!("(Ljava/lang/Object;)Z"),
+            &[object.value()],
         )?;
-        object.z()
+        Ok(added.z()?)
     }
 
     /// Inserts the specified element at the specified position in this list (optional operation).
-    pub fn add_at(&self, object: &Object<'a>, index: i32) -> Result<()> {
-        self.env.call_method(
+    pub fn add_at(&self, value: T, index: i32) -> Result<()> {
+        let object = Converted::new(self.env, value)?;
+        Cache::call_method(
+            self.env,
             self.inner.inner,
+            "java/util/List",
             "add",
-            "(ILjava/lang/Object;)V",
-            &[JValue::Int(index), object.into()],
+            signature!("(ILjava/lang/Object;)V"),
+            &[JValue::Int(index), object.value()],
         )?;
         Ok(())
     }
 
-    /// Removes all of the elements from this list (optional operation).
-    pub fn clear(&self) -> Result<()> {
-        self.env
-            .call_method(self.inner.inner, "clear", "()V", &[])?;
-        Ok(())
-    }
-
     /// Returns true if this list contains the specified element.
-    pub fn contains(&self, object: &Object<'a>) -> Result<bool> {
-        let contains = self.env.call_method(
+    pub fn contains(&self, value: T) -> Result<bool> {
+        let object = Converted::new(self.env, value)?;
+        let contains = Cache::call_method(
+            self.env,
             self.inner.inner,
+            "java/util/List",
             "contains",
-            "(Ljava/lang/Object;)Z",
-            &[object.into()],
-        )?;
-        contains.z()
-    }
-
-    /// Returns the element at the specified position in this list.
-    pub fn get(&self, index: i32) -> Result<Option<Object<'a>>> {
-        let value = self.env.call_method(
-            self.inner.inner,
-            "get",
-            "(I)Ljava/lang/Object;",
-            &[JValue::Int(index)],
+            signature!("(Ljava/lang/Object;)Z"),
+            &[object.value()],
         )?;
-        let maybe_object = value.l()?;
-        match maybe_object.is_null() {
-            true => Ok(None),
-            false => Ok(Some(Object::new(
-                self.env,
-                maybe_object,
-                self.class.clone(),
-            ))),
-        }
+        Ok(contains.z()?)
     }
 
     /// Returns the index of the first occurrence of the specified element in this list, or -1 if this list does not contain the element.
-    pub fn index_of(&self, object: &Object<'a>) -> Result<i32> {
-        let index = self.env.call_method(
+    pub fn index_of(&self, value: T) -> Result<i32> {
+        let object = Converted::new(self.env, value)?;
+        let index = Cache::call_method(
+            self.env,
             self.inner.inner,
+            "java/util/List",
             "indexOf",
-            "(Ljava/lang/Object;)I",
-            &[object.into()],
+            signature!("(Ljava/lang/Object;)I"),
+            &[object.value()],
         )?;
-        index.i()
-    }
-
-    /// Returns true if this list contains no elements.
-    pub fn is_empty(&self) -> Result<bool> {
-        let is_empty = self
-            .env
-            .call_method(self.inner.inner, "isEmpty", "()Z", &[])?;
-        is_empty.z()
+        Ok(index.i()?)
     }
 
     /// Removes the first occurrence of the specified element from this list, if it is present (optional operation).
-    pub fn remove(&self, object: &Object<'a>) -> Result<bool> {
-        let remove = self.env.call_method(
+    pub fn remove(&self, value: T) -> Result<bool> {
+        let object = Converted::new(self.env, value)?;
+        let remove = Cache::call_method(
+            self.env,
             self.inner.inner,
+            "java/util/List",
             "remove",
-            "(Ljava/lang/Object;)Z",
-            &[object.into()],
+            signature!("(Ljava/lang/Object;)Z"),
+            &[object.value()],
         )?;
-        remove.z()
+        Ok(remove.z()?)
     }
+}
 
-    /// Removes the element at the specified position in this list (optional operation).
-    pub fn remove_at(&self, index: i32) -> Result<Option<Object<'a>>> {
-        let value = self.env.call_method(
+impl<'a, T: FromJava<'a>> List<'a, T> {
+    /// Returns the element at the specified position in this list.
+    pub fn get(&self, index: i32) -> Result<Option<T>> {
+        let value = Cache::call_method(
+            self.env,
             self.inner.inner,
-            "remove",
-            "(I)Ljava/lang/Object;",
+            "java/util/List",
+            "get",
+            signature!("(I)Ljava/lang/Object;"),
             &[JValue::Int(index)],
         )?;
-        let maybe_removed = value.l()?;
-        match maybe_removed.is_null() {
-            true => Ok(None),
-            false => Ok(Some(Object::new(
-                self.env,
-                maybe_removed,
-                self.class.clone(),
-            ))),
-        }
+        from_nullable(self.env, value.l()?)
     }
 
-    /// Replaces the element at the specified position in this list with the specified element (optional operation).
-    pub fn set(&self, object: &Object<'a>, index: i32) -> Result<Option<Object<'a>>> {
-        let replaced = self.env.call_method(
+    /// Removes the element at the specified position in this list (optional operation).
+    pub fn remove_at(&self, index: i32) -> Result<Option<T>> {
+        let value = Cache::call_method(
+            self.env,
             self.inner.inner,
-            "set",
-            "(ILjava/lang/Object;)Ljava/lang/Object;",
-            &[JValue::Int(index), object.into()],
+            "java/util/List",
+            "remove",
+            signature!("(I)Ljava/lang/Object;"),
+            &[JValue::Int(index)],
         )?;
-        let maybe_replaced = replaced.l()?;
-        match maybe_replaced.is_null() {
-            true => Ok(None),
-            false => Ok(Some(Object::new(
-                self.env,
-                maybe_replaced,
-                self.class.clone(),
-            ))),
-        }
+        from_nullable(self.env, value.l()?)
     }
 
-    /// Returns the number of elements in this list.
-    pub fn size(&self) -> Result<i32> {
-        let size = self.env.call_method(self.inner.inner, "size", "()I", &[])?;
-        size.i()
+    /// Convert the java.util.List to a Vec
+    pub fn to_vec(&self) -> Result<Vec<T>> {
+        self.iterator()?.to_vec()
     }
+}
 
-    /// Returns a view of the portion of this list between the specified from index, inclusive, and to index, exclusive.
-    pub fn sublist(&self, from: i32, to: i32) -> Result<List<'a>> {
-        let sublist = self.env.call_method(
+impl<'a, T: IntoJava<'a> + FromJava<'a>> List<'a, T> {
+    /// Replaces the element at the specified position in this list with the specified element (optional operation).
+    pub fn set(&self, value: T, index: i32) -> Result<Option<T>> {
+        let object = Converted::new(self.env, value)?;
+        let replaced = Cache::call_method(
+            self.env,
             self.inner.inner,
-            "subList",
-            "(II)Ljava/util/List;",
-            &[JValue::Int(from), JValue::Int(to)],
+            "java/util/List",
+            "set",
+            signature!("(ILjava/lang/Object;)Ljava/lang/Object;"),
+            &[JValue::Int(index), object.value()],
         )?;
-        Ok(Self::new(
-            self.env,
-            Object::new(self.env, sublist.l()?, self.inner.class.clone()),
-            self.class.clone(),
-        ))
-    }
-
-    /// Returns an iterator over the elements in this list in proper sequence.
-    pub fn iterator(&self) -> Result<crate::Iterator<'a>> {
-        let iterator =
-            self.env
-                .call_method(self.inner.inner, "iterator", "()Ljava/util/Iterator;", &[])?;
-        Ok(crate::Iterator::new(
-            self.env,
-            Object::new(self.env, iterator.l()?, Class::Iterator(self.env)?),
-            self.class.clone(),
-        ))
+        from_nullable(self.env, replaced.l()?)
     }
 }
 
@@ -223,25 +280,45 @@ impl<'a> List<'a> {
 mod test {
     use super::List;
     use crate::class::Class;
+    use crate::error::Error;
     use crate::object::Object;
     use crate::test::JVM;
+    use jni::sys::jobject;
+    use std::convert::TryFrom;
 
     #[test]
     fn arraylist() {
         let jvm = JVM.lock().unwrap();
         let env = jvm.attach_current_thread().unwrap();
-        assert!(List::arraylist(&env, Class::Object(&env).unwrap()).is_ok())
+        assert!(List::<Object>::arraylist(&env).is_ok())
+    }
+
+    #[test]
+    fn try_from() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let list = List::<i32>::arraylist(&env).unwrap();
+        list.add(10).unwrap();
+        let inner: jobject = list.into();
+        let object = Object::new(&env, inner.into(), Class::Object(&env).unwrap());
+        let list = List::<i32>::try_from(object).unwrap();
+        assert_eq!(Some(10), list.get(0).unwrap());
+
+        let string = Object::new_string(&env, "Foo").unwrap();
+        assert!(matches!(
+            List::<i32>::try_from(string).map(|_| ()).unwrap_err(),
+            Error::ClassMismatch { .. }
+        ));
     }
 
     #[test]
     fn add() {
         let jvm = JVM.lock().unwrap();
         let env = jvm.attach_current_thread().unwrap();
-        let list = List::arraylist(&env, Class::Integer(&env).unwrap()).unwrap();
+        let list = List::<i32>::arraylist(&env).unwrap();
 
-        let has_changed = list
-            .add(&Object::new_integer_object(&env, 10).unwrap())
-            .unwrap();
+        let has_changed = list.add(10).unwrap();
         assert!(has_changed);
 
         let size = list.size().unwrap();
@@ -252,37 +329,24 @@ mod test {
     fn add_at() {
         let jvm = JVM.lock().unwrap();
         let env = jvm.attach_current_thread().unwrap();
-        let list = List::arraylist(&env, Class::Integer(&env).unwrap()).unwrap();
-        list.add(&Object::new_integer_object(&env, 20).unwrap())
-            .unwrap();
+        let list = List::<i32>::arraylist(&env).unwrap();
+        list.add(20).unwrap();
 
-        list.add_at(&Object::new_integer_object(&env, 10).unwrap(), 0)
-            .unwrap();
+        list.add_at(10, 0).unwrap();
 
         let zeroth = list.get(0).unwrap().unwrap();
-        let zeroth_int = env
-            .call_method(zeroth.inner, "intValue", "()I", &[])
-            .unwrap()
-            .i()
-            .unwrap();
-        assert_eq!(10, zeroth_int);
+        assert_eq!(10, zeroth);
 
         let first = list.get(1).unwrap().unwrap();
-        let first_int = env
-            .call_method(first.inner, "intValue", "()I", &[])
-            .unwrap()
-            .i()
-            .unwrap();
-        assert_eq!(20, first_int);
+        assert_eq!(20, first);
     }
 
     #[test]
     fn clear() {
         let jvm = JVM.lock().unwrap();
         let env = jvm.attach_current_thread().unwrap();
-        let list = List::arraylist(&env, Class::Integer(&env).unwrap()).unwrap();
-        list.add(&Object::new_integer_object(&env, 20).unwrap())
-            .unwrap();
+        let list = List::<i32>::arraylist(&env).unwrap();
+        list.add(20).unwrap();
 
         list.clear().unwrap();
 
@@ -294,35 +358,45 @@ mod test {
     fn contains() {
         let jvm = JVM.lock().unwrap();
         let env = jvm.attach_current_thread().unwrap();
-        let list = List::arraylist(&env, Class::Integer(&env).unwrap()).unwrap();
-        let integer = Object::new_integer_object(&env, 20).unwrap();
-        list.add(&integer).unwrap();
+        let list = List::<i32>::arraylist(&env).unwrap();
+        list.add(20).unwrap();
 
-        let contains = list.contains(&integer).unwrap();
-        assert!(contains)
+        assert!(list.contains(20).unwrap());
+        assert!(!list.contains(10).unwrap());
     }
 
     #[test]
     fn get() {
         let jvm = JVM.lock().unwrap();
         let env = jvm.attach_current_thread().unwrap();
-        let list = List::arraylist(&env, Class::Integer(&env).unwrap()).unwrap();
+        let list = List::<Object>::arraylist(&env).unwrap();
         let integer = Object::new_integer_object(&env, 20).unwrap();
-        list.add(&integer).unwrap();
+        list.add(integer.clone()).unwrap();
+
+        let zeroth = list.get(0).unwrap().unwrap();
+        assert!(integer.equals(&zeroth).unwrap());
+        assert_eq!(20, zeroth.get_integer().unwrap());
+    }
+
+    #[test]
+    fn get_string() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        let list = List::<String>::arraylist(&env).unwrap();
+        list.add("Foo".to_string()).unwrap();
 
         let zeroth = list.get(0).unwrap().unwrap();
-        assert!(integer.equals(&zeroth).unwrap())
+        assert_eq!("Foo", zeroth);
     }
 
     #[test]
     fn index_of() {
         let jvm = JVM.lock().unwrap();
         let env = jvm.attach_current_thread().unwrap();
-        let list = List::arraylist(&env, Class::Integer(&env).unwrap()).unwrap();
-        let integer = Object::new_integer_object(&env, 20).unwrap();
-        list.add(&integer).unwrap();
+        let list = List::<i32>::arraylist(&env).unwrap();
+        list.add(20).unwrap();
 
-        let index_of = list.index_of(&integer).unwrap();
+        let index_of = list.index_of(20).unwrap();
         assert_eq!(0, index_of);
     }
 
@@ -330,7 +404,7 @@ mod test {
     fn is_empty() {
         let jvm = JVM.lock().unwrap();
         let env = jvm.attach_current_thread().unwrap();
-        let list = List::arraylist(&env, Class::Integer(&env).unwrap()).unwrap();
+        let list = List::<i32>::arraylist(&env).unwrap();
 
         let is_empty = list.is_empty().unwrap();
         assert!(is_empty);
@@ -340,11 +414,10 @@ mod test {
     fn remove() {
         let jvm = JVM.lock().unwrap();
         let env = jvm.attach_current_thread().unwrap();
-        let list = List::arraylist(&env, Class::Integer(&env).unwrap()).unwrap();
-        let integer = Object::new_integer_object(&env, 20).unwrap();
-        list.add(&integer).unwrap();
+        let list = List::<i32>::arraylist(&env).unwrap();
+        list.add(20).unwrap();
 
-        let list_changed = list.remove(&integer).unwrap();
+        let list_changed = list.remove(20).unwrap();
         assert!(list_changed);
     }
 
@@ -352,38 +425,31 @@ mod test {
     fn remove_at() {
         let jvm = JVM.lock().unwrap();
         let env = jvm.attach_current_thread().unwrap();
-        let list = List::arraylist(&env, Class::Integer(&env).unwrap()).unwrap();
-        let integer = Object::new_integer_object(&env, 20).unwrap();
-        list.add(&integer).unwrap();
+        let list = List::<i32>::arraylist(&env).unwrap();
+        list.add(20).unwrap();
 
         let removed = list.remove_at(0).unwrap().unwrap();
-        let equals = integer.equals(&removed).unwrap();
-
-        assert!(equals)
+        assert_eq!(20, removed);
     }
 
     #[test]
     fn set() {
         let jvm = JVM.lock().unwrap();
         let env = jvm.attach_current_thread().unwrap();
-        let list = List::arraylist(&env, Class::Integer(&env).unwrap()).unwrap();
-        let integer = Object::new_integer_object(&env, 20).unwrap();
-        list.add(&integer).unwrap();
-
-        let new_integer = Object::new_integer_object(&env, 10).unwrap();
+        let list = List::<i32>::arraylist(&env).unwrap();
+        list.add(20).unwrap();
 
-        let old_integer = list.set(&new_integer, 0).unwrap().unwrap();
-        let equal = integer.equals(&old_integer).unwrap();
-        assert!(equal);
+        let old_integer = list.set(10, 0).unwrap().unwrap();
+        assert_eq!(20, old_integer);
+        assert_eq!(10, list.get(0).unwrap().unwrap());
     }
 
     #[test]
     fn size() {
         let jvm = JVM.lock().unwrap();
         let env = jvm.attach_current_thread().unwrap();
-        let list = List::arraylist(&env, Class::Integer(&env).unwrap()).unwrap();
-        let integer = Object::new_integer_object(&env, 20).unwrap();
-        list.add(&integer).unwrap();
+        let list = List::<i32>::arraylist(&env).unwrap();
+        list.add(20).unwrap();
 
         let size = list.size().unwrap();
         assert_eq!(1, size);
@@ -393,26 +459,35 @@ mod test {
     fn sublist() {
         let jvm = JVM.lock().unwrap();
         let env = jvm.attach_current_thread().unwrap();
-        let list = List::arraylist(&env, Class::Integer(&env).unwrap()).unwrap();
-        list.add(&Object::new_integer_object(&env, 10).unwrap())
-            .unwrap();
-        list.add(&Object::new_integer_object(&env, 20).unwrap())
-            .unwrap();
-        list.add(&Object::new_integer_object(&env, 30).unwrap())
-            .unwrap();
+        let list = List::<i32>::arraylist(&env).unwrap();
+        list.add(10).unwrap();
+        list.add(20).unwrap();
+        list.add(30).unwrap();
 
         let sublist = list.sublist(1, 3).unwrap();
         let size = sublist.size().unwrap();
         assert_eq!(2, size);
+        assert_eq!(20, sublist.get(0).unwrap().unwrap());
     }
 
     #[test]
     fn iterator() {
         let jvm = JVM.lock().unwrap();
         let env = jvm.attach_current_thread().unwrap();
-        let list = List::arraylist(&env, Class::Integer(&env).unwrap()).unwrap();
+        let list = List::<i32>::arraylist(&env).unwrap();
 
         let iterator = list.iterator();
         assert!(iterator.is_ok());
     }
+
+    #[test]
+    fn to_vec() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        let list = List::<i64>::arraylist(&env).unwrap();
+        list.add(10).unwrap();
+        list.add(20).unwrap();
+
+        assert_eq!(vec![10, 20], list.to_vec().unwrap());
+    }
 }
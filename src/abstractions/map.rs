@@ -1,213 +1,271 @@
+use crate::abstractions::map_entry::MapEntry;
 use crate::abstractions::set::Set;
+use crate::cache::Cache;
 use crate::class::Class;
+use crate::error::{Error, Result};
+use crate::java_type::{from_nullable, Converted, FromJava, IntoJava, JavaType};
 use crate::object::Object;
-use jni::errors::Result;
+use crate::signature;
 use jni::objects::JValue;
 use jni::sys::_jobject;
 use jni::JNIEnv;
+use std::convert::TryFrom;
+use std::marker::PhantomData;
 
 /// Wrapper around `java.util.Map`
-pub struct Map<'a> {
+pub struct Map<'a, K = Object<'a>, V = Object<'a>> {
     /// The Map itself
     pub inner: Object<'a>,
-
-    /// The key Class
-    pub k_class: Class<'a>,
-
-    /// The Value Class
-    pub v_class: Class<'a>,
-
     env: &'a JNIEnv<'a>,
+    _types: PhantomData<(K, V)>,
 }
 
 #[allow(clippy::from_over_into)]
-impl<'a> Into<*mut _jobject> for Map<'a> {
+impl<'a, K, V> Into<*mut _jobject> for Map<'a, K, V> {
     fn into(self) -> *mut _jobject {
-        self.inner.inner.into_inner()
+        // The local reference is handed to the caller, so it must not be deleted by Drop
+        let this = std::mem::ManuallyDrop::new(self);
+        this.inner.inner.into_inner()
     }
 }
 
-impl<'a> Drop for Map<'a> {
+impl<'a, K, V> Drop for Map<'a, K, V> {
     fn drop(&mut self) {
         let _ = self.env.delete_local_ref(self.inner.inner);
     }
 }
 
-impl<'a> Map<'a> {
-    /// Create a Map wrapper from an existing Map object. The caller must guarantee that the passed in Object implements Map and is not null.
-    pub fn new(
-        env: &'a JNIEnv<'a>,
-        object: Object<'a>,
-        k_class: Class<'a>,
-        v_class: Class<'a>,
-    ) -> Self {
+impl<'a, K, V> JavaType<'a> for Map<'a, K, V> {
+    fn class(env: &'a JNIEnv<'a>) -> Result<Class<'a>> {
+        Class::Map(env)
+    }
+}
+
+impl<'a, K, V> TryFrom<Object<'a>> for Map<'a, K, V> {
+    type Error = Error;
+
+    /// Fails if the Object is null or does not implement `java.util.Map`, see [Object::cast]
+    fn try_from(object: Object<'a>) -> Result<Self> {
+        object.cast()
+    }
+}
+
+impl<'a, K, V> IntoJava<'a> for Map<'a, K, V> {
+    const OWNED: bool = true;
+
+    fn into_java(self, _: &'a JNIEnv<'a>) -> Result<Object<'a>> {
+        // The local reference is handed to the Object, so it must not be deleted by Drop
+        let this = std::mem::ManuallyDrop::new(self);
+        Ok(this.inner.clone())
+    }
+}
+
+impl<'a, K, V> FromJava<'a> for Map<'a, K, V> {
+    fn from_java(object: Object<'a>) -> Result<Self> {
+        Ok(Self::new(object.env, object))
+    }
+}
+
+impl<'a, K, V> Map<'a, K, V> {
+    /// Create a Map wrapper from an existing Map object. The caller must guarantee that the passed in Object implements Map, that its keys and values are of type `K` and `V` and that it is not null.
+    pub fn new(env: &'a JNIEnv<'a>, object: Object<'a>) -> Self {
         Self {
             inner: object,
-            k_class,
-            v_class,
             env,
+            _types: PhantomData,
         }
     }
 
     /// Constructs an empty HashMap with the default initial capacity (16) and the default load factor (0.75).
-    pub fn hashmap(env: &'a JNIEnv<'a>, k_class: Class<'a>, v_class: Class<'a>) -> Result<Self> {
-        let hashmap = env.new_object("java/util/HashMap", "()V", &[])?;
-        Ok(Self {
-            inner: Object::new(env, hashmap, Class::HashMap(env)?),
-            k_class,
-            v_class,
+    pub fn hashmap(env: &'a JNIEnv<'a>) -> Result<Self> {
+        let hashmap = Cache::new_object(env, "java/util/HashMap", signature!("()V"), &[])?;
+        Ok(Self::new(
             env,
-        })
+            Object::new(env, hashmap, Class::HashMap(env)?),
+        ))
     }
 
     /// Constructs an empty HashMap with the specified initial capacity and the default load factor (0.75).
-    pub fn hashmap_with_capacity(
-        env: &'a JNIEnv<'a>,
-        k_class: Class<'a>,
-        v_class: Class<'a>,
-        initial_capacity: i32,
-    ) -> Result<Self> {
-        let hashmap = env.new_object(
+    pub fn hashmap_with_capacity(env: &'a JNIEnv<'a>, initial_capacity: i32) -> Result<Self> {
+        let hashmap = Cache::new_object(
+            env,
             "java/util/HashMap",
-            "(I)V",
+            signature!("(I)V"),
             &[JValue::Int(initial_capacity)],
         )?;
-        Ok(Self {
-            inner: Object::new(env, hashmap, Class::HashMap(env)?),
-            k_class,
-            v_class,
+        Ok(Self::new(
             env,
-        })
+            Object::new(env, hashmap, Class::HashMap(env)?),
+        ))
     }
 
     /// Constructs an empty HashMap with the specified initial capacity and load factor.
     pub fn hashmap_with_capacity_and_load_factor(
         env: &'a JNIEnv<'a>,
-        k_class: Class<'a>,
-        v_class: Class<'a>,
         initial_capacity: i32,
         load_factor: f32,
     ) -> Result<Self> {
-        let hashmap = env.new_object(
+        let hashmap = Cache::new_object(
+            env,
             "java/util/HashMap",
-            "(IF)V",
+            signature!("(IF)V"),
             &[JValue::Int(initial_capacity), JValue::Float(load_factor)],
         )?;
-        Ok(Self {
-            inner: Object::new(env, hashmap, Class::HashMap(env)?),
-            k_class,
-            v_class,
+        Ok(Self::new(
             env,
-        })
+            Object::new(env, hashmap, Class::HashMap(env)?),
+        ))
     }
 
-    /// Associates the specified value with the specified key in this map (optional operation).
-    pub fn put(&self, key: Object<'a>, value: Object<'a>) -> Result<Option<Object<'a>>> {
-        let prev_value = self.env.call_method(
+    /// Returns true if this map contains no key-value mappings.
+    pub fn is_empty(&self) -> Result<bool> {
+        let is_empty = Cache::call_method(
+            self.env,
             self.inner.inner,
-            "put",
-            "(Ljava/lang/Object;Ljava/lang/Object;)Ljava/lang/Object;",
-            &[key.into(), value.into()],
+            "java/util/Map",
+            "isEmpty",
+            signature!("()Z"),
+            &[],
         )?;
-        let object = Object::new(self.env, prev_value.l()?, self.v_class.clone());
-        match object.inner.is_null() {
-            true => Ok(None),
-            false => Ok(Some(object)),
-        }
+        Ok(is_empty.z()?)
     }
 
-    /// Returns the value to which the specified key is mapped, or null if this map contains no mapping for the key.
-    pub fn get(&self, key: &Object<'a>) -> Result<Option<Object<'a>>> {
-        let value = self.env.call_method(
+    /// Returns the number of key-value mappings in this map.
+    pub fn size(&self) -> Result<i32> {
+        let size = Cache::call_method(
+            self.env,
             self.inner.inner,
-            "get",
-            "(Ljava/lang/Object;)Ljava/lang/Object;",
-            &[key.into()],
+            "java/util/Map",
+            "size",
+            signature!("()I"),
+            &[],
         )?;
-        let object = Object::new(self.env, value.l()?, self.v_class.clone());
-        match object.inner.is_null() {
-            true => Ok(None),
-            false => Ok(Some(object)),
-        }
+        Ok(size.i()?)
     }
 
-    /// Returns true if this map contains no key-value mappings.
-    pub fn is_empty(&self) -> Result<bool> {
-        let is_empty = self
-            .env
-            .call_method(self.inner.inner, "isEmpty", "()Z", &[])?;
-        is_empty.z()
+    /// Returns a Set<Map.Entry<K, V>> view of the mappings contained in this map.
+    pub fn entry_set(&self) -> Result<Set<'a, MapEntry<'a, K, V>>> {
+        let entry_set = Cache::call_method(
+            self.env,
+            self.inner.inner,
+            "java/util/Map",
+            "entrySet",
+            signature!("()Ljava/util/Set;"),
+            &[],
+        )?;
+        let object = Object::new(self.env, entry_set.l()?, Class::Set(self.env)?);
+        let set = Set::new(self.env, object);
+        Ok(set)
     }
 
-    /// Returns the number of key-value mappings in this map.
-    pub fn size(&self) -> Result<i32> {
-        let size = self.env.call_method(self.inner.inner, "size", "()I", &[])?;
-        size.i()
+    /// Removes all of the mappings from this map.
+    pub fn clear(&self) -> Result<()> {
+        Cache::call_method(
+            self.env,
+            self.inner.inner,
+            "java/util/Map",
+            "clear",
+            signature!("()V"),
+            &[],
+        )?;
+        Ok(())
     }
+}
 
+impl<'a, K: IntoJava<'a>, V> Map<'a, K, V> {
     /// Returns true if this map contains a mapping for the specified key.
-    pub fn contains_key(&self, key: &Object<'a>) -> Result<bool> {
-        let contains_key = self.env.call_method(
+    pub fn contains_key(&self, key: K) -> Result<bool> {
+        let key = Converted::new(self.env, key)?;
+        let contains_key = Cache::call_method(
+            self.env,
             self.inner.inner,
+            "java/util/Map",
             "containsKey",
-            "(Ljava/lang/Object;)Z",
-            &[key.into()],
+            signature!("(Ljava/lang/Object;)Z"),
+            &[key.value()],
         )?;
-        contains_key.z()
+        Ok(contains_key.z()?)
     }
+}
 
+impl<'a, K, V: IntoJava<'a>> Map<'a, K, V> {
     /// Returns true if this map maps one or more keys to the specified value.
-    pub fn contains_value(&self, value: &Object<'a>) -> Result<bool> {
-        let contains_value = self.env.call_method(
+    pub fn contains_value(&self, value: V) -> Result<bool> {
+        let value = Converted::new(self.env, value)?;
+        let contains_value = Cache::call_method(
+            self.env,
             self.inner.inner,
+            "java/util/Map",
             "containsValue",
-            "(Ljava/lang/Object;)Z",
-            &[value.into()],
+            signature!("(Ljava/lang/Object;)Z"),
+            &[value.value()],
         )?;
-        contains_value.z()
+        Ok(contains_value.z()?)
     }
+}
 
-    /// Removes the mapping for a key from this map if it is present (optional operation).
-    pub fn remove(&self, key: &Object<'a>) -> Result<Option<Object<'a>>> {
-        let removed_value = self.env.call_method(
+impl<'a, K: IntoJava<'a>, V: IntoJava<'a>> Map<'a, K, V> {
+    /// Removes the entry for the specified key only if it is currently mapped to the specified value.
+    pub fn remove_if_mapped(&self, key: K, value: V) -> Result<bool> {
+        let key = Converted::new(self.env, key)?;
+        let value = Converted::new(self.env, value)?;
+        let removed = Cache::call_method(
+            self.env,
             self.inner.inner,
+            "java/util/Map",
             "remove",
-            "(Ljava/lang/Object;)Ljava/lang/Object;",
-            &[key.into()],
+            signature!("(Ljava/lang/Object;Ljava/lang/Object;)Z"),
+            &[key.value(), value.value()],
         )?;
-        let object = Object::new(self.env, removed_value.l()?, self.v_class.clone());
-        match object.inner.is_null() {
-            true => Ok(None),
-            false => Ok(Some(object)),
-        }
+        Ok(removed.z()?)
     }
+}
 
-    /// Removes the entry for the specified key only if it is currently mapped to the specified value.
-    pub fn remove_if_mapped(&self, key: &Object<'a>, value: &Object<'a>) -> Result<bool> {
-        let removed = self.env.call_method(
+impl<'a, K: IntoJava<'a>, V: FromJava<'a>> Map<'a, K, V> {
+    /// Returns the value to which the specified key is mapped, or None if this map contains no mapping for the key.
+    pub fn get(&self, key: K) -> Result<Option<V>> {
+        let key = Converted::new(self.env, key)?;
+        let value = Cache::call_method(
+            self.env,
             self.inner.inner,
-            "remove",
-            "(Ljava/lang/Object;Ljava/lang/Object;)Z",
-            &[key.into(), value.into()],
+            "java/util/Map",
+            "get",
+            signature!("(Ljava/lang/Object;)Ljava/lang/Object;"),
+            &[key.value()],
         )?;
-        removed.z()
+        from_nullable(self.env, value.l()?)
     }
 
-    /// Returns a Set<Map.Entry<K, V>> view of the mappings contained in this map.
-    pub fn entry_set(&self) -> Result<Set<'a>> {
-        let entry_set =
-            self.env
-                .call_method(self.inner.inner, "entrySet", "()Ljava/util/Set;", &[])?;
-        let object = Object::new(self.env, entry_set.l()?, Class::Set(self.env)?);
-        let set = Set::new(self.env, object, Class::MapEntry(self.env)?);
-        Ok(set)
+    /// Removes the mapping for a key from this map if it is present (optional operation).
+    pub fn remove(&self, key: K) -> Result<Option<V>> {
+        let key = Converted::new(self.env, key)?;
+        let removed_value = Cache::call_method(
+            self.env,
+            self.inner.inner,
+            "java/util/Map",
+            "remove",
+            signature!("(Ljava/lang/Object;)Ljava/lang/Object;"),
+            &[key.value()],
+        )?;
+        from_nullable(self.env, removed_value.l()?)
     }
+}
 
-    /// Removes all of the mappings from this map.
-    pub fn clear(&self) -> Result<()> {
-        self.env
-            .call_method(self.inner.inner, "clear", "()V", &[])?;
-        Ok(())
+impl<'a, K: IntoJava<'a>, V: IntoJava<'a> + FromJava<'a>> Map<'a, K, V> {
+    /// Associates the specified value with the specified key in this map (optional operation).
+    /// Returns the previous value associated with the key, if any.
+    pub fn put(&self, key: K, value: V) -> Result<Option<V>> {
+        let key = Converted::new(self.env, key)?;
+        let value = Converted::new(self.env, value)?;
+        let prev_value = Cache::call_method(
+            self.env,
+            self.inner.inner,
+            "java/util/Map",
+            "put",
+            signature!("(Ljava/lang/Object;Ljava/lang/Object;)Ljava/lang/Object;"),
+            &[key.value(), value.value()],
+        )?;
+        from_nullable(self.env, prev_value.l()?)
     }
 }
 
@@ -220,58 +278,59 @@ mod test {
     fn hashmap() {
         let jvm = JVM.lock().unwrap();
         let env = jvm.attach_current_thread().unwrap();
-        let int_class = Class::Integer(&env).unwrap();
 
-        assert!(Map::hashmap(&env, int_class.clone(), int_class.clone()).is_ok());
-        assert!(Map::hashmap_with_capacity(&env, int_class.clone(), int_class.clone(), 32).is_ok());
-        assert!(Map::hashmap_with_capacity_and_load_factor(
-            &env,
-            int_class.clone(),
-            int_class,
-            32,
-            32.5
-        )
-        .is_ok());
+        assert!(Map::<i32, i32>::hashmap(&env).is_ok());
+        assert!(Map::<i32, i32>::hashmap_with_capacity(&env, 32).is_ok());
+        assert!(Map::<i32, i32>::hashmap_with_capacity_and_load_factor(&env, 32, 32.5).is_ok());
     }
 
     #[test]
     fn put() {
         let jvm = JVM.lock().unwrap();
         let env = jvm.attach_current_thread().unwrap();
-        let int_class = Class::Integer(&env).unwrap();
-        let map = Map::hashmap(&env, int_class.clone(), int_class).unwrap();
+        let map = Map::<i32, i32>::hashmap(&env).unwrap();
 
-        let put_result = map.put(
-            Object::new_integer_object(&env, 1).unwrap(),
-            Object::new_integer_object(&env, 10).unwrap(),
-        );
+        let put_result = map.put(1, 10);
         assert!(put_result.is_ok());
+        assert!(put_result.unwrap().is_none());
+
+        let previous = map.put(1, 20).unwrap();
+        assert_eq!(Some(10), previous);
     }
 
     #[test]
     fn get() {
         let jvm = JVM.lock().unwrap();
         let env = jvm.attach_current_thread().unwrap();
-        let int_class = Class::Integer(&env).unwrap();
-        let map = Map::hashmap(&env, int_class.clone(), int_class).unwrap();
+        let map = Map::<i32, i32>::hashmap(&env).unwrap();
 
-        let key = Object::new_integer_object(&env, 1).unwrap();
+        map.put(1, 10).unwrap();
+        let gotten = map.get(1).unwrap();
+        assert!(gotten.is_some());
+        assert_eq!(10, gotten.unwrap());
+
+        assert!(map.get(2).unwrap().is_none());
+    }
+
+    #[test]
+    fn get_object() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        let map = Map::<Object, Object>::hashmap(&env).unwrap();
 
-        map.put(key.clone(), Object::new_integer_object(&env, 10).unwrap())
+        let key = Object::new_integer_object(&env, 1).unwrap();
+        map.put(key.clone(), Object::new_string(&env, "Foo").unwrap())
             .unwrap();
-        let gotten = map.get(&key).unwrap();
-        assert!(gotten.is_some());
 
-        let gotten = gotten.unwrap().get_integer().unwrap();
-        assert_eq!(10, gotten);
+        let gotten = map.get(key).unwrap().unwrap();
+        assert_eq!("java.lang.String", gotten.class.get_name().unwrap());
     }
 
     #[test]
     fn is_empty() {
         let jvm = JVM.lock().unwrap();
         let env = jvm.attach_current_thread().unwrap();
-        let int_class = Class::Integer(&env).unwrap();
-        let map = Map::hashmap(&env, int_class.clone(), int_class).unwrap();
+        let map = Map::<i32, i32>::hashmap(&env).unwrap();
 
         let is_empty = map.is_empty();
         assert!(is_empty.is_ok());
@@ -283,14 +342,9 @@ mod test {
     fn size() {
         let jvm = JVM.lock().unwrap();
         let env = jvm.attach_current_thread().unwrap();
-        let int_class = Class::Integer(&env).unwrap();
-        let map = Map::hashmap(&env, int_class.clone(), int_class).unwrap();
+        let map = Map::<i32, i32>::hashmap(&env).unwrap();
 
-        map.put(
-            Object::new_integer_object(&env, 1).unwrap(),
-            Object::new_integer_object(&env, 10).unwrap(),
-        )
-        .unwrap();
+        map.put(1, 10).unwrap();
 
         let size = map.size();
         assert!(size.is_ok());
@@ -303,15 +357,11 @@ mod test {
     fn contains_key() {
         let jvm = JVM.lock().unwrap();
         let env = jvm.attach_current_thread().unwrap();
-        let int_class = Class::Integer(&env).unwrap();
-        let map = Map::hashmap(&env, int_class.clone(), int_class).unwrap();
-
-        let key = Object::new_integer_object(&env, 1).unwrap();
+        let map = Map::<String, i32>::hashmap(&env).unwrap();
 
-        map.put(key.clone(), Object::new_integer_object(&env, 10).unwrap())
-            .unwrap();
+        map.put("Foo".to_string(), 10).unwrap();
 
-        let contains_key = map.contains_key(&key);
+        let contains_key = map.contains_key("Foo".to_string());
         assert!(contains_key.is_ok());
 
         let contains_key = contains_key.unwrap();
@@ -322,15 +372,11 @@ mod test {
     fn contains_value() {
         let jvm = JVM.lock().unwrap();
         let env = jvm.attach_current_thread().unwrap();
-        let int_class = Class::Integer(&env).unwrap();
-        let map = Map::hashmap(&env, int_class.clone(), int_class).unwrap();
-
-        let value = Object::new_integer_object(&env, 10).unwrap();
+        let map = Map::<i32, i32>::hashmap(&env).unwrap();
 
-        map.put(Object::new_integer_object(&env, 1).unwrap(), value.clone())
-            .unwrap();
+        map.put(1, 10).unwrap();
 
-        let contains_value = map.contains_value(&value);
+        let contains_value = map.contains_value(10);
         assert!(contains_value.is_ok());
 
         let contains_value = contains_value.unwrap();
@@ -341,53 +387,49 @@ mod test {
     fn remove() {
         let jvm = JVM.lock().unwrap();
         let env = jvm.attach_current_thread().unwrap();
-        let int_class = Class::Integer(&env).unwrap();
-        let map = Map::hashmap(&env, int_class.clone(), int_class).unwrap();
+        let map = Map::<i32, i32>::hashmap(&env).unwrap();
 
-        let key = Object::new_integer_object(&env, 1).unwrap();
-        let value = Object::new_integer_object(&env, 10).unwrap();
-
-        map.put(key.clone(), value.clone()).unwrap();
+        map.put(1, 10).unwrap();
 
-        let removed = map.remove(&key);
+        let removed = map.remove(1);
         assert!(removed.is_ok());
 
         let removed = removed.unwrap();
         assert!(removed.is_some());
-
-        let removed = removed.unwrap();
-        assert!(value.equals(&removed).unwrap())
+        assert_eq!(10, removed.unwrap());
     }
 
     #[test]
     fn entry_set() {
         let jvm = JVM.lock().unwrap();
         let env = jvm.attach_current_thread().unwrap();
-        let int_class = Class::Integer(&env).unwrap();
-        let map = Map::hashmap(&env, int_class.clone(), int_class).unwrap();
+        let map = Map::<i32, String>::hashmap(&env).unwrap();
+        map.put(1, "Foo".to_string()).unwrap();
 
         let entry_set = map.entry_set();
         assert!(entry_set.is_ok());
+
+        let entries = entry_set.unwrap().to_vec().unwrap();
+        assert_eq!(1, entries.len());
+
+        let entry = entries.first().unwrap();
+        assert_eq!(Some(1), entry.get_key().unwrap());
+        assert_eq!(Some("Foo".to_string()), entry.get_value().unwrap());
     }
 
     #[test]
     fn remove_if_mapped() {
         let jvm = JVM.lock().unwrap();
         let env = jvm.attach_current_thread().unwrap();
-        let int_class = Class::Integer(&env).unwrap();
-        let map = Map::hashmap(&env, int_class.clone(), int_class).unwrap();
+        let map = Map::<i32, i32>::hashmap(&env).unwrap();
 
-        let key = Object::new_integer_object(&env, 1).unwrap();
-        let value = Object::new_integer_object(&env, 10).unwrap();
-
-        map.put(key.clone(), value.clone()).unwrap();
+        map.put(1, 10).unwrap();
         assert_eq!(1, map.size().unwrap());
 
-        let other_value = Object::new_integer_object(&env, 25).unwrap();
-        assert!(map.remove_if_mapped(&key, &other_value).is_ok());
+        assert!(map.remove_if_mapped(1, 25).is_ok());
         assert_eq!(1, map.size().unwrap());
 
-        assert!(map.remove_if_mapped(&key, &value).unwrap());
+        assert!(map.remove_if_mapped(1, 10).unwrap());
         assert_eq!(0, map.size().unwrap());
     }
 
@@ -395,14 +437,9 @@ mod test {
     fn clear() {
         let jvm = JVM.lock().unwrap();
         let env = jvm.attach_current_thread().unwrap();
-        let int_class = Class::Integer(&env).unwrap();
-        let map = Map::hashmap(&env, int_class.clone(), int_class).unwrap();
-
-        map.put(
-            Object::new_integer_object(&env, 1).unwrap(),
-            Object::new_integer_object(&env, 10).unwrap(),
-        )
-        .unwrap();
+        let map = Map::<i32, i32>::hashmap(&env).unwrap();
+
+        map.put(1, 10).unwrap();
 
         let old_size = map.size().unwrap();
         assert_eq!(1, old_size);
@@ -1,73 +1,85 @@
+use crate::cache::Cache;
 use crate::class::Class;
+use crate::error::Result;
+use crate::java_type::{from_nullable, FromJava, JavaType};
 use crate::object::Object;
-use jni::errors::Result;
+use crate::signature;
 use jni::sys::_jobject;
 use jni::JNIEnv;
+use std::marker::PhantomData;
 
 /// Wrapper around `java.util.Map.Entry`
-pub struct MapEntry<'a> {
+pub struct MapEntry<'a, K = Object<'a>, V = Object<'a>> {
     /// The Map.Entry itself
     pub inner: Object<'a>,
-
-    /// The key Class
-    pub k_class: Class<'a>,
-
-    /// The value Class
-    pub v_class: Class<'a>,
-
     env: &'a JNIEnv<'a>,
+    _types: PhantomData<(K, V)>,
 }
 
 #[allow(clippy::from_over_into)]
-impl<'a> Into<*mut _jobject> for MapEntry<'a> {
+impl<'a, K, V> Into<*mut _jobject> for MapEntry<'a, K, V> {
     fn into(self) -> *mut _jobject {
-        self.inner.inner.into_inner()
+        // The local reference is handed to the caller, so it must not be deleted by Drop
+        let this = std::mem::ManuallyDrop::new(self);
+        this.inner.inner.into_inner()
     }
 }
 
-impl<'a> Drop for MapEntry<'a> {
+impl<'a, K, V> Drop for MapEntry<'a, K, V> {
     fn drop(&mut self) {
         let _ = self.env.delete_local_ref(self.inner.inner);
     }
 }
 
-impl<'a> MapEntry<'a> {
-    /// Create a new Map.Entry wrapper. The caller must guarantee that the object passed in implements Map.Entry and that it is not null
-    pub fn new(
-        env: &'a JNIEnv<'a>,
-        object: Object<'a>,
-        k_class: Class<'a>,
-        v_class: Class<'a>,
-    ) -> Self {
+impl<'a, K, V> JavaType<'a> for MapEntry<'a, K, V> {
+    fn class(env: &'a JNIEnv<'a>) -> Result<Class<'a>> {
+        Class::MapEntry(env)
+    }
+}
+
+impl<'a, K, V> FromJava<'a> for MapEntry<'a, K, V> {
+    fn from_java(object: Object<'a>) -> Result<Self> {
+        Ok(Self::new(object.env, object))
+    }
+}
+
+impl<'a, K, V> MapEntry<'a, K, V> {
+    /// Create a new Map.Entry wrapper. The caller must guarantee that the object passed in implements Map.Entry, that its key and value are of type `K` and `V` and that it is not null
+    pub fn new(env: &'a JNIEnv<'a>, object: Object<'a>) -> Self {
         Self {
             inner: object,
-            k_class,
-            v_class,
             env,
+            _types: PhantomData,
         }
     }
+}
 
+impl<'a, K: FromJava<'a>, V> MapEntry<'a, K, V> {
     /// Returns the key corresponding to this entry.
-    pub fn get_key(&self) -> Result<Option<Object<'a>>> {
-        let key = self
-            .env
-            .call_method(self.inner.inner, "getKey", "()Ljava/lang/Object;", &[])?;
-        let object = Object::new(self.env, key.l()?, self.k_class.clone());
-        match object.inner.is_null() {
-            true => Ok(None),
-            false => Ok(Some(object)),
-        }
+    pub fn get_key(&self) -> Result<Option<K>> {
+        let key = Cache::call_method(
+            self.env,
+            self.inner.inner,
+            "java/util/Map$Entry",
+            "getKey",
+            signature!("()Ljava/lang/Object;"),
+            &[],
+        )?;
+        from_nullable(self.env, key.l()?)
     }
+}
 
+impl<'a, K, V: FromJava<'a>> MapEntry<'a, K, V> {
     /// Returns the value corresponding to this entry.
-    pub fn get_value(&self) -> Result<Option<Object<'a>>> {
-        let value =
-            self.env
-                .call_method(self.inner.inner, "getValue", "()Ljava/lang/Object;", &[])?;
-        let object = Object::new(self.env, value.l()?, self.v_class.clone());
-        match object.inner.is_null() {
-            true => Ok(None),
-            false => Ok(Some(object)),
-        }
+    pub fn get_value(&self) -> Result<Option<V>> {
+        let value = Cache::call_method(
+            self.env,
+            self.inner.inner,
+            "java/util/Map$Entry",
+            "getValue",
+            signature!("()Ljava/lang/Object;"),
+            &[],
+        )?;
+        from_nullable(self.env, value.l()?)
     }
 }
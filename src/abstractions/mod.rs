@@ -18,3 +18,9 @@ pub use list::*;
 
 mod system;
 pub use system::*;
+
+mod byte_buffer;
+pub use byte_buffer::*;
+
+mod class_loader;
+pub use class_loader::*;
@@ -1,110 +1,183 @@
 use crate::abstractions::iterator::Iterator;
+use crate::cache::Cache;
 use crate::class::Class;
+use crate::error::{Error, Result};
+use crate::java_type::{Converted, FromJava, IntoJava, JavaType};
 use crate::object::Object;
-use jni::errors::Result;
+use crate::signature;
 use jni::objects::JValue;
 use jni::sys::_jobject;
 use jni::JNIEnv;
+use std::convert::TryFrom;
+use std::marker::PhantomData;
 
 /// Wrapper around `java.util.Set`
-pub struct Set<'a> {
+pub struct Set<'a, T = Object<'a>> {
     /// The Set itself
     pub inner: Object<'a>,
-
-    /// The Class contained in the Set
-    pub class: Class<'a>,
-
     env: &'a JNIEnv<'a>,
+    _type: PhantomData<T>,
 }
 
 #[allow(clippy::from_over_into)]
-impl<'a> Into<*mut _jobject> for Set<'a> {
+impl<'a, T> Into<*mut _jobject> for Set<'a, T> {
     fn into(self) -> *mut _jobject {
-        self.inner.inner.into_inner()
+        // The local reference is handed to the caller, so it must not be deleted by Drop
+        let this = std::mem::ManuallyDrop::new(self);
+        this.inner.inner.into_inner()
     }
 }
 
-impl<'a> Drop for Set<'a> {
+impl<'a, T> Drop for Set<'a, T> {
     fn drop(&mut self) {
         let _ = self.env.delete_local_ref(self.inner.inner);
     }
 }
 
-impl<'a> Set<'a> {
-    /// Create a new Set. The caller must guarantee that the passed in Object implements Set and is not null.
-    pub fn new(env: &'a JNIEnv<'a>, object: Object<'a>, class: Class<'a>) -> Self {
+impl<'a, T> JavaType<'a> for Set<'a, T> {
+    fn class(env: &'a JNIEnv<'a>) -> Result<Class<'a>> {
+        Class::Set(env)
+    }
+}
+
+impl<'a, T> TryFrom<Object<'a>> for Set<'a, T> {
+    type Error = Error;
+
+    /// Fails if the Object is null or does not implement `java.util.Set`, see [Object::cast]
+    fn try_from(object: Object<'a>) -> Result<Self> {
+        object.cast()
+    }
+}
+
+impl<'a, T> IntoJava<'a> for Set<'a, T> {
+    const OWNED: bool = true;
+
+    fn into_java(self, _: &'a JNIEnv<'a>) -> Result<Object<'a>> {
+        // The local reference is handed to the Object, so it must not be deleted by Drop
+        let this = std::mem::ManuallyDrop::new(self);
+        Ok(this.inner.clone())
+    }
+}
+
+impl<'a, T> FromJava<'a> for Set<'a, T> {
+    fn from_java(object: Object<'a>) -> Result<Self> {
+        Ok(Self::new(object.env, object))
+    }
+}
+
+impl<'a, T> Set<'a, T> {
+    /// Create a new Set. The caller must guarantee that the passed in Object implements Set, that it contains elements of type `T` and is not null.
+    pub fn new(env: &'a JNIEnv<'a>, object: Object<'a>) -> Self {
         Self {
             inner: object,
-            class,
             env,
+            _type: PhantomData,
         }
     }
 
     /// Constructs a new, empty set; the backing HashMap instance has default initial capacity (16) and load factor (0.75).
-    pub fn hashset(env: &'a JNIEnv<'a>, v_class: Class<'a>) -> Result<Self> {
-        let hashset = env.new_object("java/util/HashSet", "()V", &[])?;
-        Ok(Self {
-            inner: Object::new(env, hashset, Class::HashSet(env)?),
-            class: v_class,
+    pub fn hashset(env: &'a JNIEnv<'a>) -> Result<Self> {
+        let hashset = Cache::new_object(env, "java/util/HashSet", signature!("()V"), &[])?;
+        Ok(Self::new(
             env,
-        })
+            Object::new(env, hashset, Class::HashSet(env)?),
+        ))
     }
 
     /// Constructs a new, empty set; the backing HashMap instance has the specified initial capacity and default load factor (0.75).
-    pub fn hashset_with_capacity(
-        env: &'a JNIEnv<'a>,
-        v_class: Class<'a>,
-        initial_capacity: i32,
-    ) -> Result<Self> {
-        let hashset = env.new_object(
+    pub fn hashset_with_capacity(env: &'a JNIEnv<'a>, initial_capacity: i32) -> Result<Self> {
+        let hashset = Cache::new_object(
+            env,
             "java/util/HashSet",
-            "(I)V",
+            signature!("(I)V"),
             &[JValue::Int(initial_capacity)],
         )?;
-        Ok(Self {
-            inner: Object::new(env, hashset, Class::HashSet(env)?),
-            class: v_class,
+        Ok(Self::new(
             env,
-        })
+            Object::new(env, hashset, Class::HashSet(env)?),
+        ))
     }
 
     /// Constructs a new, empty set; the backing HashMap instance has the specified initial capacity and the specified load factor.
     pub fn hashset_with_capacity_and_load_factor(
         env: &'a JNIEnv<'a>,
-        v_class: Class<'a>,
         initial_capacity: i32,
         load_factor: f32,
     ) -> Result<Self> {
-        let hashset = env.new_object(
+        let hashset = Cache::new_object(
+            env,
             "java/util/HashSet",
-            "(IF)V",
+            signature!("(IF)V"),
             &[JValue::Int(initial_capacity), JValue::Float(load_factor)],
         )?;
-        Ok(Self {
-            inner: Object::new(env, hashset, Class::HashSet(env)?),
-            class: v_class,
+        Ok(Self::new(
             env,
-        })
+            Object::new(env, hashset, Class::HashSet(env)?),
+        ))
     }
 
     /// Returns the number of elements in this set (its cardinality).
     pub fn size(&self) -> Result<i32> {
-        let size = self.env.call_method(self.inner.inner, "size", "()I", &[])?;
-        size.i()
+        let size = Cache::call_method(
+            self.env,
+            self.inner.inner,
+            "java/util/Set",
+            "size",
+            signature!("()I"),
+            &[],
+        )?;
+        Ok(size.i()?)
     }
 
     /// Returns an iterator over the elements in this set.
-    pub fn iterator(&self) -> Result<Iterator<'a>> {
-        let iterator =
-            self.env
-                .call_method(self.inner.inner, "iterator", "()Ljava/util/Iterator;", &[])?;
+    pub fn iterator(&self) -> Result<Iterator<'a, T>> {
+        let iterator = Cache::call_method(
+            self.env,
+            self.inner.inner,
+            "java/util/Set",
+            "iterator",
+            signature!("()Ljava/util/Iterator;"),
+            &[],
+        )?;
         let object = Object::new(self.env, iterator.l()?, Class::Iterator(self.env)?);
-        let iterator = Iterator::new(self.env, object, self.class.clone());
+        let iterator = Iterator::new(self.env, object);
         Ok(iterator)
     }
+}
+
+impl<'a, T: IntoJava<'a>> Set<'a, T> {
+    /// Adds the specified element to this set if it is not already present (optional operation).
+    pub fn add(&self, value: T) -> Result<bool> {
+        let object = Converted::new(self.env, value)?;
+        let added = Cache::call_method(
+            self.env,
+            self.inner.inner,
+            "java/util/Set",
+            "add",
+            signature!("(Ljava/lang/Object;)Z"),
+            &[object.value()],
+        )?;
+        Ok(added.z()?)
+    }
 
+    /// Returns true if this set contains the specified element.
+    pub fn contains(&self, value: T) -> Result<bool> {
+        let object = Converted::new(self.env, value)?;
+        let contains = Cache::call_method(
+            self.env,
+            self.inner.inner,
+            "java/util/Set",
+            "contains",
+            signature!("(Ljava/lang/Object;)Z"),
+            &[object.value()],
+        )?;
+        Ok(contains.z()?)
+    }
+}
+
+impl<'a, T: FromJava<'a>> Set<'a, T> {
     /// Convert the java.util.Set to a Vec
-    pub fn to_vec(&self) -> Result<Vec<Object<'a>>> {
+    pub fn to_vec(&self) -> Result<Vec<T>> {
         let mut vec = Vec::new();
         let iter = self.iterator()?;
         while let Some(i) = iter.next()? {
@@ -114,3 +187,41 @@ impl<'a> Set<'a> {
         Ok(vec)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::JVM;
+
+    #[test]
+    fn hashset() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        assert!(Set::<i32>::hashset(&env).is_ok());
+        assert!(Set::<i32>::hashset_with_capacity(&env, 32).is_ok());
+        assert!(Set::<i32>::hashset_with_capacity_and_load_factor(&env, 32, 0.5).is_ok());
+    }
+
+    #[test]
+    fn add() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        let set = Set::<String>::hashset(&env).unwrap();
+
+        assert!(set.add("Foo".to_string()).unwrap());
+        assert!(!set.add("Foo".to_string()).unwrap());
+        assert_eq!(1, set.size().unwrap());
+        assert!(set.contains("Foo".to_string()).unwrap());
+    }
+
+    #[test]
+    fn to_vec() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        let set = Set::<i32>::hashset(&env).unwrap();
+        set.add(10).unwrap();
+
+        assert_eq!(vec![10], set.to_vec().unwrap());
+    }
+}
@@ -1,6 +1,6 @@
 use crate::class::Class;
+use crate::error::{JniResultExt, Result};
 use crate::object::Object;
-use jni::errors::Result;
 use jni::objects::{JString, JValue};
 use jni::strings::JNIString;
 use jni::sys::_jobject;
@@ -36,7 +36,7 @@ impl<'a> JavaString<'a> {
 
     /// Turn a Rust String into a JavaString
     pub fn from_rust<S: Into<JNIString>>(env: &'a JNIEnv<'a>, s: S) -> Result<Self> {
-        let string = env.new_string(s)?;
+        let string = env.new_string(s).catch(env)?;
         Ok(Self::new(
             env,
             Object::new(env, string.into(), Class::String(env)?),
@@ -1,5 +1,6 @@
-use crate::{Class, JavaString, Map, Object};
-use jni::errors::Result;
+use crate::error::Result;
+use crate::signature;
+use crate::{Cache, Class, JavaString, Map, Object};
 use jni::objects::JValue;
 use jni::JNIEnv;
 
@@ -17,16 +18,14 @@ impl<'a> System<'a> {
     /// Removes the system property indicated by the specified key.
     pub fn clear_property<S: AsRef<str>>(&self, key: S) -> Result<Option<String>> {
         let key_jstring = JavaString::from_rust(self.env, key)?;
-        let class = Class::System(self.env)?.class;
-        let prop = self
-            .env
-            .call_static_method(
-                class,
-                "clearProperty",
-                "(Ljava/lang/String;)Ljava/lang/String;",
-                &[key_jstring.into()],
-            )?
-            .l()?;
+        let prop = Cache::call_static_method(
+            self.env,
+            "java/lang/System",
+            "clearProperty",
+            signature!("(Ljava/lang/String;)Ljava/lang/String;"),
+            &[key_jstring.into()],
+        )?
+        .l()?;
         if prop.is_null() {
             return Ok(None);
         }
@@ -41,55 +40,60 @@ impl<'a> System<'a> {
 
     /// Returns the current time in milliseconds.
     pub fn current_time_millis(&self) -> Result<i64> {
-        let class = Class::System(self.env)?.class;
-        let value = self
-            .env
-            .call_static_method(class, "currentTimeMillis", "()J", &[])?;
-        value.j()
+        let value = Cache::call_static_method(
+            self.env,
+            "java/lang/System",
+            "currentTimeMillis",
+            signature!("()J"),
+            &[],
+        )?;
+        Ok(value.j()?)
     }
 
     /// Terminates the currently running Java Virtual Machine.
     pub fn exit(&self, status: i32) -> Result<()> {
-        let class = Class::System(self.env)?.class;
-        self.env
-            .call_static_method(class, "exit", "(I)V", &[JValue::Int(status)])?;
+        Cache::call_static_method(
+            self.env,
+            "java/lang/System",
+            "exit",
+            signature!("(I)V"),
+            &[JValue::Int(status)],
+        )?;
         Ok(())
     }
 
     /// Runs the garbage collector.
     pub fn gc(&self) -> Result<()> {
-        let class = Class::System(self.env)?.class;
-        self.env.call_static_method(class, "gc", "()V", &[])?;
+        Cache::call_static_method(self.env, "java/lang/System", "gc", signature!("()V"), &[])?;
         Ok(())
     }
 
     /// Returns an unmodifiable string map view of the current system environment.
-    pub fn get_env(&self) -> Result<Map<'a>> {
-        let class = Class::System(self.env)?.class;
-        let value = self
-            .env
-            .call_static_method(class, "getenv", "()Ljava/util/Map;", &[])?
-            .l()?;
+    pub fn get_env(&self) -> Result<Map<'a, String, String>> {
+        let value = Cache::call_static_method(
+            self.env,
+            "java/lang/System",
+            "getenv",
+            signature!("()Ljava/util/Map;"),
+            &[],
+        )?
+        .l()?;
 
         let object = Object::new(self.env, value, Class::Map(self.env)?);
-        let string_class = Class::String(self.env)?;
-        let map = Map::new(self.env, object, string_class.clone(), string_class);
-        Ok(map)
+        Ok(Map::new(self.env, object))
     }
 
     /// Gets the value of the specified environment variable.
     pub fn get_env_with_name<S: AsRef<str>>(&self, name: S) -> Result<Option<String>> {
         let jstring = JavaString::from_rust(self.env, name)?;
-        let class = Class::System(self.env)?;
-        let value = self
-            .env
-            .call_static_method(
-                class,
-                "getenv",
-                "(Ljava/lang/String;)Ljava/lang/String;",
-                &[jstring.into()],
-            )?
-            .l()?;
+        let value = Cache::call_static_method(
+            self.env,
+            "java/lang/System",
+            "getenv",
+            signature!("(Ljava/lang/String;)Ljava/lang/String;"),
+            &[jstring.into()],
+        )?
+        .l()?;
 
         if value.is_null() {
             return Ok(None);
@@ -103,16 +107,14 @@ impl<'a> System<'a> {
     /// Gets the system property indicated by the specified key.
     pub fn get_property<S: AsRef<str>>(&self, key: S) -> Result<Option<String>> {
         let jstring = JavaString::from_rust(self.env, key)?;
-        let class = Class::System(self.env)?.class;
-        let value = self
-            .env
-            .call_static_method(
-                class,
-                "getProperty",
-                "(Ljava/lang/String;)Ljava/lang/String;",
-                &[jstring.into()],
-            )?
-            .l()?;
+        let value = Cache::call_static_method(
+            self.env,
+            "java/lang/System",
+            "getProperty",
+            signature!("(Ljava/lang/String;)Ljava/lang/String;"),
+            &[jstring.into()],
+        )?
+        .l()?;
         if value.is_null() {
             return Ok(None);
         }
@@ -124,11 +126,14 @@ impl<'a> System<'a> {
 
     /// Returns the system-dependent line separator string.
     pub fn line_separator(&self) -> Result<String> {
-        let class = Class::System(self.env)?.class;
-        let value = self
-            .env
-            .call_static_method(class, "lineSeparator", "()Ljava/lang/String;", &[])?
-            .l()?;
+        let value = Cache::call_static_method(
+            self.env,
+            "java/lang/System",
+            "lineSeparator",
+            signature!("()Ljava/lang/String;"),
+            &[],
+        )?
+        .l()?;
 
         let object = Object::new(self.env, value, Class::String(self.env)?);
         let string = JavaString::new(self.env, object);
@@ -138,20 +143,24 @@ impl<'a> System<'a> {
     /// Loads a code file with the specified filename from the local file system as a dynamic library.
     pub fn load<S: AsRef<str>>(&self, filename: S) -> Result<()> {
         let jstring = JavaString::from_rust(self.env, filename)?;
-        let class = Class::System(self.env)?.class;
-        self.env
-            .call_static_method(class, "load", "(Ljava/lang/String;)V", &[jstring.into()])?;
+        Cache::call_static_method(
+            self.env,
+            "java/lang/System",
+            "load",
+            signature!("(Ljava/lang/String;)V"),
+            &[jstring.into()],
+        )?;
         Ok(())
     }
 
     /// Loads the system library specified by the libname argument.
     pub fn load_library<S: AsRef<str>>(&self, libname: S) -> Result<()> {
         let jstring = JavaString::from_rust(self.env, libname)?;
-        let class = Class::System(self.env)?.class;
-        self.env.call_static_method(
-            class,
+        Cache::call_static_method(
+            self.env,
+            "java/lang/System",
             "loadLibrary",
-            "(Ljava/lang/String;)V",
+            signature!("(Ljava/lang/String;)V"),
             &[jstring.into()],
         )?;
         Ok(())
@@ -159,16 +168,25 @@ impl<'a> System<'a> {
 
     /// Returns the current value of the running Java Virtual Machine's high-resolution time source, in nanoseconds.
     pub fn nano_time(&self) -> Result<i64> {
-        let class = Class::System(self.env)?.class;
-        let value = self.env.call_static_method(class, "nanoTime", "()J", &[])?;
-        value.j()
+        let value = Cache::call_static_method(
+            self.env,
+            "java/lang/System",
+            "nanoTime",
+            signature!("()J"),
+            &[],
+        )?;
+        Ok(value.j()?)
     }
 
     /// Runs the finalization methods of any objects pending finalization.
     pub fn run_finalization(&self) -> Result<()> {
-        let class = Class::System(self.env)?.class;
-        self.env
-            .call_static_method(class, "runFinalization", "()V", &[])?;
+        Cache::call_static_method(
+            self.env,
+            "java/lang/System",
+            "runFinalization",
+            signature!("()V"),
+            &[],
+        )?;
         Ok(())
     }
 
@@ -180,17 +198,15 @@ impl<'a> System<'a> {
     ) -> Result<Option<String>> {
         let key = JavaString::from_rust(self.env, key)?;
         let value = JavaString::from_rust(self.env, value)?;
-        let class = Class::System(self.env)?.class;
-
-        let value = self
-            .env
-            .call_static_method(
-                class,
-                "setProperty",
-                "(Ljava/lang/String;Ljava/lang/String;)Ljava/lang/String;",
-                &[key.into(), value.into()],
-            )?
-            .l()?;
+
+        let value = Cache::call_static_method(
+            self.env,
+            "java/lang/System",
+            "setProperty",
+            signature!("(Ljava/lang/String;Ljava/lang/String;)Ljava/lang/String;"),
+            &[key.into(), value.into()],
+        )?
+        .l()?;
         if value.is_null() {
             return Ok(None);
         }
@@ -204,6 +220,7 @@ impl<'a> System<'a> {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::error::Error;
     use crate::test::JVM;
 
     #[test]
@@ -303,6 +320,38 @@ mod test {
         }
     }
 
+    #[test]
+    fn load_nonexistent() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        let system = System::new(&env);
+
+        match system.load("/nonexistent/libfoo.so") {
+            Err(Error::JavaException(e)) => {
+                assert_eq!("java.lang.UnsatisfiedLinkError", e.class);
+                assert!(e.message.is_some());
+            }
+            _ => panic!("Expected an UnsatisfiedLinkError"),
+        }
+        assert!(!env.exception_check().unwrap());
+    }
+
+    #[test]
+    fn get_env_unmodifiable() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        let system = System::new(&env);
+
+        let map = system.get_env().unwrap();
+        match map.put("Foo".to_string(), "Bar".to_string()) {
+            Err(Error::JavaException(e)) => {
+                assert_eq!("java.lang.UnsupportedOperationException", e.class)
+            }
+            _ => panic!("Expected an UnsupportedOperationException"),
+        }
+        assert!(!env.exception_check().unwrap());
+    }
+
     #[test]
     fn nano_time() {
         let jvm = JVM.lock().unwrap();
@@ -0,0 +1,703 @@
+use crate::cache::Cache;
+use crate::class::Class;
+use crate::error::{Error, JniResultExt, Result};
+use crate::java_type::{FromJava, IntoJava, JavaType};
+use crate::object::Object;
+use crate::signature::TypeDescriptor;
+use jni::sys::{
+    self, _jobject, jarray, jboolean, jbyte, jchar, jdouble, jfloat, jint, jlong, jshort, jsize,
+    JNI_ABORT, JNI_COMMIT, JNI_FALSE,
+};
+use jni::JNIEnv;
+use std::convert::TryFrom;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+
+/// The element of a `boolean[]`. A Java boolean is stored as a byte, which is not guaranteed to be 0 or 1
+/// when it was written through JNI, so it can not be exposed as a Rust `bool` without a check
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JavaBoolean(pub jboolean);
+
+impl From<bool> for JavaBoolean {
+    fn from(b: bool) -> Self {
+        Self(b as jboolean)
+    }
+}
+
+impl From<JavaBoolean> for bool {
+    fn from(b: JavaBoolean) -> Self {
+        b.0 != JNI_FALSE
+    }
+}
+
+impl PartialEq for JavaBoolean {
+    fn eq(&self, other: &Self) -> bool {
+        bool::from(*self) == bool::from(*other)
+    }
+}
+
+impl Eq for JavaBoolean {}
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// The element type of a Java primitive array. Implemented for the JNI primitive types:
+///
+/// | Rust          | Java      |
+/// |---------------|-----------|
+/// | [JavaBoolean] | `boolean` |
+/// | i8            | `byte`    |
+/// | u16           | `char`    |
+/// | i16           | `short`   |
+/// | i32           | `int`     |
+/// | i64           | `long`    |
+/// | f32           | `float`   |
+/// | f64           | `double`  |
+///
+/// This trait is sealed, the JNI functions used for each type are fixed by the JNI specification
+pub trait PrimitiveElement: Copy + Default + private::Sealed {
+    /// The descriptor of the Java primitive, e.g `I` for `int`
+    const DESCRIPTOR: TypeDescriptor;
+
+    #[doc(hidden)]
+    fn new_array(env: &JNIEnv<'_>, len: jsize) -> jni::errors::Result<jarray>;
+
+    #[doc(hidden)]
+    fn get_region(
+        env: &JNIEnv<'_>,
+        array: jarray,
+        start: jsize,
+        buf: &mut [Self],
+    ) -> jni::errors::Result<()>;
+
+    #[doc(hidden)]
+    fn set_region(
+        env: &JNIEnv<'_>,
+        array: jarray,
+        start: jsize,
+        buf: &[Self],
+    ) -> jni::errors::Result<()>;
+
+    #[doc(hidden)]
+    /// # Safety
+    /// `env` must be the JNIEnv of the current thread, and `array` must be an array of this element type
+    unsafe fn get_elements(
+        env: *mut sys::JNIEnv,
+        array: jarray,
+        is_copy: *mut jboolean,
+    ) -> *mut Self;
+
+    #[doc(hidden)]
+    /// # Safety
+    /// `elements` must have been returned by [PrimitiveElement::get_elements] for `array`, and not released yet
+    unsafe fn release_elements(
+        env: *mut sys::JNIEnv,
+        array: jarray,
+        elements: *mut Self,
+        mode: jint,
+    );
+}
+
+macro_rules! impl_primitive_element {
+    ($ty:ty, $descriptor:ident, $new:ident, $get_region:ident, $set_region:ident, $get_elements:ident, $release_elements:ident) => {
+        impl private::Sealed for $ty {}
+
+        impl PrimitiveElement for $ty {
+            const DESCRIPTOR: TypeDescriptor = TypeDescriptor::$descriptor;
+
+            fn new_array(env: &JNIEnv<'_>, len: jsize) -> jni::errors::Result<jarray> {
+                env.$new(len)
+            }
+
+            fn get_region(
+                env: &JNIEnv<'_>,
+                array: jarray,
+                start: jsize,
+                buf: &mut [Self],
+            ) -> jni::errors::Result<()> {
+                // Safety: every element type has the layout of its JNI primitive
+                let buf =
+                    unsafe { std::slice::from_raw_parts_mut(buf.as_mut_ptr().cast(), buf.len()) };
+                env.$get_region(array, start, buf)
+            }
+
+            fn set_region(
+                env: &JNIEnv<'_>,
+                array: jarray,
+                start: jsize,
+                buf: &[Self],
+            ) -> jni::errors::Result<()> {
+                // Safety: see get_region
+                let buf = unsafe { std::slice::from_raw_parts(buf.as_ptr().cast(), buf.len()) };
+                env.$set_region(array, start, buf)
+            }
+
+            unsafe fn get_elements(
+                env: *mut sys::JNIEnv,
+                array: jarray,
+                is_copy: *mut jboolean,
+            ) -> *mut Self {
+                match (**env).$get_elements {
+                    Some(get_elements) => get_elements(env, array, is_copy).cast(),
+                    None => std::ptr::null_mut(),
+                }
+            }
+
+            unsafe fn release_elements(
+                env: *mut sys::JNIEnv,
+                array: jarray,
+                elements: *mut Self,
+                mode: jint,
+            ) {
+                if let Some(release_elements) = (**env).$release_elements {
+                    release_elements(env, array, elements.cast(), mode);
+                }
+            }
+        }
+    };
+}
+
+impl_primitive_element!(
+    JavaBoolean,
+    Boolean,
+    new_boolean_array,
+    get_boolean_array_region,
+    set_boolean_array_region,
+    GetBooleanArrayElements,
+    ReleaseBooleanArrayElements
+);
+impl_primitive_element!(
+    jbyte,
+    Byte,
+    new_byte_array,
+    get_byte_array_region,
+    set_byte_array_region,
+    GetByteArrayElements,
+    ReleaseByteArrayElements
+);
+impl_primitive_element!(
+    jchar,
+    Char,
+    new_char_array,
+    get_char_array_region,
+    set_char_array_region,
+    GetCharArrayElements,
+    ReleaseCharArrayElements
+);
+impl_primitive_element!(
+    jshort,
+    Short,
+    new_short_array,
+    get_short_array_region,
+    set_short_array_region,
+    GetShortArrayElements,
+    ReleaseShortArrayElements
+);
+impl_primitive_element!(
+    jint,
+    Int,
+    new_int_array,
+    get_int_array_region,
+    set_int_array_region,
+    GetIntArrayElements,
+    ReleaseIntArrayElements
+);
+impl_primitive_element!(
+    jlong,
+    Long,
+    new_long_array,
+    get_long_array_region,
+    set_long_array_region,
+    GetLongArrayElements,
+    ReleaseLongArrayElements
+);
+impl_primitive_element!(
+    jfloat,
+    Float,
+    new_float_array,
+    get_float_array_region,
+    set_float_array_region,
+    GetFloatArrayElements,
+    ReleaseFloatArrayElements
+);
+impl_primitive_element!(
+    jdouble,
+    Double,
+    new_double_array,
+    get_double_array_region,
+    set_double_array_region,
+    GetDoubleArrayElements,
+    ReleaseDoubleArrayElements
+);
+
+/// A Java primitive array, e.g `int[]` for `JavaArray<i32>`
+pub struct JavaArray<'a, T: PrimitiveElement> {
+    /// The array itself
+    pub inner: Object<'a>,
+    env: &'a JNIEnv<'a>,
+    _type: PhantomData<T>,
+}
+
+#[allow(clippy::from_over_into)]
+impl<'a, T: PrimitiveElement> Into<*mut _jobject> for JavaArray<'a, T> {
+    fn into(self) -> *mut _jobject {
+        // The local reference is handed to the caller, so it must not be deleted by Drop
+        let this = std::mem::ManuallyDrop::new(self);
+        this.inner.inner.into_inner()
+    }
+}
+
+impl<'a, T: PrimitiveElement> Drop for JavaArray<'a, T> {
+    fn drop(&mut self) {
+        let _ = self.env.delete_local_ref(self.inner.inner);
+    }
+}
+
+impl<'a, T: PrimitiveElement> JavaType<'a> for JavaArray<'a, T> {
+    fn class(env: &'a JNIEnv<'a>) -> Result<Class<'a>> {
+        Cache::class(env, &T::DESCRIPTOR.array().to_string())
+    }
+}
+
+impl<'a, T: PrimitiveElement> IntoJava<'a> for JavaArray<'a, T> {
+    const OWNED: bool = true;
+
+    fn into_java(self, _: &'a JNIEnv<'a>) -> Result<Object<'a>> {
+        // The local reference is handed to the Object, so it must not be deleted by Drop
+        let this = std::mem::ManuallyDrop::new(self);
+        Ok(this.inner.clone())
+    }
+}
+
+impl<'a, T: PrimitiveElement> FromJava<'a> for JavaArray<'a, T> {
+    /// Fails with [Error::ClassMismatch] if the Object is not an array of `T`
+    fn from_java(object: Object<'a>) -> Result<Self> {
+        let class = Self::class(object.env)?;
+        if !object.instance_of_class(&class)? {
+            return Err(Error::class_mismatch(&class, &object.class));
+        }
+
+        Ok(Self::new(object.env, object))
+    }
+}
+
+impl<'a, T: PrimitiveElement> JavaArray<'a, T> {
+    /// Create a new JavaArray wrapper. The caller must guarantee that the passed in Object is an array of `T` and is not null.
+    /// Use [FromJava::from_java] to check the Class of the Object instead
+    pub fn new(env: &'a JNIEnv<'a>, object: Object<'a>) -> Self {
+        Self {
+            inner: object,
+            env,
+            _type: PhantomData,
+        }
+    }
+
+    /// Create a new array of `len` elements, initialized to zero
+    pub fn with_len(env: &'a JNIEnv<'a>, len: usize) -> Result<Self> {
+        let array = T::new_array(env, to_jsize(len)?).catch(env)?;
+        Ok(Self::new(
+            env,
+            Object::new(env, array.into(), Self::class(env)?),
+        ))
+    }
+
+    /// Create a new array holding a copy of `elements`
+    pub fn from_slice(env: &'a JNIEnv<'a>, elements: &[T]) -> Result<Self> {
+        let array = Self::with_len(env, elements.len())?;
+        array.set_region(0, elements)?;
+        Ok(array)
+    }
+
+    /// Returns the number of elements in the array
+    pub fn len(&self) -> Result<usize> {
+        let len = self.env.get_array_length(self.raw()).catch(self.env)?;
+        Ok(len as usize)
+    }
+
+    /// Returns true if the array has no elements
+    pub fn is_empty(&self) -> Result<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    /// Copy `buf.len()` elements, starting at index `start`, into `buf`.
+    /// Fails with an `ArrayIndexOutOfBoundsException` if the region does not fit in the array
+    pub fn get_region(&self, start: usize, buf: &mut [T]) -> Result<()> {
+        T::get_region(self.env, self.raw(), to_jsize(start)?, buf).catch(self.env)
+    }
+
+    /// Copy `buf` into the array, starting at index `start`.
+    /// Fails with an `ArrayIndexOutOfBoundsException` if the region does not fit in the array
+    pub fn set_region(&self, start: usize, buf: &[T]) -> Result<()> {
+        T::set_region(self.env, self.raw(), to_jsize(start)?, buf).catch(self.env)
+    }
+
+    /// Copy the array to a Vec
+    pub fn to_vec(&self) -> Result<Vec<T>> {
+        let mut buf = vec![T::default(); self.len()?];
+        self.get_region(0, &mut buf)?;
+        Ok(buf)
+    }
+
+    /// Access the elements of the array through `Get<Type>ArrayElements`.
+    /// The JVM may hand out the array itself or a copy of it, see [ArrayElements::is_copy].
+    /// Changes are written back when the guard is dropped or committed, and discarded when it is aborted
+    pub fn elements(&mut self) -> Result<ArrayElements<'_, T>> {
+        let len = self.len()?;
+        let raw = self.env.get_native_interface();
+        let mut is_copy = JNI_FALSE;
+        // Safety: `raw` is the JNIEnv of the current thread, and the array holds elements of type `T`
+        let ptr = unsafe { T::get_elements(raw, self.raw(), &mut is_copy) };
+        ArrayElements::new(self, ptr, len, is_copy != JNI_FALSE, false)
+    }
+
+    /// Access the elements of the array through `GetPrimitiveArrayCritical`, which avoids a copy whenever the JVM can.
+    ///
+    /// While the guard is alive the thread must not call into the JVM or block on another thread that does,
+    /// the garbage collector may be paused until the guard is released. Keep the critical region short
+    pub fn critical(&mut self) -> Result<ArrayElements<'_, T>> {
+        // No JNI calls may be made inside the critical region, so the length is queried beforehand
+        let len = self.len()?;
+        let raw = self.env.get_native_interface();
+        let mut is_copy = JNI_FALSE;
+        // Safety: `raw` is the JNIEnv of the current thread, and the array is a primitive array
+        let ptr = unsafe {
+            match (**raw).GetPrimitiveArrayCritical {
+                Some(get_critical) => get_critical(raw, self.raw(), &mut is_copy) as *mut T,
+                None => std::ptr::null_mut(),
+            }
+        };
+        ArrayElements::new(self, ptr, len, is_copy != JNI_FALSE, true)
+    }
+
+    fn raw(&self) -> jarray {
+        self.inner.inner.into_inner()
+    }
+}
+
+/// The elements of a [JavaArray], borrowed through [JavaArray::elements] or [JavaArray::critical].
+/// Dereferences to `[T]`.
+///
+/// Dropping the guard writes the changes back to the array and releases it, like [ArrayElements::commit]
+pub struct ArrayElements<'b, T: PrimitiveElement> {
+    env: &'b JNIEnv<'b>,
+    array: jarray,
+    ptr: *mut T,
+    len: usize,
+    is_copy: bool,
+    critical: bool,
+    released: bool,
+}
+
+impl<'b, T: PrimitiveElement> ArrayElements<'b, T> {
+    fn new<'a: 'b>(
+        array: &'b mut JavaArray<'a, T>,
+        ptr: *mut T,
+        len: usize,
+        is_copy: bool,
+        critical: bool,
+    ) -> Result<Self> {
+        let env = array.env;
+        if ptr.is_null() {
+            // The JVM failed to allocate the elements, an OutOfMemoryError is pending
+            env.exception_check().catch(env)?;
+            return Err(jni::errors::Error::NullPtr("Array elements").into());
+        }
+
+        Ok(Self {
+            env,
+            array: array.raw(),
+            ptr,
+            len,
+            is_copy,
+            critical,
+            released: false,
+        })
+    }
+
+    /// Returns true if the JVM handed out a copy of the array, rather than the array itself.
+    /// Changes to a copy are only visible to Java after the guard is committed, flushed or dropped
+    pub fn is_copy(&self) -> bool {
+        self.is_copy
+    }
+
+    /// Write the changes back to the array without releasing the elements (`JNI_COMMIT`).
+    /// This is a no-op if the elements are not a copy
+    pub fn flush(&mut self) {
+        self.release(JNI_COMMIT);
+    }
+
+    /// Write the changes back to the array and release the elements (mode `0`). This is also done on Drop
+    pub fn commit(mut self) {
+        self.release(0);
+    }
+
+    /// Release the elements without writing the changes back (`JNI_ABORT`).
+    /// If the elements are not a copy, the changes have already been made to the array
+    pub fn abort(mut self) {
+        self.release(JNI_ABORT);
+    }
+
+    fn release(&mut self, mode: jint) {
+        if self.released {
+            return;
+        }
+        self.released = mode != JNI_COMMIT;
+
+        let raw = self.env.get_native_interface();
+        // Safety: `ptr` was returned by the matching Get function for `array`, and has not been released yet
+        unsafe {
+            if self.critical {
+                if let Some(release_critical) = (**raw).ReleasePrimitiveArrayCritical {
+                    release_critical(raw, self.array, self.ptr as *mut _, mode);
+                }
+            } else {
+                T::release_elements(raw, self.array, self.ptr, mode);
+            }
+        }
+    }
+}
+
+impl<'b, T: PrimitiveElement> Deref for ArrayElements<'b, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        // Safety: `ptr` points to `len` elements, which stay valid until the guard is released
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+}
+
+impl<'b, T: PrimitiveElement> DerefMut for ArrayElements<'b, T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        // Safety: see Deref. The guard mutably borrows the JavaArray, so there is no other guard for it
+        unsafe { std::slice::from_raw_parts_mut(self.ptr, self.len) }
+    }
+}
+
+impl<'b, T: PrimitiveElement> Drop for ArrayElements<'b, T> {
+    fn drop(&mut self) {
+        self.release(0);
+    }
+}
+
+/// The elements of an array of any dimension, returned by [Object::get_array_contents].
+/// Primitive arrays are copied into a Vec of the matching Rust type, see [PrimitiveElement]
+pub enum ArrayContents<'a> {
+    /// `boolean[]`
+    Boolean(Vec<bool>),
+    /// `byte[]`
+    Byte(Vec<i8>),
+    /// `char[]`
+    Char(Vec<u16>),
+    /// `short[]`
+    Short(Vec<i16>),
+    /// `int[]`
+    Int(Vec<i32>),
+    /// `long[]`
+    Long(Vec<i64>),
+    /// `float[]`
+    Float(Vec<f32>),
+    /// `double[]`
+    Double(Vec<f64>),
+    /// An array of Objects which are not arrays themselves, e.g `String[]`. Elements may be null
+    Object(Vec<Object<'a>>),
+    /// An array of arrays, e.g `int[][]`. Null elements are None
+    Array(Vec<Option<ArrayContents<'a>>>),
+}
+
+impl<'a> ArrayContents<'a> {
+    /// Returns the number of elements in the outermost dimension
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Boolean(v) => v.len(),
+            Self::Byte(v) => v.len(),
+            Self::Char(v) => v.len(),
+            Self::Short(v) => v.len(),
+            Self::Int(v) => v.len(),
+            Self::Long(v) => v.len(),
+            Self::Float(v) => v.len(),
+            Self::Double(v) => v.len(),
+            Self::Object(v) => v.len(),
+            Self::Array(v) => v.len(),
+        }
+    }
+
+    /// Returns true if the outermost dimension has no elements
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Copy a primitive array with the component type described by `descriptor`.
+    /// Returns None if `descriptor` is not a primitive type
+    pub(crate) fn primitive(
+        env: &JNIEnv<'_>,
+        array: jarray,
+        descriptor: &TypeDescriptor,
+    ) -> Result<Option<Self>> {
+        let contents = match descriptor {
+            TypeDescriptor::Boolean => Self::Boolean(
+                primitive_vec::<JavaBoolean>(env, array)?
+                    .into_iter()
+                    .map(bool::from)
+                    .collect(),
+            ),
+            TypeDescriptor::Byte => Self::Byte(primitive_vec(env, array)?),
+            TypeDescriptor::Char => Self::Char(primitive_vec(env, array)?),
+            TypeDescriptor::Short => Self::Short(primitive_vec(env, array)?),
+            TypeDescriptor::Int => Self::Int(primitive_vec(env, array)?),
+            TypeDescriptor::Long => Self::Long(primitive_vec(env, array)?),
+            TypeDescriptor::Float => Self::Float(primitive_vec(env, array)?),
+            TypeDescriptor::Double => Self::Double(primitive_vec(env, array)?),
+            _ => return Ok(None),
+        };
+        Ok(Some(contents))
+    }
+}
+
+fn primitive_vec<T: PrimitiveElement>(env: &JNIEnv<'_>, array: jarray) -> Result<Vec<T>> {
+    let len = env.get_array_length(array).catch(env)?;
+    let mut buf = vec![T::default(); len as usize];
+    T::get_region(env, array, 0, &mut buf).catch(env)?;
+    Ok(buf)
+}
+
+fn to_jsize(value: usize) -> Result<jsize> {
+    jsize::try_from(value).map_err(|_| Error::Conversion {
+        from: "usize",
+        to: "jsize",
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::error::Error;
+    use crate::test::JVM;
+
+    #[test]
+    fn with_len() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let array = JavaArray::<i32>::with_len(&env, 16).unwrap();
+        assert_eq!(16, array.len().unwrap());
+        assert!(!array.is_empty().unwrap());
+        assert_eq!(vec![0; 16], array.to_vec().unwrap());
+        assert_eq!("[I", array.inner.class.get_name().unwrap());
+
+        let empty = JavaArray::<f64>::with_len(&env, 0).unwrap();
+        assert!(empty.is_empty().unwrap());
+    }
+
+    #[test]
+    fn region() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let array = JavaArray::<i64>::from_slice(&env, &[1, 2, 3, 4, 5]).unwrap();
+        let mut buf = [0; 3];
+        array.get_region(1, &mut buf).unwrap();
+        assert_eq!([2, 3, 4], buf);
+
+        array.set_region(3, &[40, 50]).unwrap();
+        assert_eq!(vec![1, 2, 3, 40, 50], array.to_vec().unwrap());
+
+        let err = array.set_region(4, &[1, 2]).unwrap_err();
+        assert!(
+            matches!(err, Error::JavaException(e) if e.class == "java.lang.ArrayIndexOutOfBoundsException")
+        );
+    }
+
+    #[test]
+    fn elements_commit() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let mut array = JavaArray::<f32>::from_slice(&env, &[1.0, 2.0, 3.0]).unwrap();
+        {
+            let mut elements = array.elements().unwrap();
+            assert_eq!(3, elements.len());
+            elements.iter_mut().for_each(|f| *f *= 2.0);
+            elements.commit();
+        }
+        assert_eq!(vec![2.0, 4.0, 6.0], array.to_vec().unwrap());
+
+        // Dropping the guard commits as well
+        array.elements().unwrap()[0] = 10.0;
+        assert_eq!(vec![10.0, 4.0, 6.0], array.to_vec().unwrap());
+    }
+
+    #[test]
+    fn elements_flush() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let mut array = JavaArray::<i16>::from_slice(&env, &[1, 2]).unwrap();
+        let mut elements = array.elements().unwrap();
+        elements[0] = 5;
+        elements.flush();
+        elements[1] = 6;
+        elements.abort();
+        assert_eq!(5, array.to_vec().unwrap()[0]);
+    }
+
+    #[test]
+    fn elements_abort() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let mut array = JavaArray::<i8>::from_slice(&env, &[1, 2, 3]).unwrap();
+        let mut elements = array.elements().unwrap();
+        let is_copy = elements.is_copy();
+        elements[0] = 100;
+        elements.abort();
+
+        let expected = if is_copy { 1 } else { 100 };
+        assert_eq!(expected, array.to_vec().unwrap()[0]);
+    }
+
+    #[test]
+    fn critical() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let mut array = JavaArray::<i32>::with_len(&env, 1024).unwrap();
+        {
+            let mut critical = array.critical().unwrap();
+            for (i, value) in critical.iter_mut().enumerate() {
+                *value = i as i32;
+            }
+        }
+
+        let vec = array.to_vec().unwrap();
+        assert_eq!(1024, vec.len());
+        assert_eq!(1023, vec[1023]);
+
+        let mut array =
+            JavaArray::<JavaBoolean>::from_slice(&env, &[true.into(), false.into()]).unwrap();
+        let critical = array.critical().unwrap();
+        assert_eq!(&[JavaBoolean(1), JavaBoolean(0)], &critical[..]);
+        critical.abort();
+    }
+
+    #[test]
+    fn from_java() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let array = JavaArray::<u16>::from_slice(&env, &[1, 2]).unwrap();
+        let class = array.inner.class.clone();
+        let raw: *mut _jobject = array.into();
+
+        let array =
+            JavaArray::<u16>::from_java(Object::new(&env, raw.into(), class.clone())).unwrap();
+        assert_eq!(vec![1, 2], array.to_vec().unwrap());
+        let raw: *mut _jobject = array.into();
+
+        let err = JavaArray::<i32>::from_java(Object::new(&env, raw.into(), class))
+            .map(|_| ())
+            .unwrap_err();
+        assert!(matches!(err, Error::ClassMismatch { .. }));
+    }
+}
@@ -0,0 +1,421 @@
+use crate::class::Class;
+use crate::error::{JniResultExt, Result};
+use jni::objects::{
+    GlobalRef, JClass, JFieldID, JMethodID, JObject, JStaticFieldID, JStaticMethodID, JValue,
+};
+use jni::signature::{JavaType as SignatureType, TypeSignature};
+use jni::JNIEnv;
+use std::any::Any;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+use std::sync::{Arc, OnceLock, PoisonError, RwLock};
+
+/// A cached method or field ID
+struct Member {
+    class: String,
+    name: String,
+    sig: String,
+    id: usize,
+    ty: SignatureType,
+}
+
+/// A value cached by [Cache::by_class], with the Class it belongs to
+type ClassEntry = (GlobalRef, Arc<dyn Any + Send + Sync>);
+
+/// The cache belonging to a single JVM
+#[derive(Default)]
+struct VmCache {
+    classes: HashMap<String, GlobalRef>,
+    methods: HashMap<u64, Member>,
+    static_methods: HashMap<u64, Member>,
+    fields: HashMap<u64, Member>,
+    static_fields: HashMap<u64, Member>,
+    /// Values cached by a key and the identity of a Class, see [Cache::by_class]
+    by_class: HashMap<String, Vec<ClassEntry>>,
+}
+
+#[derive(Clone, Copy)]
+enum MemberKind {
+    Method,
+    StaticMethod,
+    Field,
+    StaticField,
+}
+
+impl VmCache {
+    fn members(&self, kind: MemberKind) -> &HashMap<u64, Member> {
+        match kind {
+            MemberKind::Method => &self.methods,
+            MemberKind::StaticMethod => &self.static_methods,
+            MemberKind::Field => &self.fields,
+            MemberKind::StaticField => &self.static_fields,
+        }
+    }
+
+    fn members_mut(&mut self, kind: MemberKind) -> &mut HashMap<u64, Member> {
+        match kind {
+            MemberKind::Method => &mut self.methods,
+            MemberKind::StaticMethod => &mut self.static_methods,
+            MemberKind::Field => &mut self.fields,
+            MemberKind::StaticField => &mut self.static_fields,
+        }
+    }
+}
+
+type Caches = RwLock<HashMap<usize, VmCache>>;
+
+fn caches() -> &'static Caches {
+    static CACHES: OnceLock<Caches> = OnceLock::new();
+    CACHES.get_or_init(Default::default)
+}
+
+fn vm_key(env: &JNIEnv<'_>) -> jni::errors::Result<usize> {
+    Ok(env.get_java_vm()?.get_java_vm_pointer() as usize)
+}
+
+fn member_key(class: &str, name: &str, sig: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    (class, name, sig).hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Per-JVM cache of global Class references, method IDs and field IDs.
+///
+/// Classes are pinned by a global reference for as long as the JVM lives, so the IDs cached for their members stay valid.
+/// Lookups are keyed by the binary class name (e.g `java/util/List`), the member name and the JNI signature.
+pub struct Cache();
+
+impl Cache {
+    /// Get a Class by its binary name, e.g `java/lang/Integer`. The class is looked up with `FindClass` the first time
+    pub fn class<'a>(env: &'a JNIEnv<'a>, name: &str) -> Result<Class<'a>> {
+        Self::class_with(env, name, |env| env.find_class(name).catch(env))
+    }
+
+    /// Get a Class by an arbitrary key. `lookup` is called to resolve the Class the first time the key is requested
+    pub fn class_with<'a, F>(env: &'a JNIEnv<'a>, key: &str, lookup: F) -> Result<Class<'a>>
+    where
+        F: FnOnce(&'a JNIEnv<'a>) -> Result<JClass<'a>>,
+    {
+        let vm = vm_key(env)?;
+        {
+            let caches = caches().read().unwrap_or_else(PoisonError::into_inner);
+            if let Some(global) = caches.get(&vm).and_then(|c| c.classes.get(key)) {
+                return Ok(Class::new(env, JClass::from(global.as_obj().into_inner())));
+            }
+        }
+
+        let local = lookup(env)?;
+        let global = env.new_global_ref(local).catch(env)?;
+        env.delete_local_ref(local.into())?;
+
+        let mut caches = caches().write().unwrap_or_else(PoisonError::into_inner);
+        let global = caches
+            .entry(vm)
+            .or_default()
+            .classes
+            .entry(key.to_string())
+            .or_insert(global);
+        Ok(Class::new(env, JClass::from(global.as_obj().into_inner())))
+    }
+
+    fn member<'a>(
+        env: &'a JNIEnv<'a>,
+        kind: MemberKind,
+        class: &str,
+        name: &str,
+        sig: &str,
+    ) -> Result<(usize, SignatureType)> {
+        let vm = vm_key(env)?;
+        let key = member_key(class, name, sig);
+        {
+            let caches = caches().read().unwrap_or_else(PoisonError::into_inner);
+            let member = caches.get(&vm).and_then(|c| c.members(kind).get(&key));
+            if let Some(member) = member {
+                if member.class == class && member.name == name && member.sig == sig {
+                    return Ok((member.id, member.ty.clone()));
+                }
+            }
+        }
+
+        let class_ref = Self::class(env, class)?;
+        let (id, ty) = match kind {
+            MemberKind::Method => (
+                env.get_method_id(class_ref, name, sig)
+                    .catch(env)?
+                    .into_inner() as usize,
+                TypeSignature::from_str(sig)?.ret,
+            ),
+            MemberKind::StaticMethod => (
+                env.get_static_method_id(class_ref, name, sig)
+                    .catch(env)?
+                    .into_inner() as usize,
+                TypeSignature::from_str(sig)?.ret,
+            ),
+            MemberKind::Field => (
+                env.get_field_id(class_ref, name, sig)
+                    .catch(env)?
+                    .into_inner() as usize,
+                SignatureType::from_str(sig)?,
+            ),
+            MemberKind::StaticField => (
+                env.get_static_field_id(class_ref, name, sig)
+                    .catch(env)?
+                    .into_inner() as usize,
+                SignatureType::from_str(sig)?,
+            ),
+        };
+
+        let mut caches = caches().write().unwrap_or_else(PoisonError::into_inner);
+        // On a hash collision the first entry wins, the colliding member is simply not cached
+        caches
+            .entry(vm)
+            .or_default()
+            .members_mut(kind)
+            .entry(key)
+            .or_insert_with(|| Member {
+                class: class.to_string(),
+                name: name.to_string(),
+                sig: sig.to_string(),
+                id,
+                ty: ty.clone(),
+            });
+        Ok((id, ty))
+    }
+
+    /// Get a value by `key` and the identity of `class`, e.g a member of a Class which is not looked up by its name.
+    /// `resolve` is called to resolve the value the first time. Classes of the same name defined by different ClassLoaders do not share values.
+    /// Keys are shared by every type of value, so they should be prefixed with what they describe, e.g `field count`
+    pub(crate) fn by_class<'a, T, F>(
+        env: &'a JNIEnv<'a>,
+        class: &Class<'a>,
+        key: &str,
+        resolve: F,
+    ) -> Result<Arc<T>>
+    where
+        T: Send + Sync + 'static,
+        F: FnOnce() -> Result<T>,
+    {
+        let vm = vm_key(env)?;
+        {
+            let caches = caches().read().unwrap_or_else(PoisonError::into_inner);
+            let entries = caches.get(&vm).and_then(|c| c.by_class.get(key));
+            for (cached, value) in entries.into_iter().flatten() {
+                if env.is_same_object(cached.as_obj(), class.class)? {
+                    if let Ok(value) = value.clone().downcast::<T>() {
+                        return Ok(value);
+                    }
+                }
+            }
+        }
+
+        let value = Arc::new(resolve()?);
+        let class = env.new_global_ref(class.class).catch(env)?;
+        let mut caches = caches().write().unwrap_or_else(PoisonError::into_inner);
+        caches
+            .entry(vm)
+            .or_default()
+            .by_class
+            .entry(key.to_string())
+            .or_default()
+            .push((class, value.clone()));
+        Ok(value)
+    }
+
+    /// Get the ID and return type of an instance method
+    pub fn method_id<'a>(
+        env: &'a JNIEnv<'a>,
+        class: &str,
+        name: &str,
+        sig: &str,
+    ) -> Result<(JMethodID<'a>, SignatureType)> {
+        let (id, ret) = Self::member(env, MemberKind::Method, class, name, sig)?;
+        Ok((JMethodID::from(id as jni::sys::jmethodID), ret))
+    }
+
+    /// Get the ID and return type of a static method
+    pub fn static_method_id<'a>(
+        env: &'a JNIEnv<'a>,
+        class: &str,
+        name: &str,
+        sig: &str,
+    ) -> Result<(JStaticMethodID<'a>, SignatureType)> {
+        let (id, ret) = Self::member(env, MemberKind::StaticMethod, class, name, sig)?;
+        Ok((JStaticMethodID::from(id as jni::sys::jmethodID), ret))
+    }
+
+    /// Get the ID and type of an instance field
+    pub fn field_id<'a>(
+        env: &'a JNIEnv<'a>,
+        class: &str,
+        name: &str,
+        sig: &str,
+    ) -> Result<(JFieldID<'a>, SignatureType)> {
+        let (id, ty) = Self::member(env, MemberKind::Field, class, name, sig)?;
+        Ok((JFieldID::from(id as jni::sys::jfieldID), ty))
+    }
+
+    /// Get the ID and type of a static field
+    pub fn static_field_id<'a>(
+        env: &'a JNIEnv<'a>,
+        class: &str,
+        name: &str,
+        sig: &str,
+    ) -> Result<(JStaticFieldID<'a>, SignatureType)> {
+        let (id, ty) = Self::member(env, MemberKind::StaticField, class, name, sig)?;
+        Ok((JStaticFieldID::from(id as jni::sys::jfieldID), ty))
+    }
+
+    /// Call an instance method using the cached method ID. `obj` must not be null and must be an instance of `class`
+    pub fn call_method<'a, O>(
+        env: &'a JNIEnv<'a>,
+        obj: O,
+        class: &str,
+        name: &str,
+        sig: &str,
+        args: &[JValue],
+    ) -> Result<JValue<'a>>
+    where
+        O: Into<JObject<'a>>,
+    {
+        let (id, ret) = Self::method_id(env, class, name, sig)?;
+        env.call_method_unchecked(obj, id, ret, args).catch(env)
+    }
+
+    /// Call a static method using the cached method ID
+    pub fn call_static_method<'a>(
+        env: &'a JNIEnv<'a>,
+        class: &str,
+        name: &str,
+        sig: &str,
+        args: &[JValue],
+    ) -> Result<JValue<'a>> {
+        let class_ref = Self::class(env, class)?;
+        let (id, ret) = Self::static_method_id(env, class, name, sig)?;
+        env.call_static_method_unchecked(class_ref, id, ret, args)
+            .catch(env)
+    }
+
+    /// Construct a new Object using the cached constructor ID
+    pub fn new_object<'a>(
+        env: &'a JNIEnv<'a>,
+        class: &str,
+        sig: &str,
+        args: &[JValue],
+    ) -> Result<JObject<'a>> {
+        let class_ref = Self::class(env, class)?;
+        let (id, _) = Self::method_id(env, class, "<init>", sig)?;
+        env.new_object_unchecked(class_ref, id, args).catch(env)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::error::Error;
+    use crate::object::Object;
+    use crate::test::JVM;
+    use std::cell::Cell;
+
+    #[test]
+    fn class() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let first = Cache::class(&env, "java/lang/Integer").unwrap();
+        let second = Cache::class(&env, "java/lang/Integer").unwrap();
+        assert!(env.is_same_object(first.class, second.class).unwrap());
+        assert_eq!("java.lang.Integer", second.get_name().unwrap());
+    }
+
+    #[test]
+    fn class_not_found() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let err = Cache::class(&env, "com/example/DoesNotExist").unwrap_err();
+        assert!(
+            matches!(err, Error::JavaException(e) if e.class == "java.lang.NoClassDefFoundError")
+        );
+        assert!(!env.exception_check().unwrap());
+    }
+
+    #[test]
+    fn call_method() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let integer = Object::new_integer_object(&env, 10).unwrap();
+        for _ in 0..2 {
+            let value = Cache::call_method(
+                &env,
+                integer.inner,
+                "java/lang/Integer",
+                "intValue",
+                "()I",
+                &[],
+            )
+            .unwrap();
+            assert_eq!(10, value.i().unwrap());
+        }
+    }
+
+    #[test]
+    fn call_static_method() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let value = Cache::call_static_method(
+            &env,
+            "java/lang/Math",
+            "max",
+            "(II)I",
+            &[JValue::Int(1), JValue::Int(2)],
+        )
+        .unwrap();
+        assert_eq!(2, value.i().unwrap());
+    }
+
+    #[test]
+    fn field_id() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let (id, ty) = Cache::static_field_id(&env, "java/lang/Integer", "MAX_VALUE", "I").unwrap();
+        let value = env
+            .get_static_field_unchecked("java/lang/Integer", id, ty)
+            .unwrap();
+        assert_eq!(i32::MAX, value.i().unwrap());
+    }
+
+    #[test]
+    fn reuses_cached() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let lookups = Cell::new(0);
+        for _ in 0..2 {
+            Cache::class_with(&env, "reuses cached", |env| {
+                lookups.set(lookups.get() + 1);
+                env.find_class("java/lang/Integer").catch(env)
+            })
+            .unwrap();
+        }
+        assert_eq!(1, lookups.get());
+
+        let first = Cache::class(&env, "java/lang/Integer").unwrap();
+        let second = Cache::class(&env, "java/lang/Integer").unwrap();
+        assert_eq!(first.class.into_inner(), second.class.into_inner());
+
+        let (first, _) = Cache::method_id(&env, "java/lang/Integer", "hashCode", "()I").unwrap();
+        let caches = caches().read().unwrap();
+        let member = &caches[&vm_key(&env).unwrap()].methods
+            [&member_key("java/lang/Integer", "hashCode", "()I")];
+        assert_eq!(first.into_inner() as usize, member.id);
+        drop(caches);
+
+        let (second, _) = Cache::method_id(&env, "java/lang/Integer", "hashCode", "()I").unwrap();
+        assert_eq!(first.into_inner(), second.into_inner());
+    }
+}
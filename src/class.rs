@@ -1,8 +1,16 @@
-use crate::abstractions::JavaString;
+use crate::abstractions::{ClassLoader, JavaString};
+use crate::cache::Cache;
+use crate::error::{JniResultExt, Result};
+use crate::field;
+use crate::java_type::{FromJava, IntoJava};
+use crate::method::{self, IntoArgs};
 use crate::object::Object;
+use crate::reflect::{self, Constructor, Field, Method, Modifiers};
+use crate::registry::NativeRegistry;
+use crate::signature;
+use crate::signature::TypeDescriptor;
 use jni::descriptors::Desc;
-use jni::errors::Result;
-use jni::objects::{JClass, JObject};
+use jni::objects::{JClass, JObject, JValue};
 use jni::sys::_jobject;
 use jni::JNIEnv;
 use std::fmt::{Debug, Formatter};
@@ -35,7 +43,7 @@ impl<'a> From<Class<'a>> for JClass<'a> {
 }
 
 impl<'a> Desc<'a, JClass<'a>> for Class<'a> {
-    fn lookup(self, _: &JNIEnv<'a>) -> Result<JClass<'a>> {
+    fn lookup(self, _: &JNIEnv<'a>) -> jni::errors::Result<JClass<'a>> {
         Ok(self.class)
     }
 }
@@ -50,477 +58,572 @@ impl<'a> Class<'a> {
 
     /// java.lang.Byte
     pub fn Byte(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/lang/Byte")?))
+        Cache::class(env, "java/lang/Byte")
     }
 
     /// java.lang.Boolean
     pub fn Boolean(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/lang/Boolean")?))
+        Cache::class(env, "java/lang/Boolean")
     }
 
     /// java.lang.Float
     pub fn Float(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/lang/Float")?))
+        Cache::class(env, "java/lang/Float")
     }
 
     /// java.lang.Integer
     pub fn Integer(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/lang/Integer")?))
+        Cache::class(env, "java/lang/Integer")
     }
 
     /// java.lang.Double
     pub fn Double(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/lang/Double")?))
+        Cache::class(env, "java/lang/Double")
     }
 
     /// java.lang.Short
     pub fn Short(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/lang/Short")?))
+        Cache::class(env, "java/lang/Short")
     }
 
     /// java.lang.Character
     pub fn Character(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/lang/Character")?))
+        Cache::class(env, "java/lang/Character")
     }
 
     /// java.lang.Long
     pub fn Long(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/lang/Long")?))
+        Cache::class(env, "java/lang/Long")
     }
 
     /// java.lang.Object
     pub fn Object(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/lang/Object")?))
+        Cache::class(env, "java/lang/Object")
     }
 
     /// java.lang.Class
     pub fn Class(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/lang/Class")?))
+        Cache::class(env, "java/lang/Class")
     }
 
     /// java.lang.System
     pub fn System(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/lang/System")?))
+        Cache::class(env, "java/lang/System")
     }
 
     /// java.lang.CharSequence
     pub fn CharSequence(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/lang/CharSequence")?))
+        Cache::class(env, "java/lang/CharSequence")
     }
 
     /// java.lang.Math
     pub fn Math(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/lang/Math")?))
+        Cache::class(env, "java/lang/Math")
     }
 
     /// java.lang.Record
     pub fn Record(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/lang/Record")?))
+        Cache::class(env, "java/lang/Record")
     }
 
     /// java.lang.String
     pub fn String(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/lang/String")?))
+        Cache::class(env, "java/lang/String")
     }
 
     /// java.lang.StringBuilder
     pub fn StringBuilder(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/lang/StringBuilder")?))
+        Cache::class(env, "java/lang/StringBuilder")
     }
 
     /// java.math.BigDecimal
     pub fn BigDecimal(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/math/BigDecimal")?))
+        Cache::class(env, "java/math/BigDecimal")
     }
 
     /// java.math.BigInteger
     pub fn BigInteger(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/math/BigInteger")?))
+        Cache::class(env, "java/math/BigInteger")
     }
 
     /// java.lang.reflect.Array
     pub fn Array(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/lang/reflect/Array")?))
+        Cache::class(env, "java/lang/reflect/Array")
     }
 
     /// java.lang.reflect.Field
     pub fn Field(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/lang/reflect/Field")?))
+        Cache::class(env, "java/lang/reflect/Field")
     }
 
     /// java.lang.reflect.Method
     pub fn Method(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/lang/reflect/Method")?))
+        Cache::class(env, "java/lang/reflect/Method")
     }
 
     /// java.lang.reflect.Constructor
     pub fn Constructor(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(
-            env,
-            env.find_class("java/lang/reflect/Constructor")?,
-        ))
+        Cache::class(env, "java/lang/reflect/Constructor")
     }
 
     /// java.util.Uuid
     pub fn Uuid(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/util/UUID")?))
+        Cache::class(env, "java/util/UUID")
     }
 
     /// java.util.Vector
     pub fn Vector(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/util/Vector")?))
+        Cache::class(env, "java/util/Vector")
     }
 
     /// java.util.Map.Entry
     pub fn MapEntry(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/util/Map$Entry")?))
+        Cache::class(env, "java/util/Map$Entry")
     }
 
     /// java.util.Set
     pub fn Set(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/util/Set")?))
+        Cache::class(env, "java/util/Set")
     }
 
     /// java.util.Scanner
     pub fn Scanner(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/util/Scanner")?))
+        Cache::class(env, "java/util/Scanner")
     }
 
     /// java.util.Queue
     pub fn Queue(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/util/Queue")?))
+        Cache::class(env, "java/util/Queue")
     }
 
     /// java.util.Random
     pub fn Random(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/util/Random")?))
+        Cache::class(env, "java/util/Random")
     }
 
     /// java.util.Properties
     pub fn Properties(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/util/Properties")?))
+        Cache::class(env, "java/util/Properties")
     }
 
     /// java.util.Optional
     pub fn Optional(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/util/Optional")?))
+        Cache::class(env, "java/util/Optional")
     }
 
     /// java.util.Objects
     pub fn Objects(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/util/Objects")?))
+        Cache::class(env, "java/util/Objects")
     }
 
     /// java.util.Map
     pub fn Map(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/util/Map")?))
+        Cache::class(env, "java/util/Map")
     }
 
     /// java.util.Locale
     pub fn Locale(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/util/Locale")?))
+        Cache::class(env, "java/util/Locale")
     }
 
     /// java.util.List
     pub fn List(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/util/List")?))
+        Cache::class(env, "java/util/List")
     }
 
     /// java.util.LinkedList
     pub fn LinkedList(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/util/LinkedList")?))
+        Cache::class(env, "java/util/LinkedList")
     }
 
     /// java.util.LinkedHashSet
     pub fn LinkedHashSet(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/util/LinkedHashSet")?))
+        Cache::class(env, "java/util/LinkedHashSet")
     }
 
     /// java.util.LinkedHashMap
     pub fn LinkedHashMap(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/util/LinkedHashMap")?))
+        Cache::class(env, "java/util/LinkedHashMap")
     }
 
     /// java.util.Iterator
     pub fn Iterator(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/util/Iterator")?))
+        Cache::class(env, "java/util/Iterator")
     }
 
     /// java.util.IdentityHashMap
     pub fn IdentityHashMap(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/util/IdentityHashMap")?))
+        Cache::class(env, "java/util/IdentityHashMap")
     }
 
     /// java.util.HashTable
     pub fn Hashtable(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/util/Hashtable")?))
+        Cache::class(env, "java/util/Hashtable")
     }
 
     /// java.util.HashSet
     pub fn HashSet(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/util/HashSet")?))
+        Cache::class(env, "java/util/HashSet")
     }
 
     /// java.util.HashMap
     pub fn HashMap(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/util/HashMap")?))
+        Cache::class(env, "java/util/HashMap")
     }
 
     /// java.util.EnumSet
     pub fn EnumSet(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/util/EnumSet")?))
+        Cache::class(env, "java/util/EnumSet")
     }
 
     /// java.util.EnumMap
     pub fn EnumMap(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/util/EnumMap")?))
+        Cache::class(env, "java/util/EnumMap")
     }
 
     /// java.util.Enumeration
     pub fn Enumeration(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/util/Enumeration")?))
+        Cache::class(env, "java/util/Enumeration")
     }
 
     /// java.util.Comparator
     pub fn Comparator(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/util/Comparator")?))
+        Cache::class(env, "java/util/Comparator")
     }
 
     /// java.util.Comparators
     pub fn Comparators(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/util/Comparators")?))
+        Cache::class(env, "java/util/Comparators")
     }
 
     /// java.util.Collection
     pub fn Collection(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/util/Collection")?))
+        Cache::class(env, "java/util/Collection")
     }
 
     /// java.util.Base64
     pub fn Base64(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/util/Base64")?))
+        Cache::class(env, "java/util/Base64")
     }
 
     /// java.util.Arrays
     pub fn Arrays(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/util/Arrays")?))
+        Cache::class(env, "java/util/Arrays")
     }
 
     /// java.util.ArrayList
     pub fn ArrayList(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/util/ArrayList")?))
+        Cache::class(env, "java/util/ArrayList")
     }
 
     /// java.util.concurrent.atomic.AtomicInteger
     pub fn AtomicInteger(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(
-            env,
-            env.find_class("java/util/concurrent/atomic/AtomicInteger")?,
-        ))
+        Cache::class(env, "java/util/concurrent/atomic/AtomicInteger")
     }
 
     /// java.util.concurrent.atomic.AtomicLong
     pub fn AtomicLong(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(
-            env,
-            env.find_class("java/util/concurrent/atomic/AtomicLong")?,
-        ))
+        Cache::class(env, "java/util/concurrent/atomic/AtomicLong")
     }
 
     /// java.util.concurrent.atomic.AtomicReference
     pub fn AtomicReference(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(
-            env,
-            env.find_class("java/util/concurrent/atomic/AtomicReference")?,
-        ))
+        Cache::class(env, "java/util/concurrent/atomic/AtomicReference")
     }
 
     /// java.util.concurrent.atomic.AtomicIntegerArray
     pub fn AtomicIntegerArray(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(
-            env,
-            env.find_class("java/util/concurrent/atomic/AtomicIntegerArray")?,
-        ))
+        Cache::class(env, "java/util/concurrent/atomic/AtomicIntegerArray")
     }
 
     /// java.util.concurrent.atomic.AtomicIntegerFieldUpdater
     pub fn AtomicIntegerFieldUpdater(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(
-            env,
-            env.find_class("java/util/concurrent/atomic/AtomicIntegerFieldUpdater")?,
-        ))
+        Cache::class(env, "java/util/concurrent/atomic/AtomicIntegerFieldUpdater")
     }
 
     /// java.util.Data
     pub fn Date(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/util/Date")?))
+        Cache::class(env, "java/util/Date")
     }
 
     /// java.net.URI
     pub fn URI(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/net/URI")?))
+        Cache::class(env, "java/net/URI")
     }
 
     /// java.util.concurrent.atomic.AtomicLongArray
     pub fn AtomicLongArray(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(
-            env,
-            env.find_class("java/util/concurrent/atomic/AtomicLongArray")?,
-        ))
+        Cache::class(env, "java/util/concurrent/atomic/AtomicLongArray")
     }
 
     /// java.util.concurrent.atomic.AtomicLongFieldUpdater
     pub fn AtomicLongFieldUpdater(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(
-            env,
-            env.find_class("java/util/concurrent/atomic/AtomicLongFieldUpdater")?,
-        ))
+        Cache::class(env, "java/util/concurrent/atomic/AtomicLongFieldUpdater")
     }
 
     /// java.util.concurrent.atomic.AtomicMarkableReference
     pub fn AtomicMarkableReference(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(
-            env,
-            env.find_class("java/util/concurrent/atomic/AtomicMarkableReference")?,
-        ))
+        Cache::class(env, "java/util/concurrent/atomic/AtomicMarkableReference")
     }
 
     /// java.util.concurrent.atomic.AtomicReferenceArray
     pub fn AtomicReferenceArray(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(
-            env,
-            env.find_class("java/util/concurrent/atomic/AtomicReferenceArray")?,
-        ))
+        Cache::class(env, "java/util/concurrent/atomic/AtomicReferenceArray")
     }
 
     /// java.util.concurrent.atomic.AtomicReferenceFieldUpdater
     pub fn AtomicReferenceFieldUpdater(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(
+        Cache::class(
             env,
-            env.find_class("java/util/concurrent/atomic/AtomicReferenceFieldUpdater")?,
-        ))
+            "java/util/concurrent/atomic/AtomicReferenceFieldUpdater",
+        )
     }
 
     /// java.util.concurrent.atomic.DoubleAccumulator
     pub fn DoubleAccumulator(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(
-            env,
-            env.find_class("java/util/concurrent/atomic/DoubleAccumulator")?,
-        ))
+        Cache::class(env, "java/util/concurrent/atomic/DoubleAccumulator")
     }
 
     /// java.util.concurrent.atomic.DoubleAdder
     pub fn DoubleAdder(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(
-            env,
-            env.find_class("java/util/concurrent/atomic/DoubleAdder")?,
-        ))
+        Cache::class(env, "java/util/concurrent/atomic/DoubleAdder")
     }
 
     /// java.util.concurrent.atomic.LongAccumulator
     pub fn LongAccumulator(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(
-            env,
-            env.find_class("java/util/concurrent/atomic/LongAccumulator")?,
-        ))
+        Cache::class(env, "java/util/concurrent/atomic/LongAccumulator")
     }
 
     /// java.util.concurrent.atomic.LongAdder
     pub fn LongAdder(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(
-            env,
-            env.find_class("java/util/concurrent/atomic/LongAdder")?,
-        ))
+        Cache::class(env, "java/util/concurrent/atomic/LongAdder")
     }
 
     /// java.util.concurrent.atomic.Striped64
     pub fn Striped64(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(
-            env,
-            env.find_class("java/util/concurrent/atomic/Striped64")?,
-        ))
+        Cache::class(env, "java/util/concurrent/atomic/Striped64")
     }
 
     /// java.util.concurrent.Future
     pub fn Future(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(
-            env,
-            env.find_class("java/util/concurrent/Future")?,
-        ))
+        Cache::class(env, "java/util/concurrent/Future")
     }
 
     /// java.util.concurrent.TimeUnit
     pub fn TimeUnit(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(
-            env,
-            env.find_class("java/util/concurrent/TimeUnit")?,
-        ))
+        Cache::class(env, "java/util/concurrent/TimeUnit")
     }
 
     /// java.util.regex.Pattern
     pub fn Pattern(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/util/regex/Pattern")?))
+        Cache::class(env, "java/util/regex/Pattern")
     }
 
     /// java.util.regex.Matcher
     pub fn Matcher(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/util/regex/Matcher")?))
+        Cache::class(env, "java/util/regex/Matcher")
     }
 
     /// java.time.Duration
     pub fn Duration(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/time/Duration")?))
+        Cache::class(env, "java/time/Duration")
     }
 
     /// java.time.Instant
     pub fn Instant(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/time/Instant")?))
+        Cache::class(env, "java/time/Instant")
     }
 
     /// java.io.File
     pub fn File(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/io/File")?))
+        Cache::class(env, "java/io/File")
     }
 
     /// java.io.InputStream
     pub fn InputStream(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/io/InputStream")?))
+        Cache::class(env, "java/io/InputStream")
     }
 
     /// java.io.OutputStream
     pub fn OutputStream(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("java/io/OutputStream")?))
+        Cache::class(env, "java/io/OutputStream")
     }
 
     /// sun.misc.Unsafe
     pub fn Unsafe(env: &'a JNIEnv<'a>) -> Result<Self> {
-        Ok(Self::new(env, env.find_class("sun/misc/Unsafe")?))
+        Cache::class(env, "sun/misc/Unsafe")
+    }
+
+    /// java.nio.ByteBuffer
+    pub fn ByteBuffer(env: &'a JNIEnv<'a>) -> Result<Self> {
+        Cache::class(env, "java/nio/ByteBuffer")
+    }
+
+    /// java.nio.ByteOrder
+    pub fn ByteOrder(env: &'a JNIEnv<'a>) -> Result<Self> {
+        Cache::class(env, "java/nio/ByteOrder")
+    }
+
+    /// java.lang.Runnable
+    pub fn Runnable(env: &'a JNIEnv<'a>) -> Result<Self> {
+        Cache::class(env, "java/lang/Runnable")
+    }
+
+    /// java.util.function.Function
+    pub fn Function(env: &'a JNIEnv<'a>) -> Result<Self> {
+        Cache::class(env, "java/util/function/Function")
+    }
+
+    /// java.util.function.Consumer
+    pub fn Consumer(env: &'a JNIEnv<'a>) -> Result<Self> {
+        Cache::class(env, "java/util/function/Consumer")
+    }
+
+    /// java.util.function.Predicate
+    pub fn Predicate(env: &'a JNIEnv<'a>) -> Result<Self> {
+        Cache::class(env, "java/util/function/Predicate")
+    }
+
+    /// java.util.function.Supplier
+    pub fn Supplier(env: &'a JNIEnv<'a>) -> Result<Self> {
+        Cache::class(env, "java/util/function/Supplier")
+    }
+
+    /// java.lang.ClassLoader
+    pub fn ClassLoader(env: &'a JNIEnv<'a>) -> Result<Self> {
+        Cache::class(env, "java/lang/ClassLoader")
+    }
+
+    /// java.lang.Thread
+    pub fn Thread(env: &'a JNIEnv<'a>) -> Result<Self> {
+        Cache::class(env, "java/lang/Thread")
     }
 
     /// Find a class by it's Java name. Can be in the format:
     /// - `java/lang/String`
     /// - `java.lang.String`
+    ///
+    /// The class is resolved through the ClassLoader set with [ClassLoader::set_default], or with `FindClass` if none is set
     pub fn for_name<S: AsRef<str>>(env: &'a JNIEnv<'a>, name: S) -> Result<Self> {
         let name = name.as_ref();
+        if let Some(loader) = ClassLoader::get_default(env)? {
+            return Self::for_name_in(env, name, &loader);
+        }
+
         let name_patched = name.replace('.', "/");
-        Ok(Self::new(env, env.find_class(&name_patched)?))
+        Ok(Self::new(env, env.find_class(&name_patched).catch(env)?))
+    }
+
+    /// Find a class by it's Java name through `loader`, in the same formats as [Class::for_name].
+    /// The class is initialized, like it is by `FindClass`. Invokes `Class#forName(String, boolean, ClassLoader)`
+    pub fn for_name_in<S: AsRef<str>>(
+        env: &'a JNIEnv<'a>,
+        name: S,
+        loader: &ClassLoader<'a>,
+    ) -> Result<Self> {
+        let name = JavaString::from_rust(env, name.as_ref().replace('/', "."))?;
+        let class = Cache::call_static_method(
+            env,
+            "java/lang/Class",
+            "forName",
+            signature!("(Ljava/lang/String;ZLjava/lang/ClassLoader;)Ljava/lang/Class;"),
+            &[
+                name.into(),
+                JValue::Bool(1),
+                JValue::Object(loader.inner.inner),
+            ],
+        )?
+        .l()?;
+        Ok(Self::new(env, JClass::from(class)))
+    }
+
+    /// Get the ClassLoader which loaded the current Class. Returns None for classes loaded by the bootstrap class loader.
+    /// Invokes `Class#getClassLoader()`
+    pub fn class_loader(&self) -> Result<Option<ClassLoader<'a>>> {
+        let loader = Cache::call_method(
+            self.env,
+            self.class,
+            "java/lang/Class",
+            "getClassLoader",
+            signature!("()Ljava/lang/ClassLoader;"),
+            &[],
+        )?
+        .l()?;
+        if loader.is_null() {
+            return Ok(None);
+        }
+        Ok(Some(ClassLoader::new(
+            self.env,
+            Object::new(self.env, loader, Class::ClassLoader(self.env)?),
+        )))
     }
 
-    /// Get the array type of a class. E.g `java.lang.String` results in `java.lang.String[]`
+    /// Get the array type of a class. E.g `java.lang.String` results in `java.lang.String[]`, and `int` in `int[]`
     pub fn array_type(&self, env: &'a JNIEnv<'a>) -> Result<Self> {
-        let arr = env.new_object_array(0, self.class, JObject::null())?;
-        let arr_class = env.get_object_class(arr)?;
+        if self.is_primitive()? {
+            // Object arrays can not have a primitive component type, so look the array Class up by its descriptor
+            return Cache::class(env, &self.descriptor()?.array().to_string());
+        }
+
+        let arr = env
+            .new_object_array(0, self.class, JObject::null())
+            .catch(env)?;
+        let arr_class = env.get_object_class(arr).catch(env)?;
         Ok(Self::new(env, arr_class))
     }
 
+    /// Get the component type of an array Class, e.g `int` for `int[]` and `java.lang.String[]` for `java.lang.String[][]`.
+    /// Returns None if the current Class is not an array. Invokes `Class#getComponentType()`
+    pub fn component_type(&self) -> Result<Option<Class<'a>>> {
+        let component = Cache::call_method(
+            self.env,
+            self.class,
+            "java/lang/Class",
+            "getComponentType",
+            signature!("()Ljava/lang/Class;"),
+            &[],
+        )?
+        .l()?;
+        match component.is_null() {
+            true => Ok(None),
+            false => Ok(Some(Class::new(self.env, component.into()))),
+        }
+    }
+
+    /// Check if the current Class is an array Class. Invokes `Class#isArray()`
+    pub fn is_array(&self) -> Result<bool> {
+        let is_array = Cache::call_method(
+            self.env,
+            self.class,
+            "java/lang/Class",
+            "isArray",
+            signature!("()Z"),
+            &[],
+        )?;
+        Ok(is_array.z()?)
+    }
+
+    /// Check if the current Class is a primitive type, e.g `int`. Invokes `Class#isPrimitive()`
+    pub fn is_primitive(&self) -> Result<bool> {
+        let is_primitive = Cache::call_method(
+            self.env,
+            self.class,
+            "java/lang/Class",
+            "isPrimitive",
+            signature!("()Z"),
+            &[],
+        )?;
+        Ok(is_primitive.z()?)
+    }
+
     /// Check if the current Class can be safely cast to the the other Class. E.g `java.util.HashMap` is compatible with `java.util.Map`
     pub fn is_compatible(&self, other: &Class<'a>) -> Result<bool> {
-        self.env.is_assignable_from(self.class, other.class)
+        Ok(self.env.is_assignable_from(self.class, other.class)?)
     }
 
     /// Get the superclass of the current Class. Returns None if the current class has no superclass other than java.lang.Object, or if the current Class is an interface
     pub fn get_superclass(&self) -> Result<Option<Class<'a>>> {
-        let maybe_superclass = self.env.get_superclass(self.class)?;
+        let maybe_superclass = self.env.get_superclass(self.class).catch(self.env)?;
         match maybe_superclass.is_null() {
             true => Ok(None),
             false => Ok(Some(Class::new(self.env, maybe_superclass))),
@@ -529,10 +632,15 @@ impl<'a> Class<'a> {
 
     /// Get the Class name. Invokes `Class#getName()`
     pub fn get_name(&self) -> Result<String> {
-        let class_name_object = self
-            .env
-            .call_method(self.class, "getName", "()Ljava/lang/String;", &[])?
-            .l()?;
+        let class_name_object = Cache::call_method(
+            self.env,
+            self.class,
+            "java/lang/Class",
+            "getName",
+            signature!("()Ljava/lang/String;"),
+            &[],
+        )?
+        .l()?;
         let class_name_string = JavaString::new(
             self.env,
             Object::new(self.env, class_name_object, Class::String(self.env)?),
@@ -540,92 +648,199 @@ impl<'a> Class<'a> {
         class_name_string.into_rust()
     }
 
+    /// Get the JNI type descriptor of the Class, e.g `I` for `int`, `Ljava/lang/String;` for `java.lang.String` and `[I` for `int[]`
+    pub fn descriptor(&self) -> Result<TypeDescriptor> {
+        let name = self.get_name()?;
+        let descriptor = match name.as_str() {
+            "boolean" => TypeDescriptor::Boolean,
+            "byte" => TypeDescriptor::Byte,
+            "char" => TypeDescriptor::Char,
+            "short" => TypeDescriptor::Short,
+            "int" => TypeDescriptor::Int,
+            "long" => TypeDescriptor::Long,
+            "float" => TypeDescriptor::Float,
+            "double" => TypeDescriptor::Double,
+            "void" => TypeDescriptor::Void,
+            // The names of array Classes are descriptors, e.g `[Ljava.lang.String;`
+            array if array.starts_with('[') => TypeDescriptor::parse(&array.replace('.', "/"))
+                .expect("The JVM returned an invalid array Class name"),
+            class => TypeDescriptor::object(class),
+        };
+        Ok(descriptor)
+    }
+
+    /// Get the modifiers of the Class. Invokes `Class#getModifiers()`
+    pub fn modifiers(&self) -> Result<Modifiers> {
+        let modifiers = Cache::call_method(
+            self.env,
+            self.class,
+            "java/lang/Class",
+            "getModifiers",
+            signature!("()I"),
+            &[],
+        )?
+        .i()?;
+        Ok(Modifiers(modifiers))
+    }
+
+    /// Create a builder registering Rust functions as the native methods of the Class, see [NativeRegistry]
+    pub fn natives(&self) -> NativeRegistry<'a> {
+        NativeRegistry::new(self.env, self.clone())
+    }
+
+    /// Get the public methods of the Class, including those inherited from superclasses and interfaces. Invokes `Class#getMethods()`
+    pub fn methods(&self) -> Result<Vec<Method<'a>>> {
+        reflect::members(
+            self.env,
+            self,
+            "getMethods",
+            signature!("()[Ljava/lang/reflect/Method;"),
+            Method::new,
+        )
+    }
+
+    /// Get all methods declared by the Class, regardless of their visibility, excluding inherited methods. Invokes `Class#getDeclaredMethods()`
+    pub fn declared_methods(&self) -> Result<Vec<Method<'a>>> {
+        reflect::members(
+            self.env,
+            self,
+            "getDeclaredMethods",
+            signature!("()[Ljava/lang/reflect/Method;"),
+            Method::new,
+        )
+    }
+
+    /// Get the public fields of the Class, including those inherited from superclasses and interfaces. Invokes `Class#getFields()`
+    pub fn fields(&self) -> Result<Vec<Field<'a>>> {
+        reflect::members(
+            self.env,
+            self,
+            "getFields",
+            signature!("()[Ljava/lang/reflect/Field;"),
+            Field::new,
+        )
+    }
+
+    /// Get all fields declared by the Class, regardless of their visibility, excluding inherited fields. Invokes `Class#getDeclaredFields()`
+    pub fn declared_fields(&self) -> Result<Vec<Field<'a>>> {
+        reflect::members(
+            self.env,
+            self,
+            "getDeclaredFields",
+            signature!("()[Ljava/lang/reflect/Field;"),
+            Field::new,
+        )
+    }
+
+    /// Get the public constructors of the Class. Invokes `Class#getConstructors()`
+    pub fn constructors(&self) -> Result<Vec<Constructor<'a>>> {
+        reflect::members(
+            self.env,
+            self,
+            "getConstructors",
+            signature!("()[Ljava/lang/reflect/Constructor;"),
+            Constructor::new,
+        )
+    }
+
+    /// Get all constructors declared by the Class, regardless of their visibility. Invokes `Class#getDeclaredConstructors()`
+    pub fn declared_constructors(&self) -> Result<Vec<Constructor<'a>>> {
+        reflect::members(
+            self.env,
+            self,
+            "getDeclaredConstructors",
+            signature!("()[Ljava/lang/reflect/Constructor;"),
+            Constructor::new,
+        )
+    }
+
+    /// Get the value of a static field declared by this Class or any of its superclasses, regardless of its visibility.
+    /// Primitive fields are boxed before they are converted into `T`. Returns None if the field is `null`
+    pub fn get_static_field<T: FromJava<'a>>(&self, name: &str) -> Result<Option<T>> {
+        field::get_static_field(self.env, self, name)
+    }
+
+    /// Set the value of a static field declared by this Class or any of its superclasses, regardless of its visibility.
+    /// For primitive fields `value` must convert into the boxed type of the field, e.g `i32` for an `int` field
+    pub fn set_static_field<T: IntoJava<'a>>(&self, name: &str, value: T) -> Result<()> {
+        field::set_static_field(self.env, self, name, value)
+    }
+
+    /// Call a public static method. The signature is built from the descriptors of the arguments and of `R`, see [Object::call]
+    pub fn call_static<R: FromJava<'a>, A: IntoArgs<'a>>(
+        &self,
+        name: &str,
+        args: A,
+    ) -> Result<Option<R>> {
+        method::call_static(self.env, self, name, args, false)
+    }
+
+    /// Call a public static method, picking the overload through `Class#getMethods()`, see [Object::call_resolved]
+    pub fn call_static_resolved<R: FromJava<'a>, A: IntoArgs<'a>>(
+        &self,
+        name: &str,
+        args: A,
+    ) -> Result<Option<R>> {
+        method::call_static(self.env, self, name, args, true)
+    }
+
+    /// Get a Java primitive Class by its name, e.g `int`
+    fn primitive(env: &'a JNIEnv<'a>, name: &str) -> Result<Self> {
+        Cache::class_with(env, name, |env| {
+            let class = Cache::call_static_method(
+                env,
+                "java/lang/Class",
+                "getPrimitiveClass",
+                signature!("(Ljava/lang/String;)Ljava/lang/Class;"),
+                &[JavaString::from_rust(env, name)?.into()],
+            )?;
+            Ok(JClass::from(class.l()?))
+        })
+    }
+
     /// The Java primitive `int`
     pub fn int(env: &'a JNIEnv<'a>) -> Result<Self> {
-        let int_class = env.call_static_method(
-            "java/lang/Class",
-            "getPrimitiveClass",
-            "(Ljava/lang/String;)Ljava/lang/Class;",
-            &[JavaString::from_rust(env, "int")?.into()],
-        )?;
-        Ok(Self::new(env, JClass::from(int_class.l()?)))
+        Self::primitive(env, "int")
     }
 
     /// The Java primitive `long`
     pub fn long(env: &'a JNIEnv<'a>) -> Result<Self> {
-        let int_class = env.call_static_method(
-            "java/lang/Class",
-            "getPrimitiveClass",
-            "(Ljava/lang/String;)Ljava/lang/Class;",
-            &[JavaString::from_rust(env, "long")?.into()],
-        )?;
-        Ok(Self::new(env, JClass::from(int_class.l()?)))
+        Self::primitive(env, "long")
     }
 
     /// The Java primitive `byte`
     pub fn byte(env: &'a JNIEnv<'a>) -> Result<Self> {
-        let int_class = env.call_static_method(
-            "java/lang/Class",
-            "getPrimitiveClass",
-            "(Ljava/lang/String;)Ljava/lang/Class;",
-            &[JavaString::from_rust(env, "byte")?.into()],
-        )?;
-        Ok(Self::new(env, JClass::from(int_class.l()?)))
+        Self::primitive(env, "byte")
     }
 
     /// The Java primitive `boolean`
     pub fn boolean(env: &'a JNIEnv<'a>) -> Result<Self> {
-        let int_class = env.call_static_method(
-            "java/lang/Class",
-            "getPrimitiveClass",
-            "(Ljava/lang/String;)Ljava/lang/Class;",
-            &[JavaString::from_rust(env, "boolean")?.into()],
-        )?;
-        Ok(Self::new(env, JClass::from(int_class.l()?)))
+        Self::primitive(env, "boolean")
     }
 
     /// The Java primitive `float`
     pub fn float(env: &'a JNIEnv<'a>) -> Result<Self> {
-        let int_class = env.call_static_method(
-            "java/lang/Class",
-            "getPrimitiveClass",
-            "(Ljava/lang/String;)Ljava/lang/Class;",
-            &[JavaString::from_rust(env, "float")?.into()],
-        )?;
-        Ok(Self::new(env, JClass::from(int_class.l()?)))
+        Self::primitive(env, "float")
     }
 
     /// The Java primitive `double`
     pub fn double(env: &'a JNIEnv<'a>) -> Result<Self> {
-        let int_class = env.call_static_method(
-            "java/lang/Class",
-            "getPrimitiveClass",
-            "(Ljava/lang/String;)Ljava/lang/Class;",
-            &[JavaString::from_rust(env, "double")?.into()],
-        )?;
-        Ok(Self::new(env, JClass::from(int_class.l()?)))
+        Self::primitive(env, "double")
     }
 
     /// The Java primitive `short`
     pub fn short(env: &'a JNIEnv<'a>) -> Result<Self> {
-        let int_class = env.call_static_method(
-            "java/lang/Class",
-            "getPrimitiveClass",
-            "(Ljava/lang/String;)Ljava/lang/Class;",
-            &[JavaString::from_rust(env, "short")?.into()],
-        )?;
-        Ok(Self::new(env, JClass::from(int_class.l()?)))
+        Self::primitive(env, "short")
     }
 
     /// The Java primitive `char`
     pub fn char(env: &'a JNIEnv<'a>) -> Result<Self> {
-        let int_class = env.call_static_method(
-            "java/lang/Class",
-            "getPrimitiveClass",
-            "(Ljava/lang/String;)Ljava/lang/Class;",
-            &[JavaString::from_rust(env, "char")?.into()],
-        )?;
-        Ok(Self::new(env, JClass::from(int_class.l()?)))
+        Self::primitive(env, "char")
+    }
+
+    /// The Java pseudo-type `void`
+    pub fn void(env: &'a JNIEnv<'a>) -> Result<Self> {
+        Self::primitive(env, "void")
     }
 }
 
@@ -633,8 +848,8 @@ impl<'a> Class<'a> {
 mod test {
     #![allow(non_snake_case)]
     use super::Class;
+    use crate::error::Result;
     use crate::test::*;
-    use jni::errors::Result;
 
     #[test]
     fn Byte() {
@@ -1174,6 +1389,69 @@ mod test {
         assert!(Class::Unsafe(&env).is_ok());
     }
 
+    #[test]
+    fn ByteBuffer() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        assert!(Class::ByteBuffer(&env).is_ok());
+    }
+
+    #[test]
+    fn ByteOrder() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        assert!(Class::ByteOrder(&env).is_ok());
+    }
+
+    #[test]
+    fn Runnable() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        assert!(Class::Runnable(&env).is_ok());
+    }
+
+    #[test]
+    fn Function() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        assert!(Class::Function(&env).is_ok());
+    }
+
+    #[test]
+    fn Consumer() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        assert!(Class::Consumer(&env).is_ok());
+    }
+
+    #[test]
+    fn Predicate() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        assert!(Class::Predicate(&env).is_ok());
+    }
+
+    #[test]
+    fn Supplier() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        assert!(Class::Supplier(&env).is_ok());
+    }
+
+    #[test]
+    fn ClassLoader() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        assert!(Class::ClassLoader(&env).is_ok());
+    }
+
+    #[test]
+    fn Thread() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        assert!(Class::Thread(&env).is_ok());
+    }
+
     #[test]
     fn Date() {
         let jvm = JVM.lock().unwrap();
@@ -1209,13 +1487,46 @@ mod test {
         assert!(Class::BigInteger(&env).unwrap().array_type(&env).is_ok());
     }
 
+    #[test]
+    fn array_type_primitive() -> Result<()> {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let int_array = Class::int(&env)?.array_type(&env)?;
+        assert_eq!("[I", int_array.get_name()?);
+        assert_eq!("[[I", int_array.array_type(&env)?.get_name()?);
+        Ok(())
+    }
+
+    #[test]
+    fn component_type() -> Result<()> {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let string_array = Class::String(&env)?.array_type(&env)?.array_type(&env)?;
+        assert!(string_array.is_array()?);
+        let component = string_array.component_type()?.unwrap();
+        assert_eq!("[Ljava.lang.String;", component.get_name()?);
+        let component = component.component_type()?.unwrap();
+        assert_eq!("java.lang.String", component.get_name()?);
+        assert!(!component.is_array()?);
+        assert!(component.component_type()?.is_none());
+
+        let int = Class::int(&env)?
+            .array_type(&env)?
+            .component_type()?
+            .unwrap();
+        assert!(int.is_primitive()?);
+        assert!(!Class::Integer(&env)?.is_primitive()?);
+        Ok(())
+    }
+
     #[test]
     fn is_compatible() -> Result<()> {
         let jvm = JVM.lock().unwrap();
         let env = jvm.attach_current_thread().unwrap();
 
-        let hashmap =
-            crate::abstractions::Map::hashmap(&env, Class::Object(&env)?, Class::Object(&env)?)?;
+        let hashmap = crate::abstractions::Map::<crate::Object, crate::Object>::hashmap(&env)?;
 
         let is_compat = hashmap.inner.class.is_compatible(&Class::Map(&env)?)?;
         assert!(is_compat);
@@ -0,0 +1,427 @@
+use crate::cache::Cache;
+use crate::class::Class;
+use crate::error::{Error, JniResultExt, Result};
+use crate::java_type::{FromJava, IntoJava};
+use crate::native::{throw_on_err, ThrowableError};
+use crate::object::Object;
+use crate::private::{into_return, thrown, Thrown};
+use crate::signature;
+use crate::signature::{Signature, TypeDescriptor};
+use jni::objects::{JClass, JObject, JValue};
+use jni::sys::{jlong, jobject, jobjectArray};
+use jni::JNIEnv;
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::ffi::c_void;
+use std::sync::{Mutex, PoisonError};
+
+/// The binary name of the bundled invocation handler
+const HANDLER_CLASS: &str = "ejni/RustClosure";
+
+/// The compiled invocation handler, from `java/ejni/RustClosure.java`
+const HANDLER_BYTES: &[u8] = include_bytes!("../java/ejni/RustClosure.class");
+
+/// A closure as it is stored behind the pointer held by the Java invocation handler
+type Callback = Box<
+    dyn for<'b> Fn(&'b JNIEnv<'b>, Vec<Object<'b>>) -> std::result::Result<jobject, Thrown<'b>>
+        + Send
+        + Sync,
+>;
+
+/// Turns Rust closures into Java objects implementing a functional interface, e.g `java.lang.Runnable` or `java.util.Comparator`.
+///
+/// The object is a `java.lang.reflect.Proxy`, whose invocation handler is a small Java class bundled with eJNI.
+/// The handler is defined in the bootstrap class loader with `DefineClass` the first time a closure is created.
+/// Calls to the abstract method of the interface are passed to the closure, `equals`, `hashCode` and `toString` are implemented in Java,
+/// and default methods are invoked as declared by the interface (this requires Java 16 or newer).
+///
+/// Ownership of the closure moves to the Java object. It is dropped by a `java.lang.ref.Cleaner` once the object has been garbage collected,
+/// which requires Java 9 or newer. As Java may invoke the object from any thread, the closure must be `Send` and `Sync`.
+/// Errors returned by the closure, and panics, are thrown into Java, see [throw_on_err].
+pub struct Closure;
+
+impl Closure {
+    /// Create an object implementing the functional interface `interface`, which calls `f` with the arguments of the invoked method.
+    /// Primitive arguments are boxed. The returned Object is returned to Java, and unboxed if the method returns a primitive
+    pub fn implement<'a, F, E>(
+        env: &'a JNIEnv<'a>,
+        interface: &Class<'a>,
+        f: F,
+    ) -> Result<Object<'a>>
+    where
+        F: for<'b> Fn(
+                &'b JNIEnv<'b>,
+                Vec<Object<'b>>,
+            ) -> std::result::Result<Option<Object<'b>>, E>
+            + Send
+            + Sync
+            + 'static,
+        E: ThrowableError + 'static,
+    {
+        let callback: Callback = Box::new(move |env, args| match f(env, args) {
+            Ok(Some(object)) => Ok(object.inner.into_inner()),
+            Ok(None) => Ok(std::ptr::null_mut()),
+            Err(e) => Err(thrown(e)),
+        });
+        Self::create(env, interface, callback)
+    }
+
+    /// Create a `java.lang.Runnable`
+    pub fn runnable<'a, F>(env: &'a JNIEnv<'a>, f: F) -> Result<Object<'a>>
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        let callback: Callback = Box::new(move |_, _| {
+            f();
+            Ok(std::ptr::null_mut())
+        });
+        Self::create(env, &Class::Runnable(env)?, callback)
+    }
+
+    /// Create a `java.util.function.Supplier`
+    pub fn supplier<'a, R, F>(env: &'a JNIEnv<'a>, f: F) -> Result<Object<'a>>
+    where
+        R: for<'b> IntoJava<'b>,
+        F: Fn() -> R + Send + Sync + 'static,
+    {
+        let callback: Callback = Box::new(move |env, _| into_return(env, f()).map_err(thrown));
+        Self::create(env, &Class::Supplier(env)?, callback)
+    }
+
+    /// Create a `java.util.function.Function`. The argument is converted into `T` after checking its Class, see [Object::cast]
+    pub fn function<'a, T, R, F>(env: &'a JNIEnv<'a>, f: F) -> Result<Object<'a>>
+    where
+        T: for<'b> FromJava<'b>,
+        R: for<'b> IntoJava<'b>,
+        F: Fn(T) -> R + Send + Sync + 'static,
+    {
+        let callback: Callback = Box::new(move |env, args| {
+            let [arg] = take_arguments(args)?;
+            into_return(env, f(arg.cast().map_err(thrown)?)).map_err(thrown)
+        });
+        Self::create(env, &Class::Function(env)?, callback)
+    }
+
+    /// Create a `java.util.function.Consumer`. The argument is converted into `T` after checking its Class, see [Object::cast]
+    pub fn consumer<'a, T, F>(env: &'a JNIEnv<'a>, f: F) -> Result<Object<'a>>
+    where
+        T: for<'b> FromJava<'b>,
+        F: Fn(T) + Send + Sync + 'static,
+    {
+        let callback: Callback = Box::new(move |_, args| {
+            let [arg] = take_arguments(args)?;
+            f(arg.cast().map_err(thrown)?);
+            Ok(std::ptr::null_mut())
+        });
+        Self::create(env, &Class::Consumer(env)?, callback)
+    }
+
+    /// Create a `java.util.function.Predicate`. The argument is converted into `T` after checking its Class, see [Object::cast]
+    pub fn predicate<'a, T, F>(env: &'a JNIEnv<'a>, f: F) -> Result<Object<'a>>
+    where
+        T: for<'b> FromJava<'b>,
+        F: Fn(T) -> bool + Send + Sync + 'static,
+    {
+        let callback: Callback = Box::new(move |env, args| {
+            let [arg] = take_arguments(args)?;
+            into_return(env, f(arg.cast().map_err(thrown)?)).map_err(thrown)
+        });
+        Self::create(env, &Class::Predicate(env)?, callback)
+    }
+
+    /// Create a `java.util.Comparator`. The arguments are converted into `T` after checking their Class, see [Object::cast]
+    pub fn comparator<'a, T, F>(env: &'a JNIEnv<'a>, f: F) -> Result<Object<'a>>
+    where
+        T: for<'b> FromJava<'b>,
+        F: Fn(T, T) -> Ordering + Send + Sync + 'static,
+    {
+        let callback: Callback = Box::new(move |env, args| {
+            let [a, b] = take_arguments(args)?;
+            let ordering = f(a.cast().map_err(thrown)?, b.cast().map_err(thrown)?);
+            into_return(env, ordering as i32).map_err(thrown)
+        });
+        Self::create(env, &Class::Comparator(env)?, callback)
+    }
+
+    fn create<'a>(
+        env: &'a JNIEnv<'a>,
+        interface: &Class<'a>,
+        callback: Callback,
+    ) -> Result<Object<'a>> {
+        handler_class(env)?;
+
+        // Ownership of the closure moves to the handler as soon as it is constructed.
+        // Should creating the handler itself fail the closure is leaked, which is preferable over risking a double free
+        let ptr = Box::into_raw(Box::new(callback));
+        let proxy = Cache::call_static_method(
+            env,
+            HANDLER_CLASS,
+            "create",
+            signature!("(Ljava/lang/Class;J)Ljava/lang/Object;"),
+            &[
+                JValue::Object(interface.class.into()),
+                JValue::Long(ptr as jlong),
+            ],
+        )?
+        .l()?;
+        Ok(Object::new(env, proxy, interface.clone()))
+    }
+}
+
+/// Get the invocation handler Class, defining it and registering its native methods if that has not happened yet
+fn handler_class<'a>(env: &'a JNIEnv<'a>) -> Result<Class<'a>> {
+    // Only one thread may define the class
+    static DEFINE: Mutex<()> = Mutex::new(());
+    let _guard = DEFINE.lock().unwrap_or_else(PoisonError::into_inner);
+
+    Cache::class_with(env, HANDLER_CLASS, |env| {
+        if let Ok(class) = env.find_class(HANDLER_CLASS).catch(env) {
+            return Ok(class);
+        }
+
+        let class = env
+            .define_class(HANDLER_CLASS, JObject::null(), HANDLER_BYTES)
+            .catch(env)?;
+        let objects = TypeDescriptor::object("java/lang/Object").array();
+        Class::new(env, class)
+            .natives()
+            .method(
+                "call",
+                Signature::new(
+                    vec![TypeDescriptor::Long, objects],
+                    TypeDescriptor::object("java/lang/Object"),
+                ),
+                call as *mut c_void,
+            )
+            .method(
+                "drop",
+                Signature::new(vec![TypeDescriptor::Long], TypeDescriptor::Void),
+                drop_closure as *mut c_void,
+            )
+            .register()?;
+        Ok(class)
+    })
+}
+
+/// Take exactly `N` arguments. Java passes as many arguments as the functional interface declares,
+/// so a mismatch means the closure was created for the wrong interface
+fn take_arguments<'b, const N: usize>(
+    args: Vec<Object<'b>>,
+) -> std::result::Result<[Object<'b>; N], Thrown<'b>> {
+    <[Object<'b>; N]>::try_from(args).map_err(|args| {
+        thrown(Error::ArgumentCount {
+            expected: N,
+            found: args.len(),
+        })
+    })
+}
+
+/// `RustClosure#call(long, Object[])`
+extern "system" fn call(env: JNIEnv<'_>, _: JClass<'_>, ptr: jlong, args: jobjectArray) -> jobject {
+    let env = &env;
+    throw_on_err(env, || {
+        // Safety: `ptr` was created by `Closure::create`, and the handler keeps it alive while the closure runs
+        let callback = unsafe { &*(ptr as *const Callback) };
+        let args = arguments(env, args).map_err(thrown)?;
+        callback(env, args)
+    })
+}
+
+/// `RustClosure#drop(long)`, called by the Cleaner once the proxy is unreachable
+extern "system" fn drop_closure(env: JNIEnv<'_>, _: JClass<'_>, ptr: jlong) {
+    throw_on_err(&env, || {
+        // Safety: `ptr` was created by `Closure::create`, and the Cleaner runs this exactly once
+        drop(unsafe { Box::from_raw(ptr as *mut Callback) });
+        Ok::<_, Error>(())
+    })
+}
+
+/// Read the arguments of the invoked method. Each argument carries its runtime Class
+fn arguments<'a>(env: &'a JNIEnv<'a>, args: jobjectArray) -> Result<Vec<Object<'a>>> {
+    let len = env.get_array_length(args).catch(env)?;
+    (0..len)
+        .map(|i| {
+            let obj = env.get_object_array_element(args, i).catch(env)?;
+            let class = match obj.is_null() {
+                true => Class::Object(env)?,
+                false => Class::new(env, env.get_object_class(obj).catch(env)?),
+            };
+            Ok(Object::new(env, obj, class))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::JVM;
+    use crate::List;
+    use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use thiserror::Error;
+
+    #[derive(Debug, Error)]
+    #[error("Closure failed")]
+    struct Failed;
+
+    impl ThrowableError for Failed {
+        fn exception_class(&self) -> String {
+            "java/lang/IllegalStateException".to_string()
+        }
+    }
+
+    #[test]
+    fn runnable() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let count = Arc::new(AtomicUsize::new(0));
+        let counter = count.clone();
+        let runnable = Closure::runnable(&env, move || {
+            counter.fetch_add(1, AtomicOrdering::SeqCst);
+        })
+        .unwrap();
+
+        runnable.call::<(), _>("run", ()).unwrap();
+        runnable.call::<(), _>("run", ()).unwrap();
+        assert_eq!(2, count.load(AtomicOrdering::SeqCst));
+    }
+
+    #[test]
+    fn function() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let function = Closure::function(&env, |s: String| s.len() as i32).unwrap();
+        let len: i32 = function
+            .call_resolved("apply", ("Hello".to_string(),))
+            .unwrap()
+            .unwrap();
+        assert_eq!(5, len);
+    }
+
+    #[test]
+    fn supplier() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let supplier = Closure::supplier(&env, || "Hello".to_string()).unwrap();
+        let value: String = supplier.call_resolved("get", ()).unwrap().unwrap();
+        assert_eq!("Hello", value);
+    }
+
+    #[test]
+    fn comparator() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let list = List::<String>::arraylist(&env).unwrap();
+        for s in ["ccc", "a", "bb"] {
+            list.add(s.to_string()).unwrap();
+        }
+
+        let comparator =
+            Closure::comparator(&env, |a: String, b: String| a.len().cmp(&b.len())).unwrap();
+        list.inner
+            .call_resolved::<(), _>("sort", (comparator,))
+            .unwrap();
+        assert_eq!(vec!["a", "bb", "ccc"], list.to_vec().unwrap());
+    }
+
+    #[test]
+    fn default_method() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let predicate = Closure::predicate(&env, |i: i32| i > 0).unwrap();
+        let positive: bool = predicate.call_resolved("test", (1,)).unwrap().unwrap();
+        assert!(positive);
+
+        let negated: Object = predicate.call_resolved("negate", ()).unwrap().unwrap();
+        let positive: bool = negated.call_resolved("test", (1,)).unwrap().unwrap();
+        assert!(!positive);
+    }
+
+    #[test]
+    fn implement() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let consumer =
+            Closure::implement(&env, &Class::Consumer(&env).unwrap(), |_, args| match args
+                .into_iter()
+                .next()
+                .unwrap()
+                .cast::<String>()
+            {
+                Ok(_) => Ok(None),
+                Err(_) => Err(Failed),
+            })
+            .unwrap();
+
+        consumer
+            .call_resolved::<(), _>("accept", ("Hello".to_string(),))
+            .unwrap();
+        let err = consumer
+            .call_resolved::<(), _>("accept", (1,))
+            .map(|_| ())
+            .unwrap_err();
+        match err {
+            Error::JavaException(e) => {
+                assert_eq!("java.lang.IllegalStateException", e.class);
+                assert_eq!(Some("Closure failed".to_string()), e.message);
+            }
+            e => panic!("Unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn object_methods() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let runnable = Closure::runnable(&env, || {}).unwrap();
+        let other = Closure::runnable(&env, || {}).unwrap();
+        let name: String = runnable.call("toString", ()).unwrap().unwrap();
+        assert_eq!("RustClosure[java.lang.Runnable]", name);
+        assert!(runnable.equals(&runnable).unwrap());
+        assert!(!runnable.equals(&other).unwrap());
+    }
+
+    #[test]
+    fn dropped_after_gc() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let owned = Arc::new(());
+        let captured = owned.clone();
+        let runnable = Closure::runnable(&env, move || {
+            let _ = &captured;
+        })
+        .unwrap();
+        assert_eq!(2, Arc::strong_count(&owned));
+        env.delete_local_ref(runnable.inner).unwrap();
+
+        for _ in 0..50 {
+            env.call_static_method("java/lang/System", "gc", "()V", &[])
+                .unwrap();
+            if Arc::strong_count(&owned) == 1 {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        panic!("The closure was not dropped");
+    }
+
+    #[test]
+    fn argument_count() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let arg = Object::new_string(&env, "Foo").unwrap();
+        let err = take_arguments::<2>(vec![arg]).map(|_| ()).unwrap_err();
+        assert_eq!("java/lang/IllegalArgumentException", err.exception_class());
+        assert_eq!("Expected 2 arguments, but found 1", err.exception_message());
+    }
+}
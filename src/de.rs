@@ -0,0 +1,1165 @@
+use crate::array::ArrayContents;
+use crate::cache::Cache;
+use crate::class::Class;
+use crate::error::{Error, JavaException, JniResultExt, Result};
+use crate::frame::FrameGuard;
+use crate::native::{ThrowableError, RUNTIME_EXCEPTION};
+use crate::object::Object;
+use crate::signature;
+use crate::signature::TypeDescriptor;
+use jni::objects::{GlobalRef, JObject, JString};
+use jni::JNIEnv;
+use serde::de::value::{SeqDeserializer, StringDeserializer};
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, Visitor};
+use std::fmt::Display;
+use thiserror::Error;
+
+/// The number of local references each element can create before the JVM has to grow its local frame
+const FRAME_CAPACITY: i32 = 16;
+
+/// Describes the errors that can occur when deserializing a Rust value from Java objects
+#[derive(Debug, Error)]
+pub enum DeserializeError {
+    /// JNI Error
+    #[error("JNI Error: {0}")]
+    Jni(#[from] jni::errors::Error),
+    /// A Java exception was thrown
+    #[error("Java exception: {0}")]
+    JavaException(#[from] JavaException),
+    /// The Class of an Object does not match the Rust type it is deserialized into
+    #[error("Invalid type at ${path}: expected {expected}, found {found}")]
+    InvalidType {
+        /// The location of the Object within the object graph, e.g `.tags[1]`. Empty for the root Object
+        path: String,
+        /// The Rust type that was expected, e.g `i32` or `a map`
+        expected: String,
+        /// The name of the Class that was found, or `null`
+        found: String,
+    },
+    /// Error raised by the `Deserialize` implementation of the value, e.g a missing field
+    #[error("{message} at ${path}")]
+    Custom {
+        /// The location of the Object within the object graph, e.g `.tags[1]`. Empty for the root Object
+        path: String,
+        /// The error message
+        message: String,
+    },
+    /// Any other eJNI error, e.g a null Object which can not be read
+    #[error("{error} at ${path}")]
+    Ejni {
+        /// The location of the Object within the object graph, e.g `.tags[1]`. Empty for the root Object
+        path: String,
+        /// The error itself, boxed as it is much larger than the other variants
+        #[source]
+        error: Box<Error>,
+    },
+}
+
+impl DeserializeError {
+    /// Prefix the path of the error with `segment`, as the error bubbles up through the object graph
+    fn at(mut self, segment: &str) -> Self {
+        match &mut self {
+            Self::InvalidType { path, .. }
+            | Self::Custom { path, .. }
+            | Self::Ejni { path, .. } => path.insert_str(0, segment),
+            _ => {}
+        }
+        self
+    }
+}
+
+impl From<Error> for DeserializeError {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::Jni(e) => Self::Jni(e),
+            Error::JavaException(e) => Self::JavaException(e),
+            error => Self::Ejni {
+                path: String::new(),
+                error: Box::new(error),
+            },
+        }
+    }
+}
+
+impl de::Error for DeserializeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self::Custom {
+            path: String::new(),
+            message: msg.to_string(),
+        }
+    }
+}
+
+impl ThrowableError for DeserializeError {
+    fn exception_class(&self) -> String {
+        match self {
+            Self::JavaException(e) => e.class.replace('.', "/"),
+            Self::InvalidType { .. } => "java/lang/ClassCastException".to_string(),
+            Self::Custom { .. } => "java/lang/IllegalArgumentException".to_string(),
+            Self::Ejni { error, .. } => error.exception_class(),
+            _ => RUNTIME_EXCEPTION.to_string(),
+        }
+    }
+
+    fn exception_message(&self) -> String {
+        match self {
+            Self::JavaException(e) => e.message.clone().unwrap_or_default(),
+            _ => self.to_string(),
+        }
+    }
+
+    fn throwable(&self) -> Option<&GlobalRef> {
+        match self {
+            Self::JavaException(e) => e.throwable.as_ref(),
+            Self::Ejni { error, .. } => error.throwable(),
+            _ => None,
+        }
+    }
+}
+
+/// Result returned from [from_java] and [Deserializer]
+pub type DeserializeResult<T> = std::result::Result<T, DeserializeError>;
+
+/// Deserialize a Rust value from `object`, see [Deserializer]
+///
+/// ```ignore
+/// #[derive(Deserialize)]
+/// struct Config {
+///     name: String,
+///     threads: i32,
+///     tags: Vec<String>,
+/// }
+///
+/// let config: Config = ejni::from_java(&map.inner)?;
+/// ```
+pub fn from_java<T: DeserializeOwned>(object: &Object<'_>) -> DeserializeResult<T> {
+    T::deserialize(Deserializer::new(object))
+}
+
+/// A `serde::Deserializer` reading Rust values from Java objects, driven by the runtime Class of each Object:
+///
+/// | Java                                                    | Deserialized as                        |
+/// |---------------------------------------------------------|----------------------------------------|
+/// | `null`                                                  | `None` or `()`                         |
+/// | `Boolean`, `Byte`, `Short`, `Integer`, `Long`, `Float`, `Double` | the matching Rust primitive, integers convert into any integer type they fit in |
+/// | `java.math.BigInteger`                                  | `i128` or `u128`                       |
+/// | `Character`                                             | `char`                                 |
+/// | `java.lang.String`                                      | a string, or a unit variant            |
+/// | `java.util.Map`                                         | a map or struct, or an enum variant if it has a single entry |
+/// | `java.util.Collection` and arrays                       | a sequence or tuple, `byte[]` also as bytes |
+/// | `java.util.Optional`                                    | an `Option`                            |
+/// | enum constants                                          | a unit variant, or a string of their name |
+///
+/// Errors report the location of the failing Object in the object graph, e.g `$.servers[2].port`
+pub struct Deserializer<'a> {
+    env: &'a JNIEnv<'a>,
+    object: JObject<'a>,
+}
+
+/// A getter of a cached Class, e.g [Class::String]
+type ClassGetter<'a> = fn(&'a JNIEnv<'a>) -> Result<Class<'a>>;
+
+/// The kind of an Object, determined by its runtime Class
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Null,
+    Boolean,
+    Byte,
+    Short,
+    Integer,
+    Long,
+    Float,
+    Double,
+    Character,
+    BigInteger,
+    String,
+    Map,
+    Collection,
+    Array,
+    Optional,
+    Enum,
+    Other,
+}
+
+impl Kind {
+    fn is_integer(self) -> bool {
+        matches!(
+            self,
+            Self::Byte | Self::Short | Self::Integer | Self::Long | Self::BigInteger
+        )
+    }
+
+    fn is_number(self) -> bool {
+        self.is_integer() || matches!(self, Self::Float | Self::Double)
+    }
+
+    fn is_sequence(self) -> bool {
+        matches!(self, Self::Collection | Self::Array)
+    }
+}
+
+impl<'a> Deserializer<'a> {
+    /// Create a Deserializer reading from `object`. The Object is not consumed, it must stay valid while the Deserializer is in use
+    pub fn new(object: &Object<'a>) -> Self {
+        Self::raw(object.env, object.inner)
+    }
+
+    fn raw(env: &'a JNIEnv<'a>, object: JObject<'a>) -> Self {
+        Self { env, object }
+    }
+
+    fn kind(&self) -> DeserializeResult<Kind> {
+        if self.object.is_null() {
+            return Ok(Kind::Null);
+        }
+
+        let env = self.env;
+        let kinds: [(ClassGetter<'a>, Kind); 14] = [
+            (Class::String, Kind::String),
+            (Class::Integer, Kind::Integer),
+            (Class::Long, Kind::Long),
+            (Class::Double, Kind::Double),
+            (Class::Boolean, Kind::Boolean),
+            (Class::Map, Kind::Map),
+            (Class::Collection, Kind::Collection),
+            (Class::Float, Kind::Float),
+            (Class::Short, Kind::Short),
+            (Class::Byte, Kind::Byte),
+            (Class::Character, Kind::Character),
+            (Class::BigInteger, Kind::BigInteger),
+            (Class::Optional, Kind::Optional),
+            (|env| Cache::class(env, "java/lang/Enum"), Kind::Enum),
+        ];
+        for (class, kind) in kinds.iter() {
+            if env
+                .is_instance_of(self.object, class(env)?.class)
+                .catch(env)?
+            {
+                return Ok(*kind);
+            }
+        }
+
+        let class = env.get_object_class(self.object).catch(env)?;
+        let is_array = Class::new(env, class).is_array()?;
+        env.delete_local_ref(class.into())?;
+        Ok(if is_array { Kind::Array } else { Kind::Other })
+    }
+
+    fn invalid_type(&self, expected: &str) -> DeserializeError {
+        let found = match self.class_name() {
+            Ok(found) => found,
+            Err(e) => return e,
+        };
+        DeserializeError::InvalidType {
+            path: String::new(),
+            expected: expected.to_string(),
+            found,
+        }
+    }
+
+    fn class_name(&self) -> DeserializeResult<String> {
+        if self.object.is_null() {
+            return Ok("null".to_string());
+        }
+
+        let class = self.env.get_object_class(self.object).catch(self.env)?;
+        let name = Class::new(self.env, class).get_name()?;
+        self.env.delete_local_ref(class.into())?;
+        Ok(name)
+    }
+
+    fn object(&self, class: Class<'a>) -> Object<'a> {
+        Object::new(self.env, self.object, class)
+    }
+
+    fn string(&self) -> DeserializeResult<String> {
+        Ok(self
+            .env
+            .get_string(JString::from(self.object))
+            .catch(self.env)?
+            .into())
+    }
+
+    /// Call a method returning a String on the Object, e.g `toString()`
+    fn call_string(&self, class: &str, method: &str) -> DeserializeResult<String> {
+        let string = Cache::call_method(
+            self.env,
+            self.object,
+            class,
+            method,
+            signature!("()Ljava/lang/String;"),
+            &[],
+        )?
+        .l()?;
+        let value = Self::raw(self.env, string).string();
+        self.env.delete_local_ref(string)?;
+        value
+    }
+
+    fn visit<'de, V: Visitor<'de>>(self, kind: Kind, visitor: V) -> DeserializeResult<V::Value> {
+        let env = self.env;
+        match kind {
+            Kind::Null => visitor.visit_unit(),
+            Kind::Boolean => visitor.visit_bool(self.object(Class::Boolean(env)?).get_boolean()?),
+            Kind::Byte => visitor.visit_i8(self.object(Class::Byte(env)?).get_byte()?),
+            Kind::Short => visitor.visit_i16(self.object(Class::Short(env)?).get_short()?),
+            Kind::Integer => visitor.visit_i32(self.object(Class::Integer(env)?).get_integer()?),
+            Kind::Long => visitor.visit_i64(self.object(Class::Long(env)?).get_long()?),
+            Kind::Float => visitor.visit_f32(self.object(Class::Float(env)?).get_float()?),
+            Kind::Double => visitor.visit_f64(self.object(Class::Double(env)?).get_double()?),
+            Kind::Character => {
+                let unit = self.object(Class::Character(env)?).get_char()?;
+                match char::from_u32(unit as u32) {
+                    Some(c) => visitor.visit_char(c),
+                    // A lone surrogate
+                    None => visitor.visit_u16(unit),
+                }
+            }
+            Kind::BigInteger => {
+                let digits = self.call_string("java/lang/Object", "toString")?;
+                // Visit the narrowest type, as many visitors only support 64 bit integers
+                if let Ok(v) = digits.parse::<i64>() {
+                    visitor.visit_i64(v)
+                } else if let Ok(v) = digits.parse::<u64>() {
+                    visitor.visit_u64(v)
+                } else if let Ok(v) = digits.parse::<i128>() {
+                    visitor.visit_i128(v)
+                } else if let Ok(v) = digits.parse::<u128>() {
+                    visitor.visit_u128(v)
+                } else {
+                    Err(de::Error::custom(format!(
+                        "BigInteger {} does not fit in 128 bits",
+                        digits
+                    )))
+                }
+            }
+            Kind::String => visitor.visit_string(self.string()?),
+            Kind::Enum => visitor.visit_string(self.call_string("java/lang/Enum", "name")?),
+            Kind::Map => visitor.visit_map(MapAccess::new(env, self.object)?),
+            Kind::Collection => visitor.visit_seq(SeqAccess::collection(env, self.object)?),
+            Kind::Array => self.visit_array(visitor),
+            Kind::Optional => match self.optional()? {
+                Some(value) => {
+                    let result = visitor.visit_some(Self::raw(env, value));
+                    env.delete_local_ref(value)?;
+                    result
+                }
+                None => visitor.visit_none(),
+            },
+            Kind::Other => Err(self.invalid_type("a supported type")),
+        }
+    }
+
+    fn visit_array<'de, V: Visitor<'de>>(self, visitor: V) -> DeserializeResult<V::Value> {
+        let env = self.env;
+        let descriptor = self.component_descriptor()?;
+        let contents = match ArrayContents::primitive(env, self.object.into_inner(), &descriptor)? {
+            Some(contents) => contents,
+            None => return visitor.visit_seq(SeqAccess::array(env, self.object)?),
+        };
+        match contents {
+            ArrayContents::Boolean(v) => visitor.visit_seq(seq(v)),
+            ArrayContents::Byte(v) => visitor.visit_seq(seq(v)),
+            ArrayContents::Char(v) => visitor.visit_seq(seq(char::decode_utf16(v)
+                .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                .collect())),
+            ArrayContents::Short(v) => visitor.visit_seq(seq(v)),
+            ArrayContents::Int(v) => visitor.visit_seq(seq(v)),
+            ArrayContents::Long(v) => visitor.visit_seq(seq(v)),
+            ArrayContents::Float(v) => visitor.visit_seq(seq(v)),
+            ArrayContents::Double(v) => visitor.visit_seq(seq(v)),
+            _ => Err(self.invalid_type("a primitive array")),
+        }
+    }
+
+    /// The descriptor of the component type of an array
+    fn component_descriptor(&self) -> DeserializeResult<TypeDescriptor> {
+        let class = Class::new(
+            self.env,
+            self.env.get_object_class(self.object).catch(self.env)?,
+        );
+        let component = class.component_type()?;
+        self.env.delete_local_ref(class.class.into())?;
+
+        let component = component.ok_or_else(|| self.invalid_type("an array"))?;
+        let descriptor = component.descriptor()?;
+        self.env.delete_local_ref(component.class.into())?;
+        Ok(descriptor)
+    }
+
+    /// The value of an `Optional`, or None if it is empty
+    fn optional(&self) -> DeserializeResult<Option<JObject<'a>>> {
+        let value = Cache::call_method(
+            self.env,
+            self.object,
+            "java/util/Optional",
+            "orElse",
+            signature!("(Ljava/lang/Object;)Ljava/lang/Object;"),
+            &[JObject::null().into()],
+        )?
+        .l()?;
+        Ok(if value.is_null() { None } else { Some(value) })
+    }
+
+    /// Visit the Object if it is of an expected kind, fail with [DeserializeError::InvalidType] otherwise
+    fn expect<'de, V: Visitor<'de>>(
+        self,
+        expected: &str,
+        is_expected: fn(Kind) -> bool,
+        visitor: V,
+    ) -> DeserializeResult<V::Value> {
+        let kind = self.kind()?;
+        if is_expected(kind) {
+            self.visit(kind, visitor)
+        } else {
+            Err(self.invalid_type(expected))
+        }
+    }
+}
+
+/// A sequence of primitives copied out of an array
+fn seq<T>(values: Vec<T>) -> SeqDeserializer<std::vec::IntoIter<T>, DeserializeError>
+where
+    T: for<'de> IntoDeserializer<'de, DeserializeError>,
+{
+    SeqDeserializer::new(values.into_iter())
+}
+
+/// Deserialize `object` in a local frame of its own, deleting the reference to it afterwards
+fn nested<'a, T, F>(env: &'a JNIEnv<'a>, object: JObject<'a>, f: F) -> DeserializeResult<T>
+where
+    F: FnOnce(Deserializer<'a>) -> DeserializeResult<T>,
+{
+    let result = {
+        let _frame = FrameGuard::push(env, FRAME_CAPACITY)?;
+        f(Deserializer::raw(env, object))
+    };
+    if !object.is_null() {
+        env.delete_local_ref(object)?;
+    }
+    result
+}
+
+/// The path segment of a map entry: `.key` for String keys, `[key]` for others
+fn key_segment<'a>(env: &'a JNIEnv<'a>, key: JObject<'a>) -> DeserializeResult<String> {
+    let key = Deserializer::raw(env, key);
+    Ok(match key.kind()? {
+        Kind::Null => "[null]".to_string(),
+        Kind::String => format!(".{}", key.string()?),
+        _ => format!("[{}]", key.call_string("java/lang/Object", "toString")?),
+    })
+}
+
+macro_rules! deserialize_expected {
+    ($($method:ident => $expected:expr, $is_expected:expr;)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> DeserializeResult<V::Value> {
+                self.expect($expected, $is_expected, visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, 'a> de::Deserializer<'de> for Deserializer<'a> {
+    type Error = DeserializeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> DeserializeResult<V::Value> {
+        let kind = self.kind()?;
+        self.visit(kind, visitor)
+    }
+
+    deserialize_expected! {
+        deserialize_bool => "bool", |kind| kind == Kind::Boolean;
+        deserialize_i8 => "i8", Kind::is_integer;
+        deserialize_i16 => "i16", Kind::is_integer;
+        deserialize_i32 => "i32", Kind::is_integer;
+        deserialize_i64 => "i64", Kind::is_integer;
+        deserialize_i128 => "i128", Kind::is_integer;
+        deserialize_u8 => "u8", Kind::is_integer;
+        deserialize_u16 => "u16", Kind::is_integer;
+        deserialize_u32 => "u32", Kind::is_integer;
+        deserialize_u64 => "u64", Kind::is_integer;
+        deserialize_u128 => "u128", Kind::is_integer;
+        deserialize_f32 => "f32", Kind::is_number;
+        deserialize_f64 => "f64", Kind::is_number;
+        deserialize_char => "char", |kind| matches!(kind, Kind::Character | Kind::String);
+        deserialize_str => "a string", |kind| matches!(kind, Kind::String | Kind::Character | Kind::Enum);
+        deserialize_string => "a string", |kind| matches!(kind, Kind::String | Kind::Character | Kind::Enum);
+        deserialize_identifier => "a string", |kind| matches!(kind, Kind::String | Kind::Character | Kind::Enum);
+        deserialize_seq => "a sequence", Kind::is_sequence;
+        deserialize_map => "a map", |kind| kind == Kind::Map;
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> DeserializeResult<V::Value> {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> DeserializeResult<V::Value> {
+        let kind = self.kind()?;
+        if kind == Kind::Array && self.component_descriptor()? == TypeDescriptor::Byte {
+            let bytes = self
+                .env
+                .convert_byte_array(self.object.into_inner())
+                .catch(self.env)?;
+            return visitor.visit_byte_buf(bytes);
+        }
+
+        match kind.is_sequence() {
+            true => self.visit(kind, visitor),
+            false => Err(self.invalid_type("bytes")),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> DeserializeResult<V::Value> {
+        match self.kind()? {
+            Kind::Null => visitor.visit_none(),
+            Kind::Optional => self.visit(Kind::Optional, visitor),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> DeserializeResult<V::Value> {
+        match self.kind()? {
+            Kind::Null => visitor.visit_unit(),
+            _ => Err(self.invalid_type("null")),
+        }
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> DeserializeResult<V::Value> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> DeserializeResult<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _: usize,
+        visitor: V,
+    ) -> DeserializeResult<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: usize,
+        visitor: V,
+    ) -> DeserializeResult<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> DeserializeResult<V::Value> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> DeserializeResult<V::Value> {
+        let name = match self.kind()? {
+            Kind::String => self.string()?,
+            Kind::Enum => self.call_string("java/lang/Enum", "name")?,
+            Kind::Map => return visitor.visit_enum(VariantAccess::new(self.env, self.object)?),
+            _ => return Err(self.invalid_type("an enum")),
+        };
+        let name: StringDeserializer<DeserializeError> = name.into_deserializer();
+        visitor.visit_enum(name)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> DeserializeResult<V::Value> {
+        visitor.visit_unit()
+    }
+}
+
+/// Visits the entries of a `java.util.Map`
+struct MapAccess<'a> {
+    env: &'a JNIEnv<'a>,
+    iterator: JObject<'a>,
+    /// The value of the entry whose key was visited last, and its path segment
+    value: Option<(JObject<'a>, String)>,
+}
+
+impl<'a> MapAccess<'a> {
+    fn new(env: &'a JNIEnv<'a>, map: JObject<'a>) -> DeserializeResult<Self> {
+        let entries = Cache::call_method(
+            env,
+            map,
+            "java/util/Map",
+            "entrySet",
+            signature!("()Ljava/util/Set;"),
+            &[],
+        )?
+        .l()?;
+        let iterator = iterator(env, entries)?;
+        env.delete_local_ref(entries)?;
+        Ok(Self {
+            env,
+            iterator,
+            value: None,
+        })
+    }
+}
+
+impl<'a> Drop for MapAccess<'a> {
+    fn drop(&mut self) {
+        let _ = self.env.delete_local_ref(self.iterator);
+        if let Some((value, _)) = self.value.take() {
+            let _ = self.env.delete_local_ref(value);
+        }
+    }
+}
+
+impl<'de, 'a> de::MapAccess<'de> for MapAccess<'a> {
+    type Error = DeserializeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> DeserializeResult<Option<K::Value>> {
+        let env = self.env;
+        let entry = match next(env, self.iterator)? {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+        let (key, value) = entry_parts(env, entry)?;
+        env.delete_local_ref(entry)?;
+
+        let segment = key_segment(env, key)?;
+        let key = nested(env, key, |key| seed.deserialize(key)).map_err(|e| e.at(&segment))?;
+        self.value = Some((value, segment));
+        Ok(Some(key))
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> DeserializeResult<V::Value> {
+        let (value, segment) = self.value.take().ok_or_else(|| {
+            <DeserializeError as de::Error>::custom("value requested before its key")
+        })?;
+        nested(self.env, value, |value| seed.deserialize(value)).map_err(|e| e.at(&segment))
+    }
+}
+
+/// Where the elements of a sequence come from
+enum Elements<'a> {
+    /// A `java.util.Iterator` over a Collection
+    Iterator(JObject<'a>),
+    /// An array of Objects, and its length
+    Array(JObject<'a>, i32),
+}
+
+/// Visits the elements of a `java.util.Collection` or an array of Objects
+struct SeqAccess<'a> {
+    env: &'a JNIEnv<'a>,
+    elements: Elements<'a>,
+    index: i32,
+}
+
+impl<'a> SeqAccess<'a> {
+    fn collection(env: &'a JNIEnv<'a>, collection: JObject<'a>) -> DeserializeResult<Self> {
+        Ok(Self {
+            env,
+            elements: Elements::Iterator(iterator(env, collection)?),
+            index: 0,
+        })
+    }
+
+    fn array(env: &'a JNIEnv<'a>, array: JObject<'a>) -> DeserializeResult<Self> {
+        let len = env.get_array_length(array.into_inner()).catch(env)?;
+        Ok(Self {
+            env,
+            elements: Elements::Array(array, len),
+            index: 0,
+        })
+    }
+}
+
+impl<'a> Drop for SeqAccess<'a> {
+    fn drop(&mut self) {
+        // The array is owned by the Deserializer
+        if let Elements::Iterator(iterator) = self.elements {
+            let _ = self.env.delete_local_ref(iterator);
+        }
+    }
+}
+
+impl<'de, 'a> de::SeqAccess<'de> for SeqAccess<'a> {
+    type Error = DeserializeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> DeserializeResult<Option<T::Value>> {
+        let env = self.env;
+        let element = match self.elements {
+            Elements::Iterator(iterator) => match next(env, iterator)? {
+                Some(element) => element,
+                None => return Ok(None),
+            },
+            Elements::Array(array, len) if self.index < len => env
+                .get_object_array_element(array.into_inner(), self.index)
+                .catch(env)?,
+            Elements::Array(..) => return Ok(None),
+        };
+
+        let segment = format!("[{}]", self.index);
+        self.index += 1;
+        nested(env, element, |element| seed.deserialize(element))
+            .map(Some)
+            .map_err(|e| e.at(&segment))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        match self.elements {
+            Elements::Array(_, len) => Some((len - self.index) as usize),
+            Elements::Iterator(_) => None,
+        }
+    }
+}
+
+/// Visits an enum variant stored as a `java.util.Map` with a single entry, of the variant name to its content
+struct VariantAccess<'a> {
+    env: &'a JNIEnv<'a>,
+    name: String,
+    value: JObject<'a>,
+}
+
+impl<'a> VariantAccess<'a> {
+    fn new(env: &'a JNIEnv<'a>, map: JObject<'a>) -> DeserializeResult<Self> {
+        let deserializer = Deserializer::raw(env, map);
+        let size =
+            Cache::call_method(env, map, "java/util/Map", "size", signature!("()I"), &[])?.i()?;
+        if size != 1 {
+            return Err(deserializer.invalid_type("a map with a single entry"));
+        }
+
+        let entries = MapAccess::new(env, map)?;
+        let entry = next(env, entries.iterator)?
+            .ok_or_else(|| deserializer.invalid_type("a map with a single entry"))?;
+        let (key, value) = entry_parts(env, entry)?;
+        env.delete_local_ref(entry)?;
+        drop(entries);
+
+        let key = Deserializer::raw(env, key);
+        let name = match key.kind()? {
+            Kind::String => key.string(),
+            _ => Err(key.invalid_type("a variant name")),
+        };
+        env.delete_local_ref(key.object)?;
+        Ok(Self {
+            env,
+            name: name?,
+            value,
+        })
+    }
+}
+
+impl<'a> Drop for VariantAccess<'a> {
+    fn drop(&mut self) {
+        if !self.value.is_null() {
+            let _ = self.env.delete_local_ref(self.value);
+        }
+    }
+}
+
+impl<'de, 'a> de::EnumAccess<'de> for VariantAccess<'a> {
+    type Error = DeserializeError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> DeserializeResult<(V::Value, Self)> {
+        let name: StringDeserializer<DeserializeError> = self.name.clone().into_deserializer();
+        Ok((seed.deserialize(name)?, self))
+    }
+}
+
+impl<'de, 'a> de::VariantAccess<'de> for VariantAccess<'a> {
+    type Error = DeserializeError;
+
+    fn unit_variant(self) -> DeserializeResult<()> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> DeserializeResult<T::Value> {
+        let segment = format!(".{}", self.name);
+        seed.deserialize(Deserializer::raw(self.env, self.value))
+            .map_err(|e| e.at(&segment))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _: usize, visitor: V) -> DeserializeResult<V::Value> {
+        let segment = format!(".{}", self.name);
+        de::Deserializer::deserialize_seq(Deserializer::raw(self.env, self.value), visitor)
+            .map_err(|e| e.at(&segment))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> DeserializeResult<V::Value> {
+        let segment = format!(".{}", self.name);
+        de::Deserializer::deserialize_map(Deserializer::raw(self.env, self.value), visitor)
+            .map_err(|e| e.at(&segment))
+    }
+}
+
+/// Get an iterator over a `java.lang.Iterable`
+fn iterator<'a>(env: &'a JNIEnv<'a>, iterable: JObject<'a>) -> DeserializeResult<JObject<'a>> {
+    Ok(Cache::call_method(
+        env,
+        iterable,
+        "java/lang/Iterable",
+        "iterator",
+        signature!("()Ljava/util/Iterator;"),
+        &[],
+    )?
+    .l()?)
+}
+
+/// Get the next element of an iterator, or None if it is exhausted
+fn next<'a>(env: &'a JNIEnv<'a>, iterator: JObject<'a>) -> DeserializeResult<Option<JObject<'a>>> {
+    let has_next = Cache::call_method(
+        env,
+        iterator,
+        "java/util/Iterator",
+        "hasNext",
+        signature!("()Z"),
+        &[],
+    )?
+    .z()?;
+    if !has_next {
+        return Ok(None);
+    }
+
+    let element = Cache::call_method(
+        env,
+        iterator,
+        "java/util/Iterator",
+        "next",
+        signature!("()Ljava/lang/Object;"),
+        &[],
+    )?
+    .l()?;
+    Ok(Some(element))
+}
+
+/// Get the key and value of a `java.util.Map$Entry`
+fn entry_parts<'a>(
+    env: &'a JNIEnv<'a>,
+    entry: JObject<'a>,
+) -> DeserializeResult<(JObject<'a>, JObject<'a>)> {
+    let key = Cache::call_method(
+        env,
+        entry,
+        "java/util/Map$Entry",
+        "getKey",
+        signature!("()Ljava/lang/Object;"),
+        &[],
+    )?
+    .l()?;
+    let value = Cache::call_method(
+        env,
+        entry,
+        "java/util/Map$Entry",
+        "getValue",
+        signature!("()Ljava/lang/Object;"),
+        &[],
+    )?
+    .l()?;
+    Ok((key, value))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ser::to_java;
+    use crate::test::JVM;
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Config {
+        name: String,
+        threads: u16,
+        tags: Vec<String>,
+        parent: Option<String>,
+        ratio: f64,
+        limits: BTreeMap<String, u64>,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Shape {
+        Empty,
+        Circle(i32),
+        Rect { width: i32, height: i32 },
+        Line(i32, i32),
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+    enum Unit {
+        Seconds,
+        Minutes,
+    }
+
+    /// Call a static method returning an Object
+    fn call_static<'a>(
+        env: &'a JNIEnv<'a>,
+        class: &str,
+        name: &str,
+        sig: &str,
+        args: &[jni::objects::JValue],
+    ) -> Object<'a> {
+        let object = env
+            .call_static_method(class, name, sig, args)
+            .unwrap()
+            .l()
+            .unwrap();
+        Object::new(env, object, Class::Object(env).unwrap())
+    }
+
+    #[test]
+    fn primitives() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let object = Object::new_integer_object(&env, 5).unwrap();
+        assert_eq!(5i32, from_java(&object).unwrap());
+        assert_eq!(5u8, from_java(&object).unwrap());
+        assert_eq!(5.0f64, from_java(&object).unwrap());
+        assert!(from_java::<i8>(&Object::new_integer_object(&env, 300).unwrap()).is_err());
+
+        let object = Object::new_boolean_object(&env, true).unwrap();
+        assert!(from_java::<bool>(&object).unwrap());
+
+        let object = Object::new_character_object(&env, 'é' as u16).unwrap();
+        assert_eq!('é', from_java::<char>(&object).unwrap());
+
+        let object = Object::new_string(&env, "😀").unwrap();
+        assert_eq!("😀", from_java::<String>(&object).unwrap());
+        assert_eq!('😀', from_java::<char>(&object).unwrap());
+
+        let object = to_java(&env, &u128::MAX).unwrap();
+        assert_eq!(u128::MAX, from_java::<u128>(&object).unwrap());
+        let object = to_java(&env, &i64::MIN).unwrap();
+        assert_eq!(i64::MIN, from_java::<i64>(&object).unwrap());
+
+        let null = Object::new(&env, JObject::null(), Class::Object(&env).unwrap());
+        assert_eq!(None, from_java::<Option<i32>>(&null).unwrap());
+        from_java::<()>(&null).unwrap();
+    }
+
+    #[test]
+    fn round_trip() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let config = Config {
+            name: "ejni".to_string(),
+            threads: 4,
+            tags: vec!["a".to_string(), "b".to_string()],
+            parent: None,
+            ratio: 0.5,
+            limits: vec![("memory".to_string(), u64::MAX)].into_iter().collect(),
+        };
+        let object = to_java(&env, &config).unwrap();
+        assert_eq!(config, from_java(&object).unwrap());
+
+        let shapes = vec![
+            Shape::Empty,
+            Shape::Circle(2),
+            Shape::Rect {
+                width: 3,
+                height: 4,
+            },
+            Shape::Line(5, 6),
+        ];
+        let object = to_java(&env, &shapes).unwrap();
+        assert_eq!(shapes, from_java::<Vec<Shape>>(&object).unwrap());
+    }
+
+    #[test]
+    fn invalid_type() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let object = to_java(&env, &vec![vec![1, 2], vec![3, 4]]).unwrap();
+        let err = from_java::<Vec<(i32, String)>>(&object).unwrap_err();
+        match &err {
+            DeserializeError::InvalidType {
+                path,
+                expected,
+                found,
+            } => {
+                assert_eq!("[0][1]", path);
+                assert_eq!("a string", expected);
+                assert_eq!("java.lang.Integer", found);
+            }
+            e => panic!("Unexpected error {}", e),
+        }
+        assert_eq!(
+            "Invalid type at $[0][1]: expected a string, found java.lang.Integer",
+            err.to_string()
+        );
+
+        let mut map = BTreeMap::new();
+        map.insert("tags", vec![1]);
+        let object = to_java(&env, &map).unwrap();
+        let err = from_java::<Config>(&object).unwrap_err();
+        assert_eq!(
+            "Invalid type at $.tags[0]: expected a string, found java.lang.Integer",
+            err.to_string()
+        );
+
+        let object = to_java(&env, &BTreeMap::<String, i32>::new()).unwrap();
+        let err = from_java::<Config>(&object).unwrap_err();
+        assert_eq!("missing field `name` at $", err.to_string());
+    }
+
+    #[test]
+    fn optional() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let value = Object::new_string(&env, "Hello").unwrap();
+        let object = call_static(
+            &env,
+            "java/util/Optional",
+            "of",
+            "(Ljava/lang/Object;)Ljava/util/Optional;",
+            &[value.inner.into()],
+        );
+        assert_eq!(
+            Some("Hello".to_string()),
+            from_java::<Option<String>>(&object).unwrap()
+        );
+
+        let object = call_static(
+            &env,
+            "java/util/Optional",
+            "empty",
+            "()Ljava/util/Optional;",
+            &[],
+        );
+        assert_eq!(None, from_java::<Option<String>>(&object).unwrap());
+    }
+
+    #[test]
+    fn java_enum() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let object = Object::new(
+            &env,
+            env.get_static_field(
+                "java/util/concurrent/TimeUnit",
+                "MINUTES",
+                "Ljava/util/concurrent/TimeUnit;",
+            )
+            .unwrap()
+            .l()
+            .unwrap(),
+            Class::Object(&env).unwrap(),
+        );
+        assert_eq!(Unit::Minutes, from_java(&object).unwrap());
+        assert_eq!("MINUTES", from_java::<String>(&object).unwrap());
+
+        let object = Object::new_string(&env, "SECONDS").unwrap();
+        assert_eq!(Unit::Seconds, from_java(&object).unwrap());
+    }
+
+    #[test]
+    fn arrays() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let ints = env.new_int_array(3).unwrap();
+        env.set_int_array_region(ints, 0, &[1, 2, 3]).unwrap();
+        let object = Object::new(&env, ints.into(), Class::Object(&env).unwrap());
+        assert_eq!(vec![1, 2, 3], from_java::<Vec<i64>>(&object).unwrap());
+        assert_eq!((1, 2, 3), from_java::<(u8, u8, u8)>(&object).unwrap());
+
+        let chars = env.new_char_array(2).unwrap();
+        env.set_char_array_region(chars, 0, &['h' as u16, 'i' as u16])
+            .unwrap();
+        let object = Object::new(&env, chars.into(), Class::Object(&env).unwrap());
+        assert_eq!(vec!['h', 'i'], from_java::<Vec<char>>(&object).unwrap());
+
+        let bytes = env.byte_array_from_slice(&[1, 2, 255]).unwrap();
+        let object = Object::new(&env, bytes.into(), Class::Object(&env).unwrap());
+        let buf: serde_bytes_like::ByteBuf = from_java(&object).unwrap();
+        assert_eq!(vec![1, 2, 255], buf.0);
+
+        let strings = env
+            .new_object_array(2, "java/lang/String", JObject::null())
+            .unwrap();
+        let hello = Object::new_string(&env, "Hello").unwrap();
+        env.set_object_array_element(strings, 0, hello.inner)
+            .unwrap();
+        let object = Object::new(&env, strings.into(), Class::Object(&env).unwrap());
+        assert_eq!(
+            vec![Some("Hello".to_string()), None],
+            from_java::<Vec<Option<String>>>(&object).unwrap()
+        );
+    }
+
+    mod serde_bytes_like {
+        use serde::de::{Deserialize, Deserializer, Visitor};
+        use std::fmt;
+
+        /// Deserializes from bytes, like `serde_bytes::ByteBuf`
+        pub struct ByteBuf(pub Vec<u8>);
+
+        impl<'de> Deserialize<'de> for ByteBuf {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                struct BytesVisitor;
+
+                impl<'de> Visitor<'de> for BytesVisitor {
+                    type Value = ByteBuf;
+
+                    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                        f.write_str("bytes")
+                    }
+
+                    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<ByteBuf, E> {
+                        Ok(ByteBuf(v))
+                    }
+                }
+
+                deserializer.deserialize_byte_buf(BytesVisitor)
+            }
+        }
+    }
+
+    #[test]
+    fn ejni_error() {
+        let err = DeserializeError::from(Error::NullPointer {
+            expected: "java.lang.String".to_string(),
+        })
+        .at("[1]")
+        .at(".tags");
+        assert!(matches!(
+            &err,
+            DeserializeError::Ejni { path, error } if path == ".tags[1]" && matches!(**error, Error::NullPointer { .. })
+        ));
+        assert_eq!("java/lang/NullPointerException", err.exception_class());
+        assert_eq!(
+            "Expected java.lang.String, but found null at $.tags[1]",
+            err.to_string()
+        );
+    }
+}
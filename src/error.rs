@@ -0,0 +1,582 @@
+use crate::class::Class;
+use crate::signature;
+use crate::signature::Signature;
+use jni::objects::{GlobalRef, JObject, JString, JThrowable};
+use jni::JNIEnv;
+use std::fmt::{Debug, Display, Formatter};
+use thiserror::Error;
+
+/// The maximum number of causes followed when capturing a Java exception
+const MAX_CAUSE_DEPTH: usize = 32;
+
+/// Describes the errors that can occur when calling into Java through eJNI.
+/// The error does not borrow from the JVM, Classes are described by their name, e.g `java.lang.String`.
+/// It can be sent across threads and boxed into a `Box<dyn std::error::Error + Send + Sync>`
+#[derive(Debug, Error)]
+pub enum Error {
+    /// JNI Error
+    #[error("JNI Error: {0}")]
+    Jni(#[from] jni::errors::Error),
+    /// A Java exception was thrown. The exception is no longer pending in the JVM
+    #[error("Java exception: {0}")]
+    JavaException(#[from] JavaException),
+    /// The Class of an Object does not match the expected Class, e.g when casting or converting an Object
+    #[error("Expected {expected}, but found {found}")]
+    ClassMismatch {
+        /// The name of the expected Class
+        expected: String,
+        /// The name of the Class that was found
+        found: String,
+    },
+    /// The Object is null, but a non-null Object is required
+    #[error("Expected {expected}, but found null")]
+    NullPointer {
+        /// The name of the expected Class
+        expected: String,
+    },
+    /// A field, or the value returned by a getter, is null, but the Rust type it is read into is not an `Option`
+    #[error("{member} of {class} is null")]
+    NullMember {
+        /// The name of the Class the member belongs to
+        class: String,
+        /// The name of the field or getter
+        member: String,
+    },
+    /// The Class is not an array type
+    #[error("Expected {class} to be an array, it is not")]
+    NotArray {
+        /// The name of the Class
+        class: String,
+    },
+    /// The Class is an array of primitives, which can not be retrieved as Objects
+    #[error("Expected {class} to be an array of Objects, it is an array of primitives")]
+    PrimitiveArray {
+        /// The name of the array Class, e.g `[I`
+        class: String,
+    },
+    /// A Rust value does not fit into the Java type it is converted into, e.g a `usize` larger than `int`
+    #[error("Can not convert {from} into {to}, the value is out of range")]
+    Conversion {
+        /// The Rust type, e.g `usize`
+        from: &'static str,
+        /// The Java type, e.g `int`
+        to: &'static str,
+    },
+    /// The field does not exist
+    #[error("No {} field `{name}` in {class}", if *.is_static { "static" } else { "instance" })]
+    NoSuchField {
+        /// The name of the Class the field was looked up in
+        class: String,
+        /// The name of the field
+        name: String,
+        /// Whether a static field was looked up
+        is_static: bool,
+    },
+    /// No method with a matching signature exists
+    #[error("No {} method `{name}{}` in {class}", if *.is_static { "static" } else { "instance" }, .signature.as_deref().unwrap_or(""))]
+    NoSuchMethod {
+        /// The name of the Class the method was looked up in
+        class: String,
+        /// The name of the method
+        name: String,
+        /// The signature that was looked up. None if no overload is compatible with the arguments
+        signature: Option<String>,
+        /// Whether a static method was looked up
+        is_static: bool,
+    },
+    /// More than one overload is compatible with the arguments, and none of them is the most specific
+    #[error("Call to `{name}` in {class} is ambiguous between {candidates:?}")]
+    AmbiguousMethod {
+        /// The name of the Class the method was looked up in
+        class: String,
+        /// The name of the method
+        name: String,
+        /// The signatures of the compatible overloads
+        candidates: Vec<String>,
+    },
+    /// A closure was invoked with a different number of arguments than its functional interface declares
+    #[error("Expected {expected} arguments, but found {found}")]
+    ArgumentCount {
+        /// The number of arguments declared by the functional interface
+        expected: usize,
+        /// The number of arguments passed by Java
+        found: usize,
+    },
+    /// A native method is registered, but the Class does not declare a method with its name and signature
+    #[error("No method `{name}{signature}` in {class}")]
+    NotDeclared {
+        /// The name of the Class the method was looked up in
+        class: String,
+        /// The name of the method
+        name: String,
+        /// The signature the method was registered with
+        signature: Signature,
+    },
+    /// A native method is registered, but the method declared by the Class is not `native`
+    #[error("Method `{name}{signature}` in {class} is not native")]
+    NotNative {
+        /// The name of the Class declaring the method
+        class: String,
+        /// The name of the method
+        name: String,
+        /// The signature of the method
+        signature: Signature,
+    },
+}
+
+impl Error {
+    /// Create an [Error::ClassMismatch]. If looking up the names of the Classes fails, that error is returned instead
+    pub fn class_mismatch(expected: &Class<'_>, found: &Class<'_>) -> Self {
+        match (expected.get_name(), found.get_name()) {
+            (Ok(expected), Ok(found)) => Self::ClassMismatch { expected, found },
+            (Err(e), _) | (_, Err(e)) => e,
+        }
+    }
+
+    /// Create an [Error::NullPointer]. If looking up the name of the Class fails, that error is returned instead
+    pub fn null_pointer(expected: &Class<'_>) -> Self {
+        match expected.get_name() {
+            Ok(expected) => Self::NullPointer { expected },
+            Err(e) => e,
+        }
+    }
+
+    /// Create an [Error::NotArray]. If looking up the name of the Class fails, that error is returned instead
+    pub fn not_array(class: &Class<'_>) -> Self {
+        match class.get_name() {
+            Ok(class) => Self::NotArray { class },
+            Err(e) => e,
+        }
+    }
+
+    /// Create an [Error::PrimitiveArray]. If looking up the name of the Class fails, that error is returned instead
+    pub fn primitive_array(class: &Class<'_>) -> Self {
+        match class.get_name() {
+            Ok(class) => Self::PrimitiveArray { class },
+            Err(e) => e,
+        }
+    }
+}
+
+/// Result returned from eJNI functions
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// A Java exception captured from the JVM, including its cause chain.
+/// Two exceptions are equal if their class, message, stack trace and cause are, regardless of the Throwable they were captured from
+#[derive(Clone)]
+pub struct JavaException {
+    /// The fully qualified name of the Throwable's class, e.g `java.lang.IllegalStateException`
+    pub class: String,
+    /// The result of `Throwable#getMessage()`
+    pub message: Option<String>,
+    /// The result of `Throwable#getStackTrace()`
+    pub stack_trace: Vec<StackTraceElement>,
+    /// The result of `Throwable#getCause()`
+    pub cause: Option<Box<JavaException>>,
+    /// The Throwable itself, so it can be thrown again as is, see [ThrowableError::throwable](crate::ThrowableError::throwable).
+    /// Only retained for the outermost exception, the causes are reachable from it
+    pub throwable: Option<GlobalRef>,
+}
+
+impl Debug for JavaException {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JavaException")
+            .field("class", &self.class)
+            .field("message", &self.message)
+            .field("stack_trace", &self.stack_trace)
+            .field("cause", &self.cause)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for JavaException {
+    fn eq(&self, other: &Self) -> bool {
+        self.class == other.class
+            && self.message == other.message
+            && self.stack_trace == other.stack_trace
+            && self.cause == other.cause
+    }
+}
+
+impl Eq for JavaException {}
+
+/// Describes a `java.lang.StackTraceElement`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StackTraceElement {
+    /// The fully qualified name of the class containing the execution point
+    pub class: String,
+    /// The name of the method containing the execution point
+    pub method: String,
+    /// The name of the source file, if available
+    pub file: Option<String>,
+    /// The line number of the source line, or a negative number if it is unavailable
+    pub line: i32,
+}
+
+impl std::error::Error for JavaException {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.cause
+            .as_ref()
+            .map(|cause| cause.as_ref() as &(dyn std::error::Error + 'static))
+    }
+}
+
+impl Display for JavaException {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.message {
+            Some(message) => write!(f, "{}: {}", self.class, message)?,
+            None => write!(f, "{}", self.class)?,
+        }
+
+        if f.alternate() {
+            for element in &self.stack_trace {
+                write!(f, "\n\tat {}", element)?;
+            }
+            if let Some(cause) = &self.cause {
+                write!(f, "\nCaused by: {:#}", cause)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for StackTraceElement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match (&self.file, self.line) {
+            (Some(file), line) if line >= 0 => {
+                write!(f, "{}.{}({}:{})", self.class, self.method, file, line)
+            }
+            (Some(file), _) => write!(f, "{}.{}({})", self.class, self.method, file),
+            (None, _) => write!(f, "{}.{}(Unknown Source)", self.class, self.method),
+        }
+    }
+}
+
+impl JavaException {
+    /// Capture and clear the pending Java exception. Returns None if no exception is pending
+    pub fn catch(env: &JNIEnv<'_>) -> Result<Option<Self>> {
+        if !env.exception_check()? {
+            return Ok(None);
+        }
+
+        let throwable = env.exception_occurred()?;
+        env.exception_clear()?;
+
+        let exception = Self::from_throwable(env, throwable);
+        env.delete_local_ref(throwable.into())?;
+        exception.map(Some)
+    }
+
+    /// Capture the details of a `java.lang.Throwable`.
+    /// Details which can not be retrieved because Java throws while retrieving them, e.g from an overridden `getMessage`, are left out
+    pub fn from_throwable(env: &JNIEnv<'_>, throwable: JThrowable<'_>) -> Result<Self> {
+        let mut chain = Vec::new();
+        let mut current: JObject = throwable.into();
+        let result = Self::capture_chain(env, &mut chain, &mut current);
+        // The last cause that was visited. The Throwable itself is owned by the caller
+        if !current.is_null() && current.into_inner() != throwable.into_inner() {
+            env.delete_local_ref(current)?;
+        }
+        result?;
+
+        let mut exception = chain
+            .into_iter()
+            .rev()
+            .fold(None, |cause, mut exception| {
+                exception.cause = cause.map(Box::new);
+                Some(exception)
+            })
+            .expect("Throwable is not null");
+        exception.throwable = Some(env.new_global_ref(throwable)?);
+        Ok(exception)
+    }
+
+    /// Capture `current` and its causes into `chain`, leaving `current` at the last cause that was visited
+    fn capture_chain<'a>(
+        env: &JNIEnv<'a>,
+        chain: &mut Vec<Self>,
+        current: &mut JObject<'a>,
+    ) -> jni::errors::Result<()> {
+        let throwable = current.into_inner();
+        while !current.is_null() && chain.len() < MAX_CAUSE_DEPTH {
+            chain.push(Self::capture_single(env, *current)?);
+
+            let cause = ignore_thrown(
+                env,
+                env.call_method(
+                    *current,
+                    "getCause",
+                    signature!("()Ljava/lang/Throwable;"),
+                    &[],
+                ),
+            )?
+            .map(|cause| cause.l())
+            .transpose()?
+            .unwrap_or_else(JObject::null);
+            // Throwables may report themselves as their cause
+            if !cause.is_null() && env.is_same_object(cause, *current)? {
+                env.delete_local_ref(cause)?;
+                break;
+            }
+            if current.into_inner() != throwable {
+                env.delete_local_ref(*current)?;
+            }
+            *current = cause;
+        }
+        Ok(())
+    }
+
+    /// Capture a Throwable without its cause
+    fn capture_single(env: &JNIEnv<'_>, throwable: JObject<'_>) -> jni::errors::Result<Self> {
+        let class = env.get_object_class(throwable)?;
+        let class_name = Self::string(
+            env,
+            env.call_method(class, "getName", signature!("()Ljava/lang/String;"), &[])?
+                .l()?,
+        )?
+        .unwrap_or_default();
+        env.delete_local_ref(class.into())?;
+
+        let message = match ignore_thrown(
+            env,
+            env.call_method(
+                throwable,
+                "getMessage",
+                signature!("()Ljava/lang/String;"),
+                &[],
+            ),
+        )? {
+            Some(message) => Self::string(env, message.l()?)?,
+            None => None,
+        };
+
+        let trace = ignore_thrown(
+            env,
+            env.call_method(
+                throwable,
+                "getStackTrace",
+                signature!("()[Ljava/lang/StackTraceElement;"),
+                &[],
+            ),
+        )?
+        .map(|trace| trace.l())
+        .transpose()?
+        .unwrap_or_else(JObject::null);
+        let mut stack_trace = Vec::new();
+        if !trace.is_null() {
+            let len = env.get_array_length(trace.into_inner())?;
+            stack_trace.reserve(len as usize);
+            for i in 0..len {
+                let element = env.get_object_array_element(trace.into_inner(), i)?;
+                stack_trace.push(StackTraceElement::capture(env, element)?);
+                env.delete_local_ref(element)?;
+            }
+            env.delete_local_ref(trace)?;
+        }
+
+        Ok(Self {
+            class: class_name,
+            message,
+            stack_trace,
+            cause: None,
+            throwable: None,
+        })
+    }
+
+    /// Convert a possibly null java.lang.String to a Rust String, deleting the local reference
+    fn string(env: &JNIEnv<'_>, obj: JObject<'_>) -> jni::errors::Result<Option<String>> {
+        if obj.is_null() {
+            return Ok(None);
+        }
+
+        let string: String = env.get_string(JString::from(obj))?.into();
+        env.delete_local_ref(obj)?;
+        Ok(Some(string))
+    }
+}
+
+impl StackTraceElement {
+    fn capture(env: &JNIEnv<'_>, element: JObject<'_>) -> jni::errors::Result<Self> {
+        let string_method = |name: &str| -> jni::errors::Result<Option<String>> {
+            let value = env
+                .call_method(element, name, signature!("()Ljava/lang/String;"), &[])?
+                .l()?;
+            JavaException::string(env, value)
+        };
+
+        Ok(Self {
+            class: string_method("getClassName")?.unwrap_or_default(),
+            method: string_method("getMethodName")?.unwrap_or_default(),
+            file: string_method("getFileName")?,
+            line: env
+                .call_method(element, "getLineNumber", signature!("()I"), &[])?
+                .i()?,
+        })
+    }
+}
+
+/// Clear an exception thrown by Java while a Throwable is captured, so the Throwable being captured is not replaced by it.
+/// Returns None if an exception was thrown
+fn ignore_thrown<T>(
+    env: &JNIEnv<'_>,
+    result: jni::errors::Result<T>,
+) -> jni::errors::Result<Option<T>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(jni::errors::Error::JavaException) => {
+            env.exception_clear()?;
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Extension on results returned by the `jni` crate, capturing a thrown Java exception into an [Error]
+pub(crate) trait JniResultExt<T> {
+    /// Convert into an eJNI Result. If a Java exception is pending it is cleared and captured
+    fn catch(self, env: &JNIEnv<'_>) -> Result<T>;
+}
+
+impl<T> JniResultExt<T> for jni::errors::Result<T> {
+    fn catch(self, env: &JNIEnv<'_>) -> Result<T> {
+        match self {
+            Ok(value) => Ok(value),
+            Err(jni::errors::Error::JavaException) => match JavaException::catch(env)? {
+                Some(exception) => Err(Error::JavaException(exception)),
+                None => Err(Error::Jni(jni::errors::Error::JavaException)),
+            },
+            Err(e) => Err(Error::Jni(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::JVM;
+
+    #[test]
+    fn catch_none() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        assert!(JavaException::catch(&env).unwrap().is_none());
+    }
+
+    #[test]
+    fn catch_with_cause() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let cause_message = env.new_string("Inner").unwrap();
+        let cause = env
+            .new_object(
+                "java/lang/IllegalArgumentException",
+                "(Ljava/lang/String;)V",
+                &[cause_message.into()],
+            )
+            .unwrap();
+        let message = env.new_string("Outer").unwrap();
+        let exception = env
+            .new_object(
+                "java/lang/IllegalStateException",
+                "(Ljava/lang/String;Ljava/lang/Throwable;)V",
+                &[message.into(), cause.into()],
+            )
+            .unwrap();
+        env.throw(JThrowable::from(exception)).unwrap();
+
+        let result: jni::errors::Result<()> = Err(jni::errors::Error::JavaException);
+        let err = result.catch(&env).unwrap_err();
+        assert!(!env.exception_check().unwrap());
+
+        let exception = match err {
+            Error::JavaException(e) => e,
+            e => panic!("Expected a Java exception, got {}", e),
+        };
+        assert_eq!("java.lang.IllegalStateException", exception.class);
+        assert_eq!(Some("Outer".to_string()), exception.message);
+
+        let cause = exception.cause.as_ref().unwrap();
+        assert_eq!("java.lang.IllegalArgumentException", cause.class);
+        assert_eq!(Some("Inner".to_string()), cause.message);
+        assert!(cause.cause.is_none());
+
+        assert_eq!(
+            "java.lang.IllegalStateException: Outer",
+            exception.to_string()
+        );
+        assert!(format!("{:#}", exception)
+            .contains("Caused by: java.lang.IllegalArgumentException: Inner"));
+    }
+
+    #[test]
+    fn catch_faulty() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        let class = crate::test::test_class(&env, "ejni/test/FaultyException");
+
+        // getMessage throws, and getCause returns the exception itself
+        let exception = env.new_object(class, "()V", &[]).unwrap();
+        env.throw(JThrowable::from(exception)).unwrap();
+
+        let exception = JavaException::catch(&env).unwrap().unwrap();
+        assert!(!env.exception_check().unwrap());
+        assert_eq!("ejni.test.FaultyException", exception.class);
+        assert!(exception.message.is_none());
+        assert!(exception.cause.is_none());
+    }
+
+    #[test]
+    fn owned() {
+        fn assert_owned<T: Send + Sync + 'static>() {}
+        assert_owned::<Error>();
+
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let err = Error::class_mismatch(
+            &Class::String(&env).unwrap(),
+            &Class::Integer(&env).unwrap(),
+        );
+        assert!(
+            matches!(err, Error::ClassMismatch { ref expected, ref found } if expected == "java.lang.String" && found == "java.lang.Integer")
+        );
+
+        let err: Box<dyn std::error::Error + Send + Sync> = err.into();
+        let err = std::thread::spawn(move || err.to_string()).join().unwrap();
+        assert_eq!(
+            "Expected java.lang.String, but found java.lang.Integer",
+            err
+        );
+    }
+
+    #[test]
+    fn catch_stack_trace() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let input = env.new_string("foo").unwrap();
+        let err = env
+            .call_static_method(
+                "java/lang/Integer",
+                "parseInt",
+                "(Ljava/lang/String;)I",
+                &[input.into()],
+            )
+            .catch(&env)
+            .unwrap_err();
+
+        let exception = match err {
+            Error::JavaException(e) => e,
+            e => panic!("Expected a Java exception, got {}", e),
+        };
+        assert_eq!("java.lang.NumberFormatException", exception.class);
+        assert!(exception
+            .stack_trace
+            .iter()
+            .any(|e| e.class == "java.lang.Integer" && e.method == "parseInt"));
+        assert!(format!("{:#}", exception).contains("\n\tat java.lang.Integer.parseInt("));
+    }
+}
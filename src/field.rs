@@ -0,0 +1,507 @@
+use crate::cache::Cache;
+use crate::class::Class;
+use crate::error::{Error, JniResultExt, Result};
+use crate::java_type::{from_value, into_value, Converted, FromJava, IntoJava};
+use crate::object::Object;
+use crate::reflect::Modifiers;
+use crate::signature;
+use crate::signature::TypeDescriptor;
+use crate::JavaString;
+use jni::objects::{GlobalRef, JClass, JFieldID, JObject, JStaticFieldID, JValue};
+use jni::signature::JavaType as SignatureType;
+use jni::JNIEnv;
+use std::sync::Arc;
+
+/// A field resolved through reflection, cached per JVM by [Cache::by_class]
+struct ResolvedField {
+    /// The Class declaring the field
+    declaring: GlobalRef,
+    /// The type of the field
+    type_class: GlobalRef,
+    /// The JNI descriptor of the type
+    descriptor: TypeDescriptor,
+    /// The field ID, a `jfieldID` for both instance and static fields
+    id: usize,
+}
+
+impl ResolvedField {
+    /// Get the field named `name` of `class`, resolving it the first time
+    fn get<'a>(
+        env: &'a JNIEnv<'a>,
+        class: &Class<'a>,
+        name: &str,
+        is_static: bool,
+    ) -> Result<Arc<Self>> {
+        let key = match is_static {
+            true => format!("static field {}", name),
+            false => format!("field {}", name),
+        };
+        Cache::by_class(env, class, &key, || {
+            Self::resolve(env, class, name, is_static)
+        })
+    }
+
+    /// Look up a field by its name in `class` and its superclasses, regardless of its visibility
+    fn resolve<'a>(
+        env: &'a JNIEnv<'a>,
+        class: &Class<'a>,
+        name: &str,
+        is_static: bool,
+    ) -> Result<Self> {
+        let name_string = JavaString::from_rust(env, name)?;
+        let field = Self::find(env, class, name_string.clone().into());
+        env.delete_local_ref(name_string.inner.inner)?;
+
+        let field = field?
+            .filter(|(field, modifiers)| {
+                let matches = Modifiers(*modifiers).is_static() == is_static;
+                if !matches {
+                    let _ = env.delete_local_ref(*field);
+                }
+                matches
+            })
+            .map(|(field, _)| field);
+        let field = match field {
+            Some(field) => field,
+            None => {
+                return Err(Error::NoSuchField {
+                    class: class.get_name()?,
+                    name: name.to_string(),
+                    is_static,
+                })
+            }
+        };
+
+        let resolved = Self::describe(env, field, name, is_static);
+        env.delete_local_ref(field)?;
+        resolved
+    }
+
+    /// Find the `java.lang.reflect.Field` named `name`. Returns the Field and its modifiers, or None if it does not exist
+    fn find<'a>(
+        env: &'a JNIEnv<'a>,
+        class: &Class<'a>,
+        name: JValue,
+    ) -> Result<Option<(JObject<'a>, i32)>> {
+        // Class#getField includes public fields declared by interfaces,
+        // Class#getDeclaredField includes non-public fields, but not those of superclasses
+        if let Some(field) = Self::reflect(env, class.class, "getField", name)? {
+            return Ok(Some(field));
+        }
+
+        let mut current = class.clone();
+        loop {
+            let field = Self::reflect(env, current.class, "getDeclaredField", name);
+            let superclass = match field {
+                Ok(None) => current.get_superclass(),
+                _ => Ok(None),
+            };
+            if current.class.into_inner() != class.class.into_inner() {
+                env.delete_local_ref(current.class.into())?;
+            }
+
+            match (field?, superclass?) {
+                (Some(field), _) => return Ok(Some(field)),
+                (None, Some(superclass)) => current = superclass,
+                (None, None) => return Ok(None),
+            }
+        }
+    }
+
+    /// Invoke `Class#getField` or `Class#getDeclaredField`. Returns the Field and its modifiers, or None if it does not exist
+    fn reflect<'a>(
+        env: &'a JNIEnv<'a>,
+        class: JClass<'a>,
+        method: &str,
+        name: JValue,
+    ) -> Result<Option<(JObject<'a>, i32)>> {
+        let field = Cache::call_method(
+            env,
+            class,
+            "java/lang/Class",
+            method,
+            signature!("(Ljava/lang/String;)Ljava/lang/reflect/Field;"),
+            &[name],
+        );
+        let field = match field {
+            Ok(field) => field.l()?,
+            Err(Error::JavaException(e)) if e.class == "java.lang.NoSuchFieldException" => {
+                return Ok(None)
+            }
+            Err(e) => return Err(e),
+        };
+
+        let modifiers = Cache::call_method(
+            env,
+            field,
+            "java/lang/reflect/Field",
+            "getModifiers",
+            signature!("()I"),
+            &[],
+        )
+        .and_then(|modifiers| Ok(modifiers.i()?));
+        match modifiers {
+            Ok(modifiers) => Ok(Some((field, modifiers))),
+            Err(e) => {
+                env.delete_local_ref(field)?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Retain the declaring Class and type of a `java.lang.reflect.Field`, and get its ID
+    fn describe<'a>(
+        env: &'a JNIEnv<'a>,
+        field: JObject<'a>,
+        name: &str,
+        is_static: bool,
+    ) -> Result<Self> {
+        let class_of = |method| -> Result<GlobalRef> {
+            let class = Cache::call_method(
+                env,
+                field,
+                "java/lang/reflect/Field",
+                method,
+                signature!("()Ljava/lang/Class;"),
+                &[],
+            )?
+            .l()?;
+            let global = env.new_global_ref(class).catch(env);
+            env.delete_local_ref(class)?;
+            global
+        };
+        let declaring = class_of("getDeclaringClass")?;
+        let type_class = class_of("getType")?;
+        let descriptor =
+            Class::new(env, JClass::from(type_class.as_obj().into_inner())).descriptor()?;
+
+        let declaring_class = JClass::from(declaring.as_obj().into_inner());
+        let id = match is_static {
+            true => env
+                .get_static_field_id(declaring_class, name, descriptor.to_string())
+                .catch(env)?
+                .into_inner(),
+            false => env
+                .get_field_id(declaring_class, name, descriptor.to_string())
+                .catch(env)?
+                .into_inner(),
+        };
+
+        Ok(Self {
+            declaring,
+            type_class,
+            descriptor,
+            id: id as usize,
+        })
+    }
+
+    fn declaring<'a>(&self, env: &'a JNIEnv<'a>) -> Class<'a> {
+        Class::new(env, JClass::from(self.declaring.as_obj().into_inner()))
+    }
+
+    fn field_id<'a>(&self) -> JFieldID<'a> {
+        JFieldID::from(self.id as jni::sys::jfieldID)
+    }
+
+    fn static_field_id<'a>(&self) -> JStaticFieldID<'a> {
+        JStaticFieldID::from(self.id as jni::sys::jfieldID)
+    }
+
+    fn signature_type(&self) -> SignatureType {
+        (&self.descriptor).into()
+    }
+
+    /// Convert `value` into a JValue matching the type of the field. The JValue is valid as long as `converted` is
+    fn value<'a>(&self, env: &'a JNIEnv<'a>, converted: &Converted<'a>) -> Result<JValue<'a>> {
+        let object = converted.object.clone();
+        if self.descriptor.is_primitive() {
+            return into_value(object, &self.descriptor);
+        }
+
+        let type_class = Class::new(env, JClass::from(self.type_class.as_obj().into_inner()));
+        if !object.inner.is_null() && !object.instance_of_class(&type_class)? {
+            return Err(Error::class_mismatch(&type_class, &object.class));
+        }
+        Ok(JValue::Object(object.inner))
+    }
+}
+
+/// Call `f` with the runtime Class of `object`, deleting its local reference afterwards
+fn with_object_class<'a, F, R>(object: &Object<'a>, f: F) -> Result<R>
+where
+    F: FnOnce(&Class<'a>) -> Result<R>,
+{
+    let env = object.env;
+    let class = Class::new(env, env.get_object_class(object.inner).catch(env)?);
+    let result = f(&class);
+    env.delete_local_ref(class.class.into())?;
+    result
+}
+
+/// Get the value of an instance field of `object`
+pub(crate) fn get_field<'a, T: FromJava<'a>>(object: &Object<'a>, name: &str) -> Result<Option<T>> {
+    let env = object.env;
+    let field = with_object_class(object, |class| ResolvedField::get(env, class, name, false))?;
+    let value = env
+        .get_field_unchecked(object.inner, field.field_id(), field.signature_type())
+        .catch(env)?;
+    from_value(env, value)
+}
+
+/// Set the value of an instance field of `object`
+pub(crate) fn set_field<'a, T: IntoJava<'a>>(
+    object: &Object<'a>,
+    name: &str,
+    value: T,
+) -> Result<()> {
+    let env = object.env;
+    let field = with_object_class(object, |class| ResolvedField::get(env, class, name, false))?;
+    let converted = Converted::new(env, value)?;
+    let value = field.value(env, &converted)?;
+    env.set_field_unchecked(object.inner, field.field_id(), value)
+        .catch(env)?;
+    Ok(())
+}
+
+/// Get the value of a static field of `class`
+pub(crate) fn get_static_field<'a, T: FromJava<'a>>(
+    env: &'a JNIEnv<'a>,
+    class: &Class<'a>,
+    name: &str,
+) -> Result<Option<T>> {
+    let field = ResolvedField::get(env, class, name, true)?;
+    let value = env
+        .get_static_field_unchecked(
+            field.declaring(env).class,
+            field.static_field_id(),
+            field.signature_type(),
+        )
+        .catch(env)?;
+    from_value(env, value)
+}
+
+/// Set the value of a static field of `class`
+pub(crate) fn set_static_field<'a, T: IntoJava<'a>>(
+    env: &'a JNIEnv<'a>,
+    class: &Class<'a>,
+    name: &str,
+    value: T,
+) -> Result<()> {
+    let field = ResolvedField::get(env, class, name, true)?;
+    let converted = Converted::new(env, value)?;
+    let value = field.value(env, &converted)?;
+    env.set_static_field(field.declaring(env).class, field.static_field_id(), value)
+        .catch(env)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::{test_class, JVM};
+
+    /// Create an instance of the `ejni.test.Fields` fixture
+    fn fields<'a>(env: &'a JNIEnv<'a>) -> Object<'a> {
+        test_class(env, "ejni/test/FieldsBase");
+        let class = test_class(env, "ejni/test/Fields");
+        let object = env.new_object(class.class, "()V", &[]).unwrap();
+        Object::new(env, object, class)
+    }
+
+    #[test]
+    fn get_primitive_field() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let fields = fields(&env);
+        assert_eq!(Some(10), fields.get_field::<i32>("count").unwrap());
+        assert_eq!(
+            Some("Foo".to_string()),
+            fields.get_field::<String>("name").unwrap()
+        );
+    }
+
+    #[test]
+    fn get_inherited_field() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        // `inherited` is a private field of the superclass FieldsBase
+        let fields = fields(&env);
+        assert_eq!(Some(5), fields.get_field::<i32>("inherited").unwrap());
+        fields.set_field("inherited", 6).unwrap();
+        assert_eq!(Some(6), fields.get_field::<i32>("inherited").unwrap());
+    }
+
+    #[test]
+    fn cached() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let fields = fields(&env);
+        let first = ResolvedField::get(&env, &fields.class, "count", false).unwrap();
+        let second = ResolvedField::get(&env, &fields.class, "count", false).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+
+        // Another Class with a field of the same name has an entry of its own
+        let entry =
+            ResolvedField::get(&env, &Class::Integer(&env).unwrap(), "value", false).unwrap();
+        let value = ResolvedField::get(&env, &fields.class, "value", false).unwrap();
+        assert!(!Arc::ptr_eq(&entry, &value));
+        assert_eq!(TypeDescriptor::Int, entry.descriptor);
+        assert_eq!(TypeDescriptor::object("java/lang/Object"), value.descriptor);
+    }
+
+    #[test]
+    fn object_field() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let entry = env
+            .new_object(
+                "java/util/AbstractMap$SimpleEntry",
+                "(Ljava/lang/Object;Ljava/lang/Object;)V",
+                &[
+                    Object::new_string(&env, "Foo").unwrap().into(),
+                    JObject::null().into(),
+                ],
+            )
+            .unwrap();
+        let class = Class::new(&env, env.get_object_class(entry).unwrap());
+        let entry = Object::new(&env, entry, class);
+
+        assert_eq!(
+            Some("Foo".to_string()),
+            entry.get_field::<String>("key").unwrap()
+        );
+        assert!(entry.get_field::<Object>("value").unwrap().is_none());
+
+        entry.set_field("value", 10).unwrap();
+        let value = entry.get_field::<Object>("value").unwrap().unwrap();
+        assert_eq!("java.lang.Integer", value.class.get_name().unwrap());
+
+        entry.set_field("value", None::<String>).unwrap();
+        assert!(entry.get_field::<Object>("value").unwrap().is_none());
+    }
+
+    #[test]
+    fn set_primitive_field() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let fields = fields(&env);
+        fields.set_field("count", 20).unwrap();
+        assert_eq!(Some(20), fields.get_field::<i32>("count").unwrap());
+    }
+
+    #[test]
+    fn no_such_field() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let integer = Object::new_integer_object(&env, 10).unwrap();
+        match integer.get_field::<i32>("foo") {
+            Err(Error::NoSuchField {
+                class,
+                name,
+                is_static,
+            }) => {
+                assert_eq!("java.lang.Integer", class);
+                assert_eq!("foo", name);
+                assert!(!is_static);
+            }
+            _ => panic!("Expected NoSuchField"),
+        }
+        assert!(!env.exception_check().unwrap());
+
+        // MAX_VALUE is static
+        assert!(matches!(
+            integer.get_field::<i32>("MAX_VALUE"),
+            Err(Error::NoSuchField { .. })
+        ));
+    }
+
+    #[test]
+    fn mismatched_type() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let integer = Object::new_integer_object(&env, 10).unwrap();
+        assert!(matches!(
+            integer.get_field::<i64>("value"),
+            Err(Error::ClassMismatch { .. })
+        ));
+        assert!(matches!(
+            integer.set_field("value", "Foo"),
+            Err(Error::ClassMismatch { .. })
+        ));
+        assert_eq!(10, integer.get_integer().unwrap());
+    }
+
+    #[test]
+    fn mismatched_object_type() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let fields = fields(&env);
+        match fields.class.set_static_field("task", "Foo") {
+            Err(Error::ClassMismatch { expected, found }) => {
+                assert_eq!("java.lang.Runnable", expected);
+                assert_eq!("java.lang.String", found);
+            }
+            _ => panic!("Expected ClassMismatch"),
+        }
+    }
+
+    #[test]
+    fn static_field() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let integer = Class::Integer(&env).unwrap();
+        assert_eq!(
+            Some(i32::MAX),
+            integer.get_static_field::<i32>("MAX_VALUE").unwrap()
+        );
+
+        let int = integer.get_static_field::<Object>("TYPE").unwrap().unwrap();
+        assert_eq!("java.lang.Class", int.class.get_name().unwrap());
+
+        let class = fields(&env).class;
+        let counter = class.get_static_field::<i64>("counter").unwrap().unwrap();
+        class.set_static_field("counter", counter + 1000).unwrap();
+        assert_eq!(
+            Some(counter + 1000),
+            class.get_static_field::<i64>("counter").unwrap()
+        );
+    }
+
+    #[test]
+    fn descriptor() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        assert_eq!(
+            "I",
+            Class::int(&env).unwrap().descriptor().unwrap().to_string()
+        );
+        assert_eq!(
+            "Ljava/lang/String;",
+            Class::String(&env)
+                .unwrap()
+                .descriptor()
+                .unwrap()
+                .to_string()
+        );
+        assert_eq!(
+            "[Ljava/lang/String;",
+            Class::String(&env)
+                .unwrap()
+                .array_type(&env)
+                .unwrap()
+                .descriptor()
+                .unwrap()
+                .to_string()
+        );
+    }
+}
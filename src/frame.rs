@@ -0,0 +1,174 @@
+use crate::class::Class;
+use crate::error::{Error, JniResultExt, Result};
+use crate::object::Object;
+use jni::objects::JObject;
+use jni::JNIEnv;
+
+/// A JNI local reference frame, pushed by [with_local_frame].
+/// Every local reference created while the frame is active is deleted when the frame is popped.
+/// The lifetime of the frame is shorter than that of the enclosing frame, so Objects created in it can not escape it
+pub struct LocalFrame<'a> {
+    env: &'a JNIEnv<'a>,
+}
+
+impl<'a> LocalFrame<'a> {
+    /// The JNI environment the frame was pushed on
+    pub fn env(&self) -> &'a JNIEnv<'a> {
+        self.env
+    }
+}
+
+/// Pops the frame with a null result if it was not popped explicitly, e.g when the closure returned an error or panicked
+pub(crate) struct FrameGuard<'a> {
+    env: &'a JNIEnv<'a>,
+    popped: bool,
+}
+
+impl<'a> FrameGuard<'a> {
+    pub(crate) fn push(env: &'a JNIEnv<'a>, capacity: i32) -> Result<Self> {
+        env.push_local_frame(capacity).catch(env)?;
+        Ok(Self { env, popped: false })
+    }
+
+    fn pop(mut self, result: JObject<'a>) -> Result<JObject<'a>> {
+        self.popped = true;
+        Ok(self.env.pop_local_frame(result)?)
+    }
+}
+
+impl<'a> Drop for FrameGuard<'a> {
+    fn drop(&mut self) {
+        if !self.popped {
+            let _ = self.env.pop_local_frame(JObject::null());
+        }
+    }
+}
+
+/// Run `f` in a new local reference frame with room for at least `capacity` local references.
+/// When `f` returns, the frame is popped and all local references created in it are deleted,
+/// except for the Object returned by `f`, which is moved into the enclosing frame. Its Class is the runtime Class of the Object.
+///
+/// Objects created through [LocalFrame::env] borrow the frame, so they can not outlive it:
+/// ```compile_fail
+/// # use ejni::{with_local_frame, Error, Object};
+/// # fn leak<'a>(env: &'a jni::JNIEnv<'a>) {
+/// let mut objects = Vec::new();
+/// with_local_frame(env, 16, |frame| {
+///     objects.push(Object::new_string(frame.env(), "Foo")?);
+///     Ok::<_, Error>(None)
+/// });
+/// # }
+/// ```
+/// Rust values which do not hold local references, e.g `String`s, [Error]s or [GlobalObject](crate::GlobalObject)s, can be moved out of the closure freely.
+/// The error type must be `'static`, so it can not carry Objects either.
+/// Objects created through the `JNIEnv` of the enclosing frame are not tied to the frame, they must not be created in `f`.
+///
+/// ```ignore
+/// let list = ejni::with_local_frame(&env, 64, |frame| {
+///     let list = List::<String>::arraylist(frame.env())?;
+///     for i in 0..1000 {
+///         list.add(i.to_string())?;
+///     }
+///
+///     let inner: jobject = list.into();
+///     Ok::<_, ejni::Error>(Some(Object::new(frame.env(), inner.into(), Class::List(frame.env())?)))
+/// })?;
+/// ```
+pub fn with_local_frame<'a, F, E>(
+    env: &'a JNIEnv<'a>,
+    capacity: i32,
+    f: F,
+) -> std::result::Result<Option<Object<'a>>, E>
+where
+    F: for<'f> FnOnce(&LocalFrame<'f>) -> std::result::Result<Option<Object<'f>>, E>,
+    E: From<Error> + 'static,
+{
+    let guard = FrameGuard::push(env, capacity)?;
+    let result = f(&LocalFrame { env })?
+        .map(|object| object.inner.into_inner())
+        .unwrap_or_else(std::ptr::null_mut);
+
+    // Only the raw reference leaves the frame, it is valid in the enclosing frame once popped
+    let escaped = guard.pop(JObject::from(result))?;
+    if escaped.is_null() {
+        return Ok(None);
+    }
+
+    let class = Class::new(env, env.get_object_class(escaped).catch(env)?);
+    Ok(Some(Object::new(env, escaped, class)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::JVM;
+    use crate::{JavaException, List};
+    use jni::sys::jobject;
+
+    #[test]
+    fn escape() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let object = with_local_frame(&env, 256, |frame| {
+            let list = List::<i32>::arraylist(frame.env())?;
+            for i in 0..100 {
+                list.add(i)?;
+            }
+
+            // Hand the reference out of the List, so it is not deleted when the List is dropped
+            let inner: jobject = list.into();
+            Ok::<_, Error>(Some(Object::new(
+                frame.env(),
+                inner.into(),
+                Class::List(frame.env())?,
+            )))
+        })
+        .unwrap()
+        .unwrap();
+
+        assert_eq!("java.util.ArrayList", object.class.get_name().unwrap());
+        let list = List::<i32>::new(&env, object);
+        assert_eq!(100, list.size().unwrap());
+        assert_eq!(Some(99), list.get(99).unwrap());
+    }
+
+    #[test]
+    fn no_result() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let mut name = None;
+        let object = with_local_frame(&env, 16, |frame| {
+            let object = Object::new_string(frame.env(), "Foo")?;
+            name = Some(object.class.get_name()?);
+            Ok::<_, Error>(None)
+        })
+        .unwrap();
+
+        assert!(object.is_none());
+        assert_eq!(Some("java.lang.String".to_string()), name);
+    }
+
+    #[test]
+    fn error() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let err = with_local_frame(&env, 16, |frame| {
+            let list = List::<i32>::arraylist(frame.env())?;
+            list.get(5)?;
+            Ok(None)
+        })
+        .map(|_| ())
+        .unwrap_err();
+
+        assert!(
+            matches!(err, Error::JavaException(JavaException { ref class, .. }) if class == "java.lang.IndexOutOfBoundsException")
+        );
+        // The frame is popped, so a new one can be pushed and popped again
+        assert!(with_local_frame(&env, 16, |_| Ok::<_, Error>(None))
+            .unwrap()
+            .is_none());
+    }
+}
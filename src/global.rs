@@ -0,0 +1,311 @@
+use crate::class::Class;
+use crate::error::{JniResultExt, Result};
+use crate::java_type::{FromJava, IntoJava, JavaType};
+use crate::object::Object;
+use crate::{List, Map};
+use jni::objects::{GlobalRef, JObject};
+use jni::sys::jweak;
+use jni::{JNIEnv, JavaVM};
+use std::marker::PhantomData;
+
+/// A Java Object held by a global reference. Unlike [Object] it is not bound to a `JNIEnv`,
+/// so it can be stored across native calls and shared between threads.
+///
+/// The reference is deleted when the last clone is dropped
+#[derive(Clone)]
+pub struct GlobalObject {
+    inner: GlobalRef,
+}
+
+impl GlobalObject {
+    /// Promote an Object to a global reference
+    pub fn new(object: &Object<'_>) -> Result<Self> {
+        let inner = object.env.new_global_ref(object.inner).catch(object.env)?;
+        Ok(Self { inner })
+    }
+
+    /// Get an Object for use with `env`. `env` may belong to any thread attached to the JVM.
+    /// The returned Object holds a new local reference, its Class is the runtime Class of the Object
+    pub fn as_local<'a>(&self, env: &'a JNIEnv<'a>) -> Result<Object<'a>> {
+        local(env, self.inner.as_obj())
+    }
+
+    /// The underlying global reference
+    pub fn as_global_ref(&self) -> &GlobalRef {
+        &self.inner
+    }
+}
+
+impl<'a> JavaType<'a> for GlobalObject {
+    fn class(env: &'a JNIEnv<'a>) -> Result<Class<'a>> {
+        Class::Object(env)
+    }
+}
+
+impl<'a> IntoJava<'a> for GlobalObject {
+    const OWNED: bool = true;
+
+    fn into_java(self, env: &'a JNIEnv<'a>) -> Result<Object<'a>> {
+        self.as_local(env)
+    }
+}
+
+impl<'a> JavaType<'a> for &GlobalObject {
+    fn class(env: &'a JNIEnv<'a>) -> Result<Class<'a>> {
+        Class::Object(env)
+    }
+}
+
+impl<'a> IntoJava<'a> for &GlobalObject {
+    const OWNED: bool = true;
+
+    fn into_java(self, env: &'a JNIEnv<'a>) -> Result<Object<'a>> {
+        self.as_local(env)
+    }
+}
+
+impl<'a> FromJava<'a> for GlobalObject {
+    fn from_java(object: Object<'a>) -> Result<Self> {
+        Self::new(&object)
+    }
+}
+
+/// A `java.util.List` held by a global reference. See [GlobalObject]
+pub struct GlobalList<T> {
+    inner: GlobalObject,
+    _type: PhantomData<fn() -> T>,
+}
+
+// Derived Clone would require T: Clone
+impl<T> Clone for GlobalList<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            _type: PhantomData,
+        }
+    }
+}
+
+impl<T> GlobalList<T> {
+    /// Promote a List to a global reference
+    pub fn new(list: &List<'_, T>) -> Result<Self> {
+        Ok(Self {
+            inner: GlobalObject::new(&list.inner)?,
+            _type: PhantomData,
+        })
+    }
+
+    /// Get a List for use with `env`. See [GlobalObject::as_local]
+    pub fn as_local<'a>(&self, env: &'a JNIEnv<'a>) -> Result<List<'a, T>> {
+        Ok(List::new(env, self.inner.as_local(env)?))
+    }
+
+    /// The List as a GlobalObject
+    pub fn as_object(&self) -> &GlobalObject {
+        &self.inner
+    }
+}
+
+/// A `java.util.Map` held by a global reference. See [GlobalObject]
+pub struct GlobalMap<K, V> {
+    inner: GlobalObject,
+    _types: PhantomData<fn() -> (K, V)>,
+}
+
+// Derived Clone would require K: Clone and V: Clone
+impl<K, V> Clone for GlobalMap<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            _types: PhantomData,
+        }
+    }
+}
+
+impl<K, V> GlobalMap<K, V> {
+    /// Promote a Map to a global reference
+    pub fn new(map: &Map<'_, K, V>) -> Result<Self> {
+        Ok(Self {
+            inner: GlobalObject::new(&map.inner)?,
+            _types: PhantomData,
+        })
+    }
+
+    /// Get a Map for use with `env`. See [GlobalObject::as_local]
+    pub fn as_local<'a>(&self, env: &'a JNIEnv<'a>) -> Result<Map<'a, K, V>> {
+        Ok(Map::new(env, self.inner.as_local(env)?))
+    }
+
+    /// The Map as a GlobalObject
+    pub fn as_object(&self) -> &GlobalObject {
+        &self.inner
+    }
+}
+
+/// A Java Object held by a weak global reference. It does not prevent the Object from being garbage collected.
+/// Like [GlobalObject] it can be stored across native calls and shared between threads
+pub struct WeakObject {
+    weak: jweak,
+    vm: JavaVM,
+}
+
+// A weak global reference is valid on every thread, and it is only deleted on Drop
+unsafe impl Send for WeakObject {}
+unsafe impl Sync for WeakObject {}
+
+impl WeakObject {
+    /// Create a weak global reference to an Object
+    pub fn new(object: &Object<'_>) -> Result<Self> {
+        let env = object.env;
+        let vm = env.get_java_vm().catch(env)?;
+
+        let raw = env.get_native_interface();
+        // Safety: `raw` is the valid JNIEnv of the current thread, and `object` is a valid reference
+        let weak = unsafe {
+            let new_weak_global_ref = (**raw)
+                .NewWeakGlobalRef
+                .ok_or(jni::errors::Error::JNIEnvMethodNotFound("NewWeakGlobalRef"))?;
+            new_weak_global_ref(raw, object.inner.into_inner())
+        };
+        if weak.is_null() {
+            // NewWeakGlobalRef returns null when the JVM is out of memory
+            env.exception_check().catch(env)?;
+            return Err(jni::errors::Error::NullPtr("NewWeakGlobalRef").into());
+        }
+
+        Ok(Self { weak, vm })
+    }
+
+    /// Get the Object for use with `env`, if it has not been garbage collected yet.
+    /// The returned Object holds a new local (strong) reference, so it will not be collected while it is in use
+    pub fn upgrade<'a>(&self, env: &'a JNIEnv<'a>) -> Result<Option<Object<'a>>> {
+        let obj = env.new_local_ref::<JObject>(self.weak.into()).catch(env)?;
+        if obj.is_null() {
+            return Ok(None);
+        }
+
+        let class = Class::new(env, env.get_object_class(obj).catch(env)?);
+        Ok(Some(Object::new(env, obj, class)))
+    }
+
+    /// Returns true if the Object has been garbage collected
+    pub fn is_collected(&self, env: &JNIEnv<'_>) -> Result<bool> {
+        env.is_same_object(self.weak, JObject::null()).catch(env)
+    }
+}
+
+impl Drop for WeakObject {
+    fn drop(&mut self) {
+        // Dropping may happen on a thread that is not attached to the JVM
+        if let Ok(env) = self.vm.attach_current_thread() {
+            let raw = env.get_native_interface();
+            // Safety: `self.weak` was created by NewWeakGlobalRef and is deleted exactly once.
+            // DeleteWeakGlobalRef may be called with a pending exception
+            unsafe {
+                if let Some(delete_weak_global_ref) = (**raw).DeleteWeakGlobalRef {
+                    delete_weak_global_ref(raw, self.weak);
+                }
+            }
+        }
+    }
+}
+
+/// Create a new local reference to `obj`, with its runtime Class
+fn local<'a>(env: &'a JNIEnv<'a>, obj: JObject<'_>) -> Result<Object<'a>> {
+    let local = env
+        .new_local_ref::<JObject>(obj.into_inner().into())
+        .catch(env)?;
+    let class = Class::new(env, env.get_object_class(local).catch(env)?);
+    Ok(Object::new(env, local, class))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::JVM;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn send_sync() {
+        assert_send_sync::<GlobalObject>();
+        assert_send_sync::<GlobalList<String>>();
+        assert_send_sync::<GlobalMap<String, i32>>();
+        assert_send_sync::<WeakObject>();
+    }
+
+    #[test]
+    fn global_object() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let global = {
+            let object = Object::new_integer_object(&env, 10).unwrap();
+            GlobalObject::new(&object).unwrap()
+        };
+
+        let object = global.as_local(&env).unwrap();
+        assert_eq!("java.lang.Integer", object.class.get_name().unwrap());
+        assert_eq!(10, object.get_integer().unwrap());
+    }
+
+    #[test]
+    fn global_map_other_thread() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let map = Map::<String, i32>::hashmap(&env).unwrap();
+        map.put("Foo".to_string(), 10).unwrap();
+        let global = GlobalMap::new(&map).unwrap();
+        drop(map);
+
+        let vm: &JavaVM = &jvm;
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                let env = vm.attach_current_thread().unwrap();
+                let map = global.as_local(&env).unwrap();
+                assert_eq!(Some(10), map.get("Foo".to_string()).unwrap());
+                map.put("Bar".to_string(), 20).unwrap();
+            });
+        });
+
+        let map = global.as_local(&env).unwrap();
+        assert_eq!(Some(20), map.get("Bar".to_string()).unwrap());
+    }
+
+    #[test]
+    fn global_list() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let list = List::<GlobalObject>::arraylist(&env).unwrap();
+        let element = GlobalObject::new(&Object::new_string(&env, "Foo").unwrap()).unwrap();
+        list.add(element.clone()).unwrap();
+        let global = GlobalList::new(&list).unwrap();
+        drop(list);
+
+        let list = global.as_local(&env).unwrap();
+        assert_eq!(1, list.size().unwrap());
+        let first = list.get(0).unwrap().unwrap();
+        assert!(env
+            .is_same_object(
+                first.as_global_ref().as_obj(),
+                element.as_global_ref().as_obj()
+            )
+            .unwrap());
+    }
+
+    #[test]
+    fn weak_object() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let object = Object::new_string(&env, "Foo").unwrap();
+        let weak = WeakObject::new(&object).unwrap();
+
+        assert!(!weak.is_collected(&env).unwrap());
+        let upgraded = weak.upgrade(&env).unwrap().unwrap();
+        assert_eq!("java.lang.String", upgraded.class.get_name().unwrap());
+        assert!(env.is_same_object(upgraded.inner, object.inner).unwrap());
+    }
+}
@@ -0,0 +1,356 @@
+use crate::abstractions::JavaString;
+use crate::class::Class;
+use crate::error::{Error, JniResultExt, Result};
+use crate::object::Object;
+use crate::signature::TypeDescriptor;
+use jni::objects::{JObject, JValue};
+use jni::JNIEnv;
+
+/// A Rust type which has a counterpart in Java
+pub trait JavaType<'a> {
+    /// The Java Class of this type. For Rust primitives this is the boxed Class, e.g `java.lang.Integer` for `i32`
+    fn class(env: &'a JNIEnv<'a>) -> Result<Class<'a>>;
+
+    /// The JNI type descriptor of this type when it is used in a method signature.
+    /// Defaults to the descriptor of [JavaType::class]. For Rust primitives this is the Java primitive, e.g `I` for `i32`
+    fn descriptor(env: &'a JNIEnv<'a>) -> Result<TypeDescriptor> {
+        Self::class(env)?.descriptor()
+    }
+}
+
+/// Conversion from a Rust value into a Java Object
+pub trait IntoJava<'a>: JavaType<'a> {
+    /// Whether [IntoJava::into_java] returns a local reference owned by the caller, either a new one, e.g of a boxed primitive,
+    /// or one the value owned itself, e.g the reference of a [List](crate::List). eJNI deletes such references once they have been passed to Java.
+    /// Defaults to false, for values like [Object] which do not own their reference
+    const OWNED: bool = false;
+
+    /// Convert the value into a Java Object
+    fn into_java(self, env: &'a JNIEnv<'a>) -> Result<Object<'a>>;
+}
+
+/// Conversion from a Java Object into a Rust value
+pub trait FromJava<'a>: JavaType<'a> + Sized {
+    /// Convert a Java Object into the Rust value. The Object is never null
+    fn from_java(object: Object<'a>) -> Result<Self>;
+}
+
+/// A value converted into an Object to be passed to Java.
+/// If the conversion handed out a local reference owned by the caller, see [IntoJava::OWNED], it is deleted when this is dropped
+pub(crate) struct Converted<'a> {
+    pub object: Object<'a>,
+    owned: bool,
+}
+
+impl<'a> Converted<'a> {
+    /// Convert `value` into an Object
+    pub fn new<T: IntoJava<'a>>(env: &'a JNIEnv<'a>, value: T) -> Result<Self> {
+        Ok(Self {
+            object: value.into_java(env)?,
+            owned: T::OWNED,
+        })
+    }
+
+    /// The Object as an argument of a method call
+    pub fn value(&self) -> JValue<'a> {
+        JValue::Object(self.object.inner)
+    }
+}
+
+impl<'a> Drop for Converted<'a> {
+    fn drop(&mut self) {
+        if self.owned && !self.object.inner.is_null() {
+            let _ = self.object.env.delete_local_ref(self.object.inner);
+        }
+    }
+}
+
+/// Convert a JObject returned from Java into `T`. Returns None if the JObject is null.
+/// The Object passed to `T::from_java` carries the runtime Class of the JObject
+pub(crate) fn from_nullable<'a, T: FromJava<'a>>(
+    env: &'a JNIEnv<'a>,
+    obj: JObject<'a>,
+) -> Result<Option<T>> {
+    if obj.is_null() {
+        return Ok(None);
+    }
+
+    let class = Class::new(env, env.get_object_class(obj).catch(env)?);
+    T::from_java(Object::new(env, obj, class)).map(Some)
+}
+
+/// Convert a JValue returned from Java into `T`. Primitives are boxed before they are passed to `T::from_java`.
+/// Returns None if the JValue is a null Object or void
+pub(crate) fn from_value<'a, T: FromJava<'a>>(
+    env: &'a JNIEnv<'a>,
+    value: JValue<'a>,
+) -> Result<Option<T>> {
+    let object = match value {
+        JValue::Object(obj) => return from_nullable(env, obj),
+        JValue::Byte(b) => Object::new_byte_object(env, b)?,
+        JValue::Char(c) => Object::new_character_object(env, c)?,
+        JValue::Short(s) => Object::new_short_object(env, s)?,
+        JValue::Int(i) => Object::new_integer_object(env, i)?,
+        JValue::Long(l) => Object::new_long_object(env, l)?,
+        JValue::Float(f) => Object::new_float_object(env, f)?,
+        JValue::Double(d) => Object::new_double_object(env, d)?,
+        JValue::Bool(b) => Object::new_boolean_object(env, b != 0)?,
+        JValue::Void => return Ok(None),
+    };
+
+    T::from_java(object).map(Some)
+}
+
+/// Convert an Object into a JValue of type `ty`. Primitives are unboxed, the Object must be of the matching boxed Class
+pub(crate) fn into_value<'a>(object: Object<'a>, ty: &TypeDescriptor) -> Result<JValue<'a>> {
+    let value = match ty {
+        TypeDescriptor::Boolean => JValue::Bool(object.get_boolean()? as u8),
+        TypeDescriptor::Byte => JValue::Byte(object.get_byte()?),
+        TypeDescriptor::Char => JValue::Char(object.get_char()?),
+        TypeDescriptor::Short => JValue::Short(object.get_short()?),
+        TypeDescriptor::Int => JValue::Int(object.get_integer()?),
+        TypeDescriptor::Long => JValue::Long(object.get_long()?),
+        TypeDescriptor::Float => JValue::Float(object.get_float()?),
+        TypeDescriptor::Double => JValue::Double(object.get_double()?),
+        TypeDescriptor::Void => JValue::Void,
+        TypeDescriptor::Object(_) | TypeDescriptor::Array(_) => JValue::Object(object.inner),
+    };
+
+    Ok(value)
+}
+
+macro_rules! impl_boxed {
+    ($rust:ty, $class:ident, $new:ident, $get:ident, $descriptor:ident) => {
+        impl<'a> JavaType<'a> for $rust {
+            fn class(env: &'a JNIEnv<'a>) -> Result<Class<'a>> {
+                Class::$class(env)
+            }
+
+            fn descriptor(_: &'a JNIEnv<'a>) -> Result<TypeDescriptor> {
+                Ok(TypeDescriptor::$descriptor)
+            }
+        }
+
+        impl<'a> IntoJava<'a> for $rust {
+            const OWNED: bool = true;
+
+            fn into_java(self, env: &'a JNIEnv<'a>) -> Result<Object<'a>> {
+                Object::$new(env, self)
+            }
+        }
+
+        impl<'a> FromJava<'a> for $rust {
+            fn from_java(object: Object<'a>) -> Result<Self> {
+                object.$get()
+            }
+        }
+    };
+}
+
+impl_boxed!(i8, Byte, new_byte_object, get_byte, Byte);
+impl_boxed!(i16, Short, new_short_object, get_short, Short);
+impl_boxed!(i32, Integer, new_integer_object, get_integer, Int);
+impl_boxed!(i64, Long, new_long_object, get_long, Long);
+impl_boxed!(f32, Float, new_float_object, get_float, Float);
+impl_boxed!(f64, Double, new_double_object, get_double, Double);
+impl_boxed!(bool, Boolean, new_boolean_object, get_boolean, Boolean);
+impl_boxed!(u16, Character, new_character_object, get_char, Char);
+
+impl<'a> JavaType<'a> for Object<'a> {
+    fn class(env: &'a JNIEnv<'a>) -> Result<Class<'a>> {
+        Class::Object(env)
+    }
+}
+
+impl<'a> IntoJava<'a> for Object<'a> {
+    fn into_java(self, _: &'a JNIEnv<'a>) -> Result<Object<'a>> {
+        Ok(self)
+    }
+}
+
+impl<'a> FromJava<'a> for Object<'a> {
+    fn from_java(object: Object<'a>) -> Result<Self> {
+        Ok(object)
+    }
+}
+
+impl<'a> JavaType<'a> for &Object<'a> {
+    fn class(env: &'a JNIEnv<'a>) -> Result<Class<'a>> {
+        Class::Object(env)
+    }
+}
+
+impl<'a> IntoJava<'a> for &Object<'a> {
+    fn into_java(self, _: &'a JNIEnv<'a>) -> Result<Object<'a>> {
+        Ok(self.clone())
+    }
+}
+
+impl<'a> JavaType<'a> for JavaString<'a> {
+    fn class(env: &'a JNIEnv<'a>) -> Result<Class<'a>> {
+        Class::String(env)
+    }
+}
+
+impl<'a> IntoJava<'a> for JavaString<'a> {
+    fn into_java(self, _: &'a JNIEnv<'a>) -> Result<Object<'a>> {
+        Ok(self.inner)
+    }
+}
+
+impl<'a> FromJava<'a> for JavaString<'a> {
+    fn from_java(object: Object<'a>) -> Result<Self> {
+        let string_class = Class::String(object.env)?;
+        if !object.instance_of_class(&string_class)? {
+            return Err(Error::class_mismatch(&string_class, &object.class));
+        }
+
+        Ok(JavaString::new(object.env, object))
+    }
+}
+
+impl<'a> JavaType<'a> for String {
+    fn class(env: &'a JNIEnv<'a>) -> Result<Class<'a>> {
+        Class::String(env)
+    }
+}
+
+impl<'a> IntoJava<'a> for String {
+    const OWNED: bool = true;
+
+    fn into_java(self, env: &'a JNIEnv<'a>) -> Result<Object<'a>> {
+        Object::new_string(env, self)
+    }
+}
+
+impl<'a> FromJava<'a> for String {
+    fn from_java(object: Object<'a>) -> Result<Self> {
+        JavaString::from_java(object)?.into_rust()
+    }
+}
+
+impl<'a> JavaType<'a> for &str {
+    fn class(env: &'a JNIEnv<'a>) -> Result<Class<'a>> {
+        Class::String(env)
+    }
+}
+
+impl<'a> IntoJava<'a> for &str {
+    const OWNED: bool = true;
+
+    fn into_java(self, env: &'a JNIEnv<'a>) -> Result<Object<'a>> {
+        Object::new_string(env, self)
+    }
+}
+
+impl<'a, T: JavaType<'a>> JavaType<'a> for Option<T> {
+    fn class(env: &'a JNIEnv<'a>) -> Result<Class<'a>> {
+        T::class(env)
+    }
+
+    // `null` can not be passed as a primitive
+    fn descriptor(env: &'a JNIEnv<'a>) -> Result<TypeDescriptor> {
+        T::class(env)?.descriptor()
+    }
+}
+
+impl<'a> JavaType<'a> for () {
+    fn class(env: &'a JNIEnv<'a>) -> Result<Class<'a>> {
+        Class::void(env)
+    }
+
+    fn descriptor(_: &'a JNIEnv<'a>) -> Result<TypeDescriptor> {
+        Ok(TypeDescriptor::Void)
+    }
+}
+
+impl<'a> FromJava<'a> for () {
+    fn from_java(_: Object<'a>) -> Result<Self> {
+        Ok(())
+    }
+}
+
+impl<'a, T: IntoJava<'a>> IntoJava<'a> for Option<T> {
+    const OWNED: bool = T::OWNED;
+
+    /// `None` is converted into `null`
+    fn into_java(self, env: &'a JNIEnv<'a>) -> Result<Object<'a>> {
+        match self {
+            Some(value) => value.into_java(env),
+            None => Ok(Object::new(env, JObject::null(), T::class(env)?)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::JVM;
+
+    #[test]
+    fn integer() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let object = 10i32.into_java(&env).unwrap();
+        assert_eq!("java.lang.Integer", object.class.get_name().unwrap());
+        assert_eq!(10, i32::from_java(object).unwrap());
+    }
+
+    #[test]
+    fn string() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let object = "Foo".into_java(&env).unwrap();
+        assert_eq!("Foo", String::from_java(object).unwrap());
+    }
+
+    #[test]
+    fn converted() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        // The Object does not own its reference, so converting it must not delete it
+        let object = Object::new_string(&env, "Foo").unwrap();
+        drop(Converted::new(&env, &object).unwrap());
+        drop(Converted::new(&env, object.clone()).unwrap());
+        assert_eq!("Foo", String::from_java(object).unwrap());
+
+        drop(Converted::new(&env, None::<String>).unwrap());
+        let converted = Converted::new(&env, 10i32).unwrap();
+        assert_eq!(Some(10), converted.object.get_integer().ok());
+    }
+
+    #[test]
+    fn from_nullable_null() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let value: Option<i32> = from_nullable(&env, JObject::null()).unwrap();
+        assert!(value.is_none());
+    }
+
+    #[test]
+    fn from_nullable_runtime_class() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let integer = Object::new_integer_object(&env, 10).unwrap();
+        let value: Option<Object> = from_nullable(&env, integer.inner).unwrap();
+        assert_eq!(
+            "java.lang.Integer",
+            value.unwrap().class.get_name().unwrap()
+        );
+    }
+
+    #[test]
+    fn wrong_class() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let value: Result<Option<String>> =
+            from_nullable(&env, Object::new_integer_object(&env, 10).unwrap().inner);
+        assert!(value.is_err());
+    }
+}
@@ -3,21 +3,86 @@
 #![deny(clippy::missing_safety_doc)]
 #![warn(missing_docs)]
 
+// Allows the code generated by ejni-macros to refer to `::ejni` inside this crate as well
+extern crate self as ejni;
+
+pub use ejni_macros::{export, FromJava, IntoJava, JavaType};
+pub use ejni_mangle as mangle;
+
+mod error;
+pub use error::*;
+
+mod array;
+pub use array::*;
+
+mod cache;
+pub use cache::*;
+
 mod class;
 pub use class::*;
 
+mod closure;
+pub use closure::*;
+
+#[cfg(feature = "serde")]
+mod de;
+#[cfg(feature = "serde")]
+pub use de::*;
+
 mod object;
 pub use object::*;
 
+mod field;
+
+mod frame;
+pub use frame::*;
+
+mod global;
+pub use global::*;
+
+mod java_type;
+pub use java_type::*;
+
+mod method;
+pub use method::*;
+
+mod monitor;
+pub use monitor::*;
+
+mod native;
+pub use native::*;
+
 mod primitives;
 pub use primitives::*;
 
+#[doc(hidden)]
+pub mod private;
+
+mod reflect;
+pub use reflect::*;
+
+mod registry;
+pub use registry::*;
+
+#[cfg(feature = "serde")]
+mod ser;
+#[cfg(feature = "serde")]
+pub use ser::*;
+
+mod signature;
+pub use signature::*;
+
+mod vm;
+pub use vm::*;
+
 mod abstractions;
 pub use abstractions::*;
 
 #[cfg(test)]
 mod test {
-    use jni::{InitArgsBuilder, JNIVersion, JavaVM};
+    use crate::Class;
+    use jni::objects::JObject;
+    use jni::{InitArgsBuilder, JNIEnv, JNIVersion, JavaVM};
     use lazy_static::lazy_static;
     use std::sync::Mutex;
     lazy_static! {
@@ -31,4 +96,26 @@ mod test {
             Mutex::new(JavaVM::new(jvm_args).unwrap())
         };
     }
+
+    /// Bindings generated by `ejni-bindgen` for `Order` and `Plugin`, kept in sync by its `checked_in` test
+    #[allow(dead_code)]
+    pub mod bindings {
+        include!("../test-classes/bindings.rs");
+    }
+
+    /// Get a Class from `test-classes`, defining it in the bootstrap class loader on first use.
+    /// The classes are compiled with `javac --release 8`, from the sources next to them
+    pub fn test_class<'a>(env: &'a JNIEnv<'a>, name: &str) -> Class<'a> {
+        if let Ok(class) = env.find_class(name) {
+            return Class::new(env, class);
+        }
+        env.exception_clear().unwrap();
+
+        let path = format!("{}/test-classes/{}.class", env!("CARGO_MANIFEST_DIR"), name);
+        let bytes = std::fs::read(path).unwrap();
+        Class::new(
+            env,
+            env.define_class(name, JObject::null(), &bytes).unwrap(),
+        )
+    }
 }
@@ -0,0 +1,649 @@
+use crate::cache::Cache;
+use crate::class::Class;
+use crate::error::{Error, JniResultExt, Result};
+use crate::java_type::{from_value, into_value, FromJava, IntoJava, JavaType};
+use crate::object::Object;
+use crate::reflect::Modifiers;
+use crate::signature;
+use crate::signature::{Signature, TypeDescriptor};
+use crate::JavaString;
+use jni::objects::{GlobalRef, JClass, JMethodID, JObject, JStaticMethodID, JValue};
+use jni::sys::jmethodID;
+use jni::JNIEnv;
+use std::sync::Arc;
+
+/// An argument passed to a Java method.
+/// If the conversion into an Object created a local reference, see [IntoJava::OWNED], it is deleted when the Argument is dropped
+pub struct Argument<'a> {
+    /// The value of the argument
+    pub object: Object<'a>,
+    /// The JNI type descriptor of the argument, e.g `I` or `Ljava/lang/String;`
+    pub descriptor: TypeDescriptor,
+    owned: bool,
+}
+
+impl<'a> Argument<'a> {
+    /// Create an Argument from a Rust value. The descriptor is given by [JavaType::descriptor]
+    pub fn new<T: IntoJava<'a>>(env: &'a JNIEnv<'a>, value: T) -> Result<Self> {
+        Ok(Self {
+            descriptor: T::descriptor(env)?,
+            object: value.into_java(env)?,
+            owned: T::OWNED,
+        })
+    }
+}
+
+impl<'a> Drop for Argument<'a> {
+    fn drop(&mut self) {
+        if self.owned && !self.object.inner.is_null() {
+            let _ = self.object.env.delete_local_ref(self.object.inner);
+        }
+    }
+}
+
+/// A list of arguments passed to a Java method. Implemented for tuples of up to 8 [IntoJava] values, and for `Vec<Argument>`
+pub trait IntoArgs<'a> {
+    /// Convert into a list of Arguments
+    fn into_args(self, env: &'a JNIEnv<'a>) -> Result<Vec<Argument<'a>>>;
+}
+
+impl<'a> IntoArgs<'a> for Vec<Argument<'a>> {
+    fn into_args(self, _: &'a JNIEnv<'a>) -> Result<Vec<Argument<'a>>> {
+        Ok(self)
+    }
+}
+
+macro_rules! impl_into_args {
+    ($($arg:ident),*) => {
+        impl<'a, $($arg: IntoJava<'a>),*> IntoArgs<'a> for ($($arg,)*) {
+            #[allow(non_snake_case, unused_variables)]
+            fn into_args(self, env: &'a JNIEnv<'a>) -> Result<Vec<Argument<'a>>> {
+                let ($($arg,)*) = self;
+                Ok(vec![$(Argument::new(env, $arg)?),*])
+            }
+        }
+    };
+}
+
+impl_into_args!();
+impl_into_args!(A);
+impl_into_args!(A, B);
+impl_into_args!(A, B, C);
+impl_into_args!(A, B, C, D);
+impl_into_args!(A, B, C, D, E);
+impl_into_args!(A, B, C, D, E, F);
+impl_into_args!(A, B, C, D, E, F, G);
+impl_into_args!(A, B, C, D, E, F, G, H);
+
+/// An overload of a method, found through `Class#getMethods()`
+struct Overload {
+    signature: Signature,
+    /// The Classes of the parameters
+    params: Vec<GlobalRef>,
+    bridge: bool,
+    /// The method ID, a `jmethodID` for both instance and static methods
+    id: usize,
+}
+
+/// The public overloads of a method, cached per JVM by [Cache::by_class] so they are reflected on once
+struct Overloads(Vec<Overload>);
+
+impl Overloads {
+    /// Get the public methods named `name` of `class`, reflecting on them the first time
+    fn get<'a>(
+        env: &'a JNIEnv<'a>,
+        class: &Class<'a>,
+        name: &str,
+        is_static: bool,
+    ) -> Result<Arc<Self>> {
+        let key = match is_static {
+            true => format!("static overloads {}", name),
+            false => format!("overloads {}", name),
+        };
+        Cache::by_class(env, class, &key, || {
+            Self::reflect(env, class, name, is_static)
+        })
+    }
+
+    fn reflect<'a>(
+        env: &'a JNIEnv<'a>,
+        class: &Class<'a>,
+        name: &str,
+        is_static: bool,
+    ) -> Result<Self> {
+        let methods = Cache::call_method(
+            env,
+            class.class,
+            "java/lang/Class",
+            "getMethods",
+            signature!("()[Ljava/lang/reflect/Method;"),
+            &[],
+        )?
+        .l()?;
+
+        let mut overloads: Vec<Overload> = Vec::new();
+        let len = match env.get_array_length(methods.into_inner()).catch(env) {
+            Ok(len) => len,
+            Err(e) => {
+                env.delete_local_ref(methods).catch(env)?;
+                return Err(e);
+            }
+        };
+        for i in 0..len {
+            // The local references of the reflected methods are dropped with the frame
+            env.push_local_frame(16).catch(env)?;
+            let overload = env
+                .get_object_array_element(methods.into_inner(), i)
+                .catch(env)
+                .and_then(|method| Overload::new(env, class, method, name, is_static));
+            env.pop_local_frame(JObject::null()).catch(env)?;
+
+            let overload = match overload {
+                Ok(Some(overload)) => overload,
+                Ok(None) => continue,
+                Err(e) => {
+                    env.delete_local_ref(methods).catch(env)?;
+                    return Err(e);
+                }
+            };
+            // Bridge methods share their parameters with the method they bridge to
+            match overloads
+                .iter_mut()
+                .find(|o| o.signature.params == overload.signature.params)
+            {
+                Some(existing) if existing.bridge && !overload.bridge => *existing = overload,
+                Some(_) => {}
+                None => overloads.push(overload),
+            }
+        }
+        env.delete_local_ref(methods).catch(env)?;
+        Ok(Self(overloads))
+    }
+
+    /// Pick the most specific overload which is compatible with `args`
+    fn select<'a>(
+        &self,
+        env: &'a JNIEnv<'a>,
+        class: &Class<'a>,
+        name: &str,
+        args: &[Argument<'a>],
+        is_static: bool,
+    ) -> Result<&Overload> {
+        let mut candidates = Vec::new();
+        for overload in &self.0 {
+            if let Some(score) = overload.score(env, args)? {
+                candidates.push((overload, score));
+            }
+        }
+
+        let best_score = candidates.iter().map(|(_, score)| *score).max();
+        candidates.retain(|(_, score)| Some(*score) == best_score);
+
+        let mut most_specific = Vec::new();
+        for (i, (candidate, _)) in candidates.iter().enumerate() {
+            let mut is_most_specific = true;
+            for (j, (other, _)) in candidates.iter().enumerate() {
+                if i != j && !candidate.more_specific_than(env, other)? {
+                    is_most_specific = false;
+                    break;
+                }
+            }
+            if is_most_specific {
+                most_specific.push(*candidate);
+            }
+        }
+
+        match (most_specific.as_slice(), candidates.len()) {
+            (_, 0) => Err(Error::NoSuchMethod {
+                class: class.get_name()?,
+                name: name.to_string(),
+                signature: None,
+                is_static,
+            }),
+            ([overload], _) => Ok(overload),
+            _ => Err(Error::AmbiguousMethod {
+                class: class.get_name()?,
+                name: name.to_string(),
+                candidates: candidates
+                    .iter()
+                    .map(|(c, _)| c.signature.to_string())
+                    .collect(),
+            }),
+        }
+    }
+}
+
+impl Overload {
+    /// Returns None if `method` is not named `name`
+    fn new<'a>(
+        env: &'a JNIEnv<'a>,
+        class: &Class<'a>,
+        method: JObject<'a>,
+        name: &str,
+        is_static: bool,
+    ) -> Result<Option<Self>> {
+        let call = |method_name: &str, sig: &str| {
+            Cache::call_method(
+                env,
+                method,
+                "java/lang/reflect/Method",
+                method_name,
+                sig,
+                &[],
+            )
+        };
+
+        let method_name = call("getName", signature!("()Ljava/lang/String;"))?.l()?;
+        let method_name =
+            JavaString::new(env, Object::new(env, method_name, Class::String(env)?)).into_rust()?;
+        if method_name != name {
+            return Ok(None);
+        }
+
+        let modifiers = call("getModifiers", signature!("()I"))?.i()?;
+        if Modifiers(modifiers).is_static() != is_static {
+            return Ok(None);
+        }
+
+        let param_array = call("getParameterTypes", signature!("()[Ljava/lang/Class;"))?.l()?;
+        let len = env.get_array_length(param_array.into_inner()).catch(env)?;
+        let mut params = Vec::with_capacity(len as usize);
+        let mut descriptors = Vec::with_capacity(len as usize);
+        for i in 0..len {
+            let param = env
+                .get_object_array_element(param_array.into_inner(), i)
+                .catch(env)?;
+            descriptors.push(Class::new(env, JClass::from(param)).descriptor()?);
+            params.push(env.new_global_ref(param).catch(env)?);
+        }
+
+        let ret = call("getReturnType", signature!("()Ljava/lang/Class;"))?.l()?;
+        let ret = Class::new(env, JClass::from(ret)).descriptor()?;
+        let signature = Signature::new(descriptors, ret);
+
+        let id = match is_static {
+            true => env
+                .get_static_method_id(class.class, name, signature.to_string())
+                .catch(env)?
+                .into_inner(),
+            false => env
+                .get_method_id(class.class, name, signature.to_string())
+                .catch(env)?
+                .into_inner(),
+        };
+
+        Ok(Some(Self {
+            signature,
+            params,
+            bridge: call("isBridge", signature!("()Z"))?.z()?,
+            id: id as usize,
+        }))
+    }
+
+    /// How closely the parameters match `args`, higher is closer. None if the overload is not compatible with them
+    fn score<'a>(&self, env: &'a JNIEnv<'a>, args: &[Argument<'a>]) -> Result<Option<usize>> {
+        if self.params.len() != args.len() {
+            return Ok(None);
+        }
+
+        let mut score = 0;
+        for ((param, descriptor), arg) in self.params.iter().zip(&self.signature.params).zip(args) {
+            score += if *descriptor == arg.descriptor {
+                2
+            } else if let Some(boxed) = descriptor.boxed().and_then(|b| b.class_name()) {
+                // A primitive parameter accepts only its boxed Class
+                let boxed = Cache::class(env, &boxed)?;
+                if arg.object.inner.is_null() || !arg.object.instance_of_class(&boxed)? {
+                    return Ok(None);
+                }
+                1
+            } else if arg.object.inner.is_null()
+                || arg.object.instance_of_class(&class(env, param))?
+            {
+                0
+            } else {
+                return Ok(None);
+            };
+        }
+        Ok(Some(score))
+    }
+
+    /// Every parameter of this overload can be passed to the parameter of `other`
+    fn more_specific_than<'a>(&self, env: &'a JNIEnv<'a>, other: &Self) -> Result<bool> {
+        for (this, that) in self.params.iter().zip(&other.params) {
+            if !env
+                .is_assignable_from(class(env, this).class, class(env, that).class)
+                .catch(env)?
+            {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// A Class held by a global reference
+fn class<'a>(env: &'a JNIEnv<'a>, global: &GlobalRef) -> Class<'a> {
+    Class::new(env, JClass::from(global.as_obj().into_inner()))
+}
+
+/// Get the ID of the method named `name` with `signature`, looking it up the first time.
+/// A NoSuchMethodError thrown by the lookup is mapped into [Error::NoSuchMethod]
+fn method_id<'a>(
+    env: &'a JNIEnv<'a>,
+    class: &Class<'a>,
+    name: &str,
+    signature: &Signature,
+    is_static: bool,
+) -> Result<usize> {
+    let key = match is_static {
+        true => format!("static method {}{}", name, signature),
+        false => format!("method {}{}", name, signature),
+    };
+    let id = Cache::by_class(env, class, &key, || {
+        let id = match is_static {
+            true => env
+                .get_static_method_id(class.class, name, signature.to_string())
+                .map(|id| id.into_inner()),
+            false => env
+                .get_method_id(class.class, name, signature.to_string())
+                .map(|id| id.into_inner()),
+        };
+        match id.catch(env) {
+            Ok(id) => Ok(id as usize),
+            Err(Error::JavaException(e)) if e.class == "java.lang.NoSuchMethodError" => {
+                Err(Error::NoSuchMethod {
+                    class: class.get_name()?,
+                    name: name.to_string(),
+                    signature: Some(signature.to_string()),
+                    is_static,
+                })
+            }
+            Err(e) => Err(e),
+        }
+    })?;
+    Ok(*id)
+}
+
+/// Find the method to call, and convert the arguments into JValues matching its parameters.
+/// The JValues are valid as long as `args` is
+fn target<'a, R: JavaType<'a>>(
+    env: &'a JNIEnv<'a>,
+    class: &Class<'a>,
+    name: &str,
+    args: &[Argument<'a>],
+    resolve: bool,
+    is_static: bool,
+) -> Result<(Signature, usize, Vec<JValue<'a>>)> {
+    let (signature, id) = match resolve {
+        true => {
+            let overloads = Overloads::get(env, class, name, is_static)?;
+            let overload = overloads.select(env, class, name, args, is_static)?;
+            (overload.signature.clone(), overload.id)
+        }
+        false => {
+            let params = args.iter().map(|arg| arg.descriptor.clone()).collect();
+            let signature = Signature::new(params, R::descriptor(env)?);
+            let id = method_id(env, class, name, &signature, is_static)?;
+            (signature, id)
+        }
+    };
+
+    let values = args
+        .iter()
+        .zip(&signature.params)
+        .map(|(arg, param)| into_value(arg.object.clone(), param))
+        .collect::<Result<Vec<_>>>()?;
+    Ok((signature, id, values))
+}
+
+/// Call an instance method on `object`
+pub(crate) fn call<'a, R, A>(
+    object: &Object<'a>,
+    name: &str,
+    args: A,
+    resolve: bool,
+) -> Result<Option<R>>
+where
+    R: FromJava<'a>,
+    A: IntoArgs<'a>,
+{
+    let env = object.env;
+    let args = args.into_args(env)?;
+    let class = Class::new(env, env.get_object_class(object.inner).catch(env)?);
+    let target = target::<R>(env, &class, name, &args, resolve, false);
+    env.delete_local_ref(class.class.into())?;
+
+    let (signature, id, values) = target?;
+    let value = env
+        .call_method_unchecked(
+            object.inner,
+            JMethodID::from(id as jmethodID),
+            (&signature.ret).into(),
+            &values,
+        )
+        .catch(env)?;
+    from_value(env, value)
+}
+
+/// Call a static method on `class`
+pub(crate) fn call_static<'a, R, A>(
+    env: &'a JNIEnv<'a>,
+    class: &Class<'a>,
+    name: &str,
+    args: A,
+    resolve: bool,
+) -> Result<Option<R>>
+where
+    R: FromJava<'a>,
+    A: IntoArgs<'a>,
+{
+    let args = args.into_args(env)?;
+    let (signature, id, values) = target::<R>(env, class, name, &args, resolve, true)?;
+    let value = env
+        .call_static_method_unchecked(
+            class.class,
+            JStaticMethodID::from(id as jmethodID),
+            (&signature.ret).into(),
+            &values,
+        )
+        .catch(env)?;
+    from_value(env, value)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::JVM;
+    use crate::List;
+
+    #[test]
+    fn call() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let string = Object::new_string(&env, "Hello").unwrap();
+        assert_eq!(Some(5), string.call::<i32, _>("length", ()).unwrap());
+        assert_eq!(
+            Some('e' as u16),
+            string.call::<u16, _>("charAt", (1,)).unwrap()
+        );
+        assert_eq!(
+            Some("el".to_string()),
+            string.call::<String, _>("substring", (1, 3)).unwrap()
+        );
+        assert_eq!(
+            Some("Hello World".to_string()),
+            string.call::<String, _>("concat", (" World",)).unwrap()
+        );
+    }
+
+    #[test]
+    fn call_void() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let list = List::<i32>::arraylist(&env).unwrap();
+        list.add(1).unwrap();
+        assert!(list.inner.call::<(), _>("clear", ()).unwrap().is_none());
+        assert_eq!(0, list.size().unwrap());
+    }
+
+    #[test]
+    fn call_static() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let integer = Class::Integer(&env).unwrap();
+        assert_eq!(
+            Some(42),
+            integer.call_static::<i32, _>("parseInt", ("42",)).unwrap()
+        );
+
+        let math = Class::Math(&env).unwrap();
+        assert_eq!(
+            Some(10i64),
+            math.call_static::<i64, _>("max", (5i64, 10i64)).unwrap()
+        );
+    }
+
+    #[test]
+    fn cached() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let string = Class::String(&env).unwrap();
+        let first = Overloads::get(&env, &string, "substring", false).unwrap();
+        let second = Overloads::get(&env, &string, "substring", false).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(2, first.0.len());
+
+        let signature = Signature::parse("()I").unwrap();
+        let id = method_id(&env, &string, "length", &signature, false).unwrap();
+        assert_eq!(
+            id,
+            method_id(&env, &string, "length", &signature, false).unwrap()
+        );
+    }
+
+    #[test]
+    fn no_such_method() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let string = Object::new_string(&env, "Hello").unwrap();
+        match string.call::<i32, _>("length", (1,)) {
+            Err(Error::NoSuchMethod {
+                class,
+                name,
+                signature,
+                is_static,
+            }) => {
+                assert_eq!("java.lang.String", class);
+                assert_eq!("length", name);
+                assert_eq!(Some("(I)I".to_string()), signature);
+                assert!(!is_static);
+            }
+            _ => panic!("Expected NoSuchMethod"),
+        }
+        assert!(!env.exception_check().unwrap());
+    }
+
+    #[test]
+    fn exception() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let integer = Class::Integer(&env).unwrap();
+        match integer.call_static::<i32, _>("parseInt", ("Foo",)) {
+            Err(Error::JavaException(e)) => {
+                assert_eq!("java.lang.NumberFormatException", e.class)
+            }
+            _ => panic!("Expected a NumberFormatException"),
+        }
+    }
+
+    #[test]
+    fn resolved_boxed() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        // toHexString only takes an `int`, the boxed Integer is unboxed
+        let integer = Class::Integer(&env).unwrap();
+        let boxed = Object::new_integer_object(&env, 255).unwrap();
+        assert!(matches!(
+            integer.call_static::<String, _>("toHexString", (boxed.clone(),)),
+            Err(Error::NoSuchMethod { .. })
+        ));
+        assert_eq!(
+            Some("ff".to_string()),
+            integer
+                .call_static_resolved::<String, _>("toHexString", (boxed,))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn resolved_overload() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let list = List::<i32>::arraylist(&env).unwrap();
+        list.add(5).unwrap();
+        list.add(0).unwrap();
+
+        // remove(int) removes by index
+        let removed = list
+            .inner
+            .call_resolved::<Object, _>("remove", (1,))
+            .unwrap()
+            .unwrap();
+        assert_eq!(0, removed.get_integer().unwrap());
+
+        // remove(Object) removes by value
+        let value = Object::new_integer_object(&env, 5).unwrap();
+        assert_eq!(
+            Some(true),
+            list.inner
+                .call_resolved::<bool, _>("remove", (value,))
+                .unwrap()
+        );
+        assert_eq!(0, list.size().unwrap());
+    }
+
+    #[test]
+    fn resolved_return_type() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        // The declared return type of `Object#toString` differs from `Object`, which is requested
+        let integer = Object::new_integer_object(&env, 10).unwrap();
+        let string = integer
+            .call_resolved::<Object, _>("toString", ())
+            .unwrap()
+            .unwrap();
+        assert_eq!("java.lang.String", string.class.get_name().unwrap());
+
+        let math = Class::Math(&env).unwrap();
+        assert_eq!(
+            Some(10.0),
+            math.call_static_resolved::<f64, _>("max", (5.0, 10.0))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn resolved_incompatible() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        // There is no `max(short, short)`, and a Short is not unboxed into an int
+        let math = Class::Math(&env).unwrap();
+        match math.call_static_resolved::<i32, _>("max", (1i16, 2i16)) {
+            Err(Error::NoSuchMethod {
+                signature: None, ..
+            }) => {}
+            _ => panic!("Expected NoSuchMethod"),
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use crate::cache::Cache;
+use crate::error::{JniResultExt, Result};
+use crate::object::Object;
+use crate::signature;
+use jni::objects::JValue;
+use std::convert::TryFrom;
+use std::time::Duration;
+
+/// The monitor of an Object, entered by [Object::lock]. Holding the guard is equivalent to being inside a `synchronized (object)` block,
+/// so native code and Java code synchronizing on the same Object exclude each other.
+///
+/// The monitor is exited when the guard is dropped. Monitors are reentrant, and owned by the thread which entered them
+pub struct MonitorGuard<'b, 'a> {
+    object: &'b Object<'a>,
+}
+
+impl<'b, 'a> MonitorGuard<'b, 'a> {
+    /// Enter the monitor of `object`, blocking until it is available
+    pub(crate) fn enter(object: &'b Object<'a>) -> Result<Self> {
+        let env = object.env;
+        if object.inner.is_null() {
+            return Err(jni::errors::Error::NullPtr("MonitorEnter").into());
+        }
+
+        let raw = env.get_native_interface();
+        // Safety: `raw` is the valid JNIEnv of the current thread, and `object` is a valid, non-null reference
+        let status = unsafe {
+            let monitor_enter = (**raw)
+                .MonitorEnter
+                .ok_or(jni::errors::Error::JNIEnvMethodNotFound("MonitorEnter"))?;
+            monitor_enter(raw, object.inner.into_inner())
+        };
+        if status != jni::sys::JNI_OK {
+            let err = match env.exception_check()? {
+                true => jni::errors::Error::JavaException,
+                false => jni::errors::Error::JniCall(jni::errors::JniError::Other(status)),
+            };
+            return Err(err).catch(env);
+        }
+
+        Ok(Self { object })
+    }
+
+    /// The Object whose monitor is held
+    pub fn object(&self) -> &'b Object<'a> {
+        self.object
+    }
+
+    /// Release the monitor and wait until another thread calls `notify` or `notifyAll` on the Object, then enter it again.
+    /// Like in Java, the thread may also wake up spuriously, so wait in a loop checking the condition. Invokes `Object#wait()`
+    pub fn wait(&self) -> Result<()> {
+        self.call("wait", signature!("()V"), &[])
+    }
+
+    /// Like [MonitorGuard::wait], but returns once `timeout` has elapsed without being notified.
+    /// Timeouts shorter than a millisecond are rounded up to a millisecond, as `Object#wait(0)` waits forever. Invokes `Object#wait(long)`
+    pub fn wait_timeout(&self, timeout: Duration) -> Result<()> {
+        let millis = i64::try_from(timeout.as_millis())
+            .unwrap_or(i64::MAX)
+            .max(1);
+        self.call("wait", signature!("(J)V"), &[JValue::Long(millis)])
+    }
+
+    /// Wake up a single thread waiting on the Object. Invokes `Object#notify()`
+    pub fn notify(&self) -> Result<()> {
+        self.call("notify", signature!("()V"), &[])
+    }
+
+    /// Wake up all threads waiting on the Object. Invokes `Object#notifyAll()`
+    pub fn notify_all(&self) -> Result<()> {
+        self.call("notifyAll", signature!("()V"), &[])
+    }
+
+    fn call(&self, name: &str, sig: &str, args: &[JValue]) -> Result<()> {
+        Cache::call_method(
+            self.object.env,
+            self.object.inner,
+            "java/lang/Object",
+            name,
+            sig,
+            args,
+        )?;
+        Ok(())
+    }
+}
+
+impl<'b, 'a> Drop for MonitorGuard<'b, 'a> {
+    fn drop(&mut self) {
+        let raw = self.object.env.get_native_interface();
+        // Safety: the monitor was entered by this thread in `MonitorGuard::enter`, and is exited exactly once.
+        // MonitorExit may be called with a pending exception
+        unsafe {
+            if let Some(monitor_exit) = (**raw).MonitorExit {
+                monitor_exit(raw, self.object.inner.into_inner());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::global::GlobalObject;
+    use crate::test::JVM;
+    use crate::{Class, Object};
+    use jni::JNIEnv;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::{Duration, Instant};
+
+    fn holds_lock(env: &JNIEnv<'_>, object: &Object<'_>) -> bool {
+        env.call_static_method(
+            "java/lang/Thread",
+            "holdsLock",
+            "(Ljava/lang/Object;)Z",
+            &[object.inner.into()],
+        )
+        .unwrap()
+        .z()
+        .unwrap()
+    }
+
+    fn new_object<'a>(env: &'a JNIEnv<'a>) -> Object<'a> {
+        let obj = env.new_object("java/lang/Object", "()V", &[]).unwrap();
+        Object::new(env, obj, Class::Object(env).unwrap())
+    }
+
+    #[test]
+    fn lock() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        let object = new_object(&env);
+
+        assert!(!holds_lock(&env, &object));
+        {
+            let _guard = object.lock().unwrap();
+            assert!(holds_lock(&env, &object));
+            {
+                // Monitors are reentrant
+                let _inner = object.lock().unwrap();
+            }
+            assert!(holds_lock(&env, &object));
+        }
+        assert!(!holds_lock(&env, &object));
+    }
+
+    #[test]
+    fn notify() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        let object = new_object(&env);
+
+        let guard = object.lock().unwrap();
+        guard.notify().unwrap();
+        guard.notify_all().unwrap();
+        drop(guard);
+
+        // Without holding the monitor Java throws an IllegalMonitorStateException
+        let err = env.call_method(object.inner, "notify", "()V", &[]);
+        assert!(err.is_err());
+        env.exception_clear().unwrap();
+    }
+
+    #[test]
+    fn wait_timeout() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        let object = new_object(&env);
+
+        let guard = object.lock().unwrap();
+        let start = Instant::now();
+        // Nothing notifies the object, but a spurious wakeup may still return early
+        guard.wait_timeout(Duration::from_millis(50)).unwrap();
+        assert!(start.elapsed() < Duration::from_secs(10));
+        assert!(holds_lock(&env, guard.object()));
+    }
+
+    #[test]
+    fn wait_notify_across_threads() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        let object = new_object(&env);
+        let global = GlobalObject::new(&object).unwrap();
+        let ready = AtomicBool::new(false);
+
+        std::thread::scope(|scope| {
+            let guard = object.lock().unwrap();
+            scope.spawn(|| {
+                let env = jvm.attach_current_thread().unwrap();
+                let object = global.as_local(&env).unwrap();
+                // Blocks until the main thread releases the monitor by waiting
+                let guard = object.lock().unwrap();
+                ready.store(true, Ordering::SeqCst);
+                guard.notify_all().unwrap();
+            });
+
+            while !ready.load(Ordering::SeqCst) {
+                guard.wait_timeout(Duration::from_secs(5)).unwrap();
+            }
+        });
+        assert!(ready.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,355 @@
+use crate::error::Error;
+use jni::objects::{GlobalRef, JThrowable};
+use jni::sys::{jobject, JNI_FALSE};
+use jni::JNIEnv;
+use std::any::Any;
+use std::fmt::Display;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+/// The exception class thrown when no other class is configured, and when a Rust panic is caught
+pub const RUNTIME_EXCEPTION: &str = "java/lang/RuntimeException";
+
+/// A value which can be returned from a native function to Java
+pub trait NativeReturn {
+    /// The value returned to Java when an exception has been thrown. This is `null` for Objects and zero for primitives
+    fn sentinel() -> Self;
+}
+
+macro_rules! impl_native_return {
+    ($($ty:ty => $sentinel:expr),*) => {
+        $(
+            impl NativeReturn for $ty {
+                fn sentinel() -> Self {
+                    $sentinel
+                }
+            }
+        )*
+    };
+}
+
+// jboolean, jbyte, jchar, jshort, jint, jlong, jfloat and jdouble are aliases for these types
+impl_native_return!(
+    () => (),
+    jobject => std::ptr::null_mut(),
+    u8 => JNI_FALSE,
+    i8 => 0,
+    u16 => 0,
+    i16 => 0,
+    i32 => 0,
+    i64 => 0,
+    f32 => 0.0,
+    f64 => 0.0
+);
+
+/// An error which can be thrown into Java as an exception
+pub trait ThrowableError: Display {
+    /// The class of the exception to throw, e.g `java/lang/IllegalStateException`.
+    /// Defaults to [RUNTIME_EXCEPTION]
+    fn exception_class(&self) -> String {
+        RUNTIME_EXCEPTION.to_string()
+    }
+
+    /// The message of the exception to throw. Defaults to the Display representation of the error
+    fn exception_message(&self) -> String {
+        self.to_string()
+    }
+
+    /// A Throwable to throw as is, instead of creating a new exception from [ThrowableError::exception_class] and [ThrowableError::exception_message].
+    /// Used to rethrow a caught Java exception with its original stack trace and cause. Defaults to None
+    fn throwable(&self) -> Option<&GlobalRef> {
+        None
+    }
+}
+
+impl ThrowableError for Error {
+    fn exception_class(&self) -> String {
+        match self {
+            Self::JavaException(e) => e.class.replace('.', "/"),
+            Self::ClassMismatch { .. } => "java/lang/ClassCastException".to_string(),
+            Self::NullPointer { .. } | Self::NullMember { .. } => {
+                "java/lang/NullPointerException".to_string()
+            }
+            Self::Conversion { .. } | Self::ArgumentCount { .. } => {
+                "java/lang/IllegalArgumentException".to_string()
+            }
+            Self::NoSuchField { .. } => "java/lang/NoSuchFieldError".to_string(),
+            Self::NoSuchMethod { .. } | Self::AmbiguousMethod { .. } | Self::NotDeclared { .. } => {
+                "java/lang/NoSuchMethodError".to_string()
+            }
+            _ => RUNTIME_EXCEPTION.to_string(),
+        }
+    }
+
+    fn exception_message(&self) -> String {
+        match self {
+            Self::JavaException(e) => e.message.clone().unwrap_or_default(),
+            _ => self.to_string(),
+        }
+    }
+
+    fn throwable(&self) -> Option<&GlobalRef> {
+        match self {
+            Self::JavaException(e) => e.throwable.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> ThrowableError for Box<dyn ThrowableError + 'a> {
+    fn exception_class(&self) -> String {
+        self.as_ref().exception_class()
+    }
+
+    fn exception_message(&self) -> String {
+        self.as_ref().exception_message()
+    }
+
+    fn throwable(&self) -> Option<&GlobalRef> {
+        self.as_ref().throwable()
+    }
+}
+
+/// Run the body of a native function. If `f` returns an error, it is thrown into Java as an exception,
+/// with the class and message provided by [ThrowableError]. A Rust panic is thrown as a [RUNTIME_EXCEPTION].
+/// In both cases the [NativeReturn::sentinel] value for `T` is returned.
+///
+/// If a Java exception is already pending when `f` fails, that exception is left pending instead.
+///
+/// ```ignore
+/// #[no_mangle]
+/// pub extern "system" fn Java_MyClass_doNative(env: JNIEnv<'_>, _: JClass) -> jobject {
+///     ejni::throw_on_err(&env, || {
+///         let list = List::<String>::arraylist(&env)?;
+///         list.add("Foo".to_string())?;
+///         Ok::<_, ejni::Error>(list.into())
+///     })
+/// }
+/// ```
+pub fn throw_on_err<T, E, F>(env: &JNIEnv<'_>, f: F) -> T
+where
+    T: NativeReturn,
+    E: ThrowableError,
+    F: FnOnce() -> std::result::Result<T, E>,
+{
+    run(env, f, |e| match e.throwable() {
+        Some(throwable) => rethrow(env, throwable, &e.exception_class(), &e.exception_message()),
+        None => throw(env, &e.exception_class(), &e.exception_message()),
+    })
+}
+
+/// Run the body of a native function. If `f` returns an error, an exception of class `class` is thrown into Java,
+/// with the Display representation of the error as its message. `class` should be in the form `java/lang/IllegalStateException`.
+/// A Rust panic is thrown as a [RUNTIME_EXCEPTION]. In both cases the [NativeReturn::sentinel] value for `T` is returned.
+///
+/// If a Java exception is already pending when `f` fails, that exception is left pending instead.
+pub fn throw_on_err_as<T, E, F>(env: &JNIEnv<'_>, class: &str, f: F) -> T
+where
+    T: NativeReturn,
+    E: Display,
+    F: FnOnce() -> std::result::Result<T, E>,
+{
+    run(env, f, |e| throw(env, class, &e.to_string()))
+}
+
+fn run<T, E, F, M>(env: &JNIEnv<'_>, f: F, throw_error: M) -> T
+where
+    T: NativeReturn,
+    F: FnOnce() -> std::result::Result<T, E>,
+    M: FnOnce(&E),
+{
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => value,
+        Ok(Err(e)) => {
+            throw_error(&e);
+            T::sentinel()
+        }
+        Err(payload) => {
+            throw(
+                env,
+                RUNTIME_EXCEPTION,
+                &format!("Rust panic: {}", panic_message(payload.as_ref())),
+            );
+            T::sentinel()
+        }
+    }
+}
+
+/// Throw a new exception, unless one is already pending.
+/// If `class` cannot be thrown, e.g because it does not exist, a [RUNTIME_EXCEPTION] is thrown instead
+fn throw(env: &JNIEnv<'_>, class: &str, message: &str) {
+    // If we can't even query the JVM there is nothing left to do
+    if env.exception_check().unwrap_or(true) {
+        return;
+    }
+
+    if env.throw_new(class, message).is_ok() {
+        return;
+    }
+
+    let _ = env.exception_clear();
+    let _ = env.throw_new(
+        RUNTIME_EXCEPTION,
+        format!("{} (thrown as {})", message, class),
+    );
+}
+
+/// Throw `throwable` again, unless an exception is already pending.
+/// If it cannot be thrown, a new exception of `class` with `message` is thrown instead
+fn rethrow(env: &JNIEnv<'_>, throwable: &GlobalRef, class: &str, message: &str) {
+    if env.exception_check().unwrap_or(true) {
+        return;
+    }
+
+    if env.throw(JThrowable::from(throwable.as_obj())).is_ok() {
+        return;
+    }
+
+    let _ = env.exception_clear();
+    throw(env, class, message);
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "Box<dyn Any>"
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::error::JavaException;
+    use crate::test::JVM;
+    use crate::{List, Object};
+    use jni::sys::jint;
+    use thiserror::Error;
+
+    #[derive(Debug, Error)]
+    enum MyError {
+        #[error("Bad state")]
+        State,
+        #[error("Bad argument")]
+        Argument,
+    }
+
+    impl ThrowableError for MyError {
+        fn exception_class(&self) -> String {
+            match self {
+                Self::State => "java/lang/IllegalStateException",
+                Self::Argument => "java/lang/IllegalArgumentException",
+            }
+            .to_string()
+        }
+    }
+
+    #[test]
+    fn ok() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let value: jint = throw_on_err(&env, || Ok::<_, MyError>(10));
+        assert_eq!(10, value);
+        assert!(!env.exception_check().unwrap());
+    }
+
+    #[test]
+    fn err_mapped() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let value: jobject = throw_on_err(&env, || Err(MyError::State));
+        assert!(value.is_null());
+        let exception = JavaException::catch(&env).unwrap().unwrap();
+        assert_eq!("java.lang.IllegalStateException", exception.class);
+        assert_eq!(Some("Bad state".to_string()), exception.message);
+
+        let value: jint = throw_on_err(&env, || Err(MyError::Argument));
+        assert_eq!(0, value);
+        let exception = JavaException::catch(&env).unwrap().unwrap();
+        assert_eq!("java.lang.IllegalArgumentException", exception.class);
+    }
+
+    #[test]
+    fn err_as() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let value: f64 = throw_on_err_as(&env, "java/lang/IllegalStateException", || Err("Foo"));
+        assert_eq!(0.0, value);
+        let exception = JavaException::catch(&env).unwrap().unwrap();
+        assert_eq!("java.lang.IllegalStateException", exception.class);
+        assert_eq!(Some("Foo".to_string()), exception.message);
+    }
+
+    #[test]
+    fn err_unknown_class() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let _: () = throw_on_err_as(&env, "com/example/DoesNotExist", || Err("Foo"));
+        let exception = JavaException::catch(&env).unwrap().unwrap();
+        assert_eq!("java.lang.RuntimeException", exception.class);
+    }
+
+    #[test]
+    fn rethrow_java_exception() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let value: jobject = throw_on_err(&env, || {
+            let list = List::<i32>::arraylist(&env)?;
+            list.remove_at(5)?;
+            Ok::<_, Error>(list.into())
+        });
+        assert!(value.is_null());
+        let exception = JavaException::catch(&env).unwrap().unwrap();
+        assert_eq!("java.lang.IndexOutOfBoundsException", exception.class);
+    }
+
+    #[test]
+    fn rethrow_same_throwable() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        // Has no constructor taking a message, so it can not be thrown by class and message
+        let class = crate::test::test_class(&env, "ejni/test/FaultyException");
+        let thrown = env.new_object(class, "()V", &[]).unwrap();
+
+        let _: () = throw_on_err(&env, || {
+            env.throw(JThrowable::from(thrown)).unwrap();
+            Err(Error::from(JavaException::catch(&env)?.unwrap()))
+        });
+        let rethrown = env.exception_occurred().unwrap();
+        env.exception_clear().unwrap();
+        assert!(env.is_same_object(rethrown, thrown).unwrap());
+    }
+
+    #[test]
+    fn class_mismatch() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let _: jint = throw_on_err(&env, || {
+            let object = Object::new_string(&env, "Foo")?;
+            object.get_integer()
+        });
+        let exception = JavaException::catch(&env).unwrap().unwrap();
+        assert_eq!("java.lang.ClassCastException", exception.class);
+    }
+
+    #[test]
+    fn panic() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let value: jint = throw_on_err(&env, || -> std::result::Result<jint, MyError> {
+            panic!("Oh no")
+        });
+        assert_eq!(0, value);
+        let exception = JavaException::catch(&env).unwrap().unwrap();
+        assert_eq!("java.lang.RuntimeException", exception.class);
+        assert_eq!(Some("Rust panic: Oh no".to_string()), exception.message);
+    }
+}
@@ -1,10 +1,16 @@
+use crate::array::ArrayContents;
+use crate::cache::Cache;
 use crate::class::Class;
-use jni::errors::Result;
-use jni::objects::{JClass, JMethodID, JObject, JValue};
-use jni::signature::{JavaType, Primitive};
+use crate::error::{Error, JniResultExt, Result};
+use crate::field;
+use crate::java_type::{FromJava, IntoJava};
+use crate::method::{self, IntoArgs};
+use crate::monitor::MonitorGuard;
+use crate::signature;
+use jni::objects::{JClass, JObject, JValue};
 use jni::sys::{_jobject, jsize};
 use jni::JNIEnv;
-use thiserror::Error;
+use std::borrow::Borrow;
 
 /// Describes a Java Object
 #[derive(Clone)]
@@ -37,35 +43,11 @@ impl<'a> Into<*mut _jobject> for Object<'a> {
     }
 }
 
-/// Describes the possible errors that can occur when retrieving primitives from the primitive's Object equivalent
-#[derive(Debug, Error)]
-pub enum PrimitiveError<'a> {
-    /// JNI Error
-    #[error("JNI Error: {0}")]
-    Jni(#[from] jni::errors::Error),
-    /// Classes are not the same
-    #[error("Expected {0:?}, but found {1:?}")]
-    ClassMismatch(Class<'a>, Class<'a>),
-}
-
-/// Result returned from functions that retrieve a primitive from the respective primitive's Object equivalent
-pub type PrimitiveResult<'a, T> = std::result::Result<T, PrimitiveError<'a>>;
-
-/// Describes the possible errors that can occur when getting the items from an array
-#[derive(Debug, Error)]
-pub enum GetArrayError<'a> {
-    /// JNI Error
-    #[error("JNI Error: {0}")]
-    Jni(#[from] jni::errors::Error),
-    /// Class is not an array type
-    #[error("Expected {0:?} to be an array, it is not")]
-    NotArray(Class<'a>),
-}
-
 macro_rules! assert_same_class {
-    ($a:expr, $b:expr) => {
-        if $a.get_name()?.ne(&$b.get_name()?) {
-            return Err(PrimitiveError::ClassMismatch($b, $a.clone()));
+    ($obj:expr, $b:expr) => {
+        let expected = $b;
+        if !$obj.env.is_same_object($obj.class.class, expected.class)? {
+            return Err(Error::class_mismatch(&expected, &$obj.class));
         }
     };
 }
@@ -80,164 +62,250 @@ impl<'a> Object<'a> {
         }
     }
 
-    /// Get a constructor
-    fn get_constructor(env: &'a JNIEnv<'a>, class: Class<'a>, sig: &str) -> Result<JMethodID<'a>> {
-        env.get_method_id(class.class, "<init>", sig)
+    /// Create a new Object wrapper, verifying that the provided Object is an instance of the provided Class with `IsInstanceOf`.
+    /// Like a cast in Java, a null Object is an instance of every Class.
+    ///
+    /// Fails with [Error::ClassMismatch] if the Object is not an instance of the Class
+    pub fn checked(env: &'a JNIEnv<'a>, obj: JObject<'a>, class: Class<'a>) -> Result<Self> {
+        if !obj.is_null() && !env.is_instance_of(obj, class.class).catch(env)? {
+            let found = Class::new(env, env.get_object_class(obj).catch(env)?);
+            return Err(Error::class_mismatch(&class, &found));
+        }
+
+        Ok(Self::new(env, obj, class))
+    }
+
+    /// Cast the Object to `T`, e.g a [List](crate::List), after checking that the runtime Class of the Object is compatible with the Class of `T`.
+    /// The Object passed to `T::from_java` carries its runtime Class.
+    ///
+    /// Fails with [Error::NullPointer] if the Object is null, and with [Error::ClassMismatch] if it is not an instance of the Class of `T`
+    /// ```ignore
+    /// let list = object.cast::<List<String>>()?;
+    /// ```
+    pub fn cast<T: FromJava<'a>>(self) -> Result<T> {
+        let expected = T::class(self.env)?;
+        if self.inner.is_null() {
+            return Err(Error::null_pointer(&expected));
+        }
+
+        let class = Class::new(
+            self.env,
+            self.env.get_object_class(self.inner).catch(self.env)?,
+        );
+        if !class.is_compatible(&expected)? {
+            return Err(Error::class_mismatch(&expected, &class));
+        }
+
+        T::from_java(Self::new(self.env, self.inner, class))
     }
 
     /// Create a new java.lang.String
     pub fn new_string<S: AsRef<str>>(env: &'a JNIEnv<'a>, str: S) -> Result<Self> {
         Ok(Self::new(
             env,
-            env.new_string(str.as_ref())?.into(),
+            env.new_string(str.as_ref()).catch(env)?.into(),
             Class::String(env)?,
         ))
     }
 
     /// Create a new java.lang.Byte
-    pub fn new_byte_object(env: &'a JNIEnv<'a>, b: u8) -> Result<Self> {
-        Ok(Self::new(
+    pub fn new_byte_object(env: &'a JNIEnv<'a>, b: i8) -> Result<Self> {
+        let object = Cache::new_object(
             env,
-            env.new_object_unchecked(
-                Class::Byte(env)?.class,
-                Self::get_constructor(env, Class::Byte(env)?, "(B)V")?,
-                &[JValue::Byte(b as i8)],
-            )?,
-            Class::Byte(env)?,
-        ))
+            "java/lang/Byte",
+            signature!("(B)V"),
+            &[JValue::Byte(b)],
+        )?;
+        Ok(Self::new(env, object, Class::Byte(env)?))
     }
 
     /// Create a new java.lang.Long
     pub fn new_long_object(env: &'a JNIEnv<'a>, l: i64) -> Result<Self> {
-        Ok(Self::new(
+        let object = Cache::new_object(
             env,
-            env.new_object_unchecked(
-                Class::Long(env)?.class,
-                Self::get_constructor(env, Class::Long(env)?, "(J)V")?,
-                &[JValue::Long(l)],
-            )?,
-            Class::Long(env)?,
-        ))
+            "java/lang/Long",
+            signature!("(J)V"),
+            &[JValue::Long(l)],
+        )?;
+        Ok(Self::new(env, object, Class::Long(env)?))
     }
 
     /// Create a new java.lang.Integer
     pub fn new_integer_object(env: &'a JNIEnv<'a>, i: i32) -> Result<Self> {
-        Ok(Self::new(
+        let object = Cache::new_object(
             env,
-            env.new_object_unchecked(
-                Class::Integer(env)?.class,
-                Self::get_constructor(env, Class::Integer(env)?, "(I)V")?,
-                &[JValue::Int(i)],
-            )?,
-            Class::Integer(env)?,
-        ))
+            "java/lang/Integer",
+            signature!("(I)V"),
+            &[JValue::Int(i)],
+        )?;
+        Ok(Self::new(env, object, Class::Integer(env)?))
     }
 
     /// Create a new java.lang.Float
     pub fn new_float_object(env: &'a JNIEnv<'a>, f: f32) -> Result<Self> {
-        Ok(Self::new(
+        let object = Cache::new_object(
             env,
-            env.new_object_unchecked(
-                Class::Float(env)?.class,
-                Self::get_constructor(env, Class::Float(env)?, "(F)V")?,
-                &[JValue::Float(f)],
-            )?,
-            Class::Float(env)?,
-        ))
+            "java/lang/Float",
+            signature!("(F)V"),
+            &[JValue::Float(f)],
+        )?;
+        Ok(Self::new(env, object, Class::Float(env)?))
     }
 
     /// Create a new java.lang.Double
     pub fn new_double_object(env: &'a JNIEnv<'a>, d: f64) -> Result<Self> {
-        Ok(Self::new(
+        let object = Cache::new_object(
             env,
-            env.new_object_unchecked(
-                Class::Double(env)?.class,
-                Self::get_constructor(env, Class::Double(env)?, "(D)V")?,
-                &[JValue::Double(d)],
-            )?,
-            Class::Double(env)?,
-        ))
+            "java/lang/Double",
+            signature!("(D)V"),
+            &[JValue::Double(d)],
+        )?;
+        Ok(Self::new(env, object, Class::Double(env)?))
     }
 
     /// Create a new java.lang.Boolean
     pub fn new_boolean_object(env: &'a JNIEnv<'a>, b: bool) -> Result<Self> {
         let int_val = if b { 1 } else { 0 };
-        Ok(Self::new(
+        let object = Cache::new_object(
             env,
-            env.new_object_unchecked(
-                Class::Boolean(env)?.class,
-                Self::get_constructor(env, Class::Boolean(env)?, "(Z)V")?,
-                &[JValue::Bool(int_val)],
-            )?,
-            Class::Boolean(env)?,
-        ))
+            "java/lang/Boolean",
+            signature!("(Z)V"),
+            &[JValue::Bool(int_val)],
+        )?;
+        Ok(Self::new(env, object, Class::Boolean(env)?))
     }
 
     /// Create a new java.lang.Character
     pub fn new_character_object(env: &'a JNIEnv<'a>, c: u16) -> Result<Self> {
-        Ok(Self::new(
+        let object = Cache::new_object(
             env,
-            env.new_object_unchecked(
-                Class::Character(env)?.class,
-                Self::get_constructor(env, Class::Character(env)?, "(C)V")?,
-                &[JValue::Char(c)],
-            )?,
-            Class::Character(env)?,
-        ))
+            "java/lang/Character",
+            signature!("(C)V"),
+            &[JValue::Char(c)],
+        )?;
+        Ok(Self::new(env, object, Class::Character(env)?))
     }
 
     /// Create a new java.lang.Short
     pub fn new_short_object(env: &'a JNIEnv<'a>, s: i16) -> Result<Self> {
-        Ok(Self::new(
+        let object = Cache::new_object(
             env,
-            env.new_object_unchecked(
-                Class::Short(env)?.class,
-                Self::get_constructor(env, Class::Short(env)?, "(S)V")?,
-                &[JValue::Short(s)],
-            )?,
-            Class::Short(env)?,
-        ))
+            "java/lang/Short",
+            signature!("(S)V"),
+            &[JValue::Short(s)],
+        )?;
+        Ok(Self::new(env, object, Class::Short(env)?))
     }
 
-    /// Create an array from a slice of Objects. The caller must guarantee that all Objects contained in the slice are of the same Class as the provided Class
-    pub fn new_array(env: &'a JNIEnv<'a>, class: Class<'a>, data: &'a [Self]) -> Result<Self> {
-        let arr = env.new_object_array(data.len() as i32, class.class, JObject::null())?;
-
-        for i in 0..data.len() {
-            let elem = data.get(i).unwrap();
-            env.set_object_array_element(arr, i as i32, elem.inner)?;
+    /// Create an array from the Objects yielded by `data`. The caller must guarantee that all Objects are of the same Class as the provided Class.
+    /// The provided Class may be an array Class itself, e.g `int[]` to create an `int[][]`
+    pub fn new_array<I>(env: &'a JNIEnv<'a>, class: Class<'a>, data: I) -> Result<Self>
+    where
+        I: IntoIterator,
+        I::Item: Borrow<Self>,
+    {
+        // The length of the array must be known up front
+        let data: Vec<I::Item> = data.into_iter().collect();
+        let arr = env
+            .new_object_array(data.len() as jsize, class.class, JObject::null())
+            .catch(env)?;
+
+        for (i, elem) in data.iter().enumerate() {
+            env.set_object_array_element(arr, i as jsize, elem.borrow().inner)
+                .catch(env)?;
         }
 
         Ok(Self::new(env, JObject::from(arr), class.array_type(env)?))
     }
 
-    /// Get the elements of the array. The caller must guarantee that the current Object is an array
-    pub fn get_array(&self) -> std::result::Result<Vec<Self>, GetArrayError<'a>> {
-        if !self.is_array()? {
-            return Err(GetArrayError::NotArray(self.class.clone()));
+    /// Get the elements of an array of Objects. Each element has the component type of the array as its Class,
+    /// e.g `java.lang.String[]` for a `java.lang.String[][]`.
+    ///
+    /// Fails with [Error::NotArray] if the current Object is not an array,
+    /// and with [Error::PrimitiveArray] if it is an array of primitives. Use [Object::get_array_contents] for those
+    pub fn get_array(&self) -> Result<Vec<Self>> {
+        let component = self.component_type()?;
+        if component.is_primitive()? {
+            return Err(Error::primitive_array(&self.class));
         }
 
-        let class_name = self.class.get_name()?;
-        let regular_name = &class_name[2..class_name.len() - 1];
-        let object_class = Class::for_name(self.env, regular_name)?;
-
-        let len = self.env.get_array_length(self.inner.into_inner())?;
+        let len = self
+            .env
+            .get_array_length(self.inner.into_inner())
+            .catch(self.env)?;
         let mut buf = Vec::with_capacity(len as usize);
         for i in 0..len {
             let obj = self
                 .env
-                .get_object_array_element(self.inner.into_inner(), i as jsize)?;
-            let object = Self::new(self.env, obj, object_class.clone());
+                .get_object_array_element(self.inner.into_inner(), i as jsize)
+                .catch(self.env)?;
+            let object = Self::new(self.env, obj, component.clone());
             buf.push(object);
         }
 
         Ok(buf)
     }
 
-    /// Check if the current object is an array
-    fn is_array(&self) -> Result<bool> {
-        let class_name = self.class.get_name()?;
-        Ok(class_name.starts_with("["))
+    /// Get the elements of an array of any dimension and component type.
+    /// Primitive arrays are copied into a Vec, arrays of arrays are retrieved recursively,
+    /// and other arrays result in their elements, as retrieved by [Object::get_array].
+    ///
+    /// Fails with [Error::NotArray] if the current Object is not an array
+    pub fn get_array_contents(&self) -> Result<ArrayContents<'a>> {
+        let component = self.component_type()?;
+        let descriptor = component.descriptor()?;
+        if let Some(contents) =
+            ArrayContents::primitive(self.env, self.inner.into_inner(), &descriptor)?
+        {
+            return Ok(contents);
+        }
+
+        if !component.is_array()? {
+            return Ok(ArrayContents::Object(self.get_array()?));
+        }
+
+        let len = self
+            .env
+            .get_array_length(self.inner.into_inner())
+            .catch(self.env)?;
+        let mut buf = Vec::with_capacity(len as usize);
+        for i in 0..len {
+            let obj = self
+                .env
+                .get_object_array_element(self.inner.into_inner(), i as jsize)
+                .catch(self.env)?;
+            if obj.is_null() {
+                buf.push(None);
+                continue;
+            }
+
+            let contents = Self::new(self.env, obj, component.clone()).get_array_contents();
+            // The contents do not reference the nested array itself, only its elements
+            self.env.delete_local_ref(obj).catch(self.env)?;
+            buf.push(Some(contents?));
+        }
+
+        Ok(ArrayContents::Array(buf))
+    }
+
+    /// Get the component type of the runtime Class of the current Object, failing if it is not an array
+    fn component_type(&self) -> Result<Class<'a>> {
+        let class = Class::new(
+            self.env,
+            self.env.get_object_class(self.inner).catch(self.env)?,
+        );
+        class
+            .component_type()?
+            .ok_or_else(|| Error::not_array(&self.class))
+    }
+
+    /// Check if the runtime Class of the current Object is an array Class
+    pub fn is_array(&self) -> Result<bool> {
+        let class = Class::new(
+            self.env,
+            self.env.get_object_class(self.inner).catch(self.env)?,
+        );
+        class.is_array()
     }
 
     /// Call java.object.Object#getClass() on the current Object
@@ -246,96 +314,90 @@ impl<'a> Object<'a> {
     }
 
     /// Get the byte value from this Object. The Object must be of type java.lang.Byte
-    pub fn get_byte(&self) -> PrimitiveResult<u8> {
-        assert_same_class!(self.class, Class::Byte(self.env)?);
+    pub fn get_byte(&self) -> Result<i8> {
+        assert_same_class!(self, Class::Byte(self.env)?);
 
-        let method = self
-            .env
-            .get_method_id("java/lang/Byte", "byteValue", "()B")?;
-        let value = self.env.call_method_unchecked(
+        let value = Cache::call_method(
+            self.env,
             self.inner,
-            method,
-            JavaType::Primitive(Primitive::Byte),
+            "java/lang/Byte",
+            "byteValue",
+            signature!("()B"),
             &[],
         )?;
-        Ok(value.b()? as u8)
+        Ok(value.b()?)
     }
 
     /// Get the long value from this Object. The Object must be of type java.lang.Long
-    pub fn get_long(&self) -> PrimitiveResult<i64> {
-        assert_same_class!(self.class, Class::Long(self.env)?);
+    pub fn get_long(&self) -> Result<i64> {
+        assert_same_class!(self, Class::Long(self.env)?);
 
-        let method = self
-            .env
-            .get_method_id("java/lang/Long", "longValue", "()J")?;
-        let value = self.env.call_method_unchecked(
+        let value = Cache::call_method(
+            self.env,
             self.inner,
-            method,
-            JavaType::Primitive(Primitive::Long),
+            "java/lang/Long",
+            "longValue",
+            signature!("()J"),
             &[],
         )?;
         Ok(value.j()?)
     }
 
     /// Get the int value from this Object. The Object must be of type java.lang.Integer
-    pub fn get_integer(&self) -> PrimitiveResult<i32> {
-        assert_same_class!(self.class, Class::Integer(self.env)?);
+    pub fn get_integer(&self) -> Result<i32> {
+        assert_same_class!(self, Class::Integer(self.env)?);
 
-        let method = self
-            .env
-            .get_method_id("java/lang/Integer", "intValue", "()I")?;
-        let value = self.env.call_method_unchecked(
+        let value = Cache::call_method(
+            self.env,
             self.inner,
-            method,
-            JavaType::Primitive(Primitive::Int),
+            "java/lang/Integer",
+            "intValue",
+            signature!("()I"),
             &[],
         )?;
         Ok(value.i()?)
     }
 
     /// Get the float value from this Object. The Object must be of type java.lang.Float
-    pub fn get_float(&self) -> PrimitiveResult<f32> {
-        assert_same_class!(self.class, Class::Float(self.env)?);
+    pub fn get_float(&self) -> Result<f32> {
+        assert_same_class!(self, Class::Float(self.env)?);
 
-        let method = self
-            .env
-            .get_method_id("java/lang/Float", "floatValue", "()F")?;
-        let value = self.env.call_method_unchecked(
+        let value = Cache::call_method(
+            self.env,
             self.inner,
-            method,
-            JavaType::Primitive(Primitive::Float),
+            "java/lang/Float",
+            "floatValue",
+            signature!("()F"),
             &[],
         )?;
         Ok(value.f()?)
     }
 
     /// Get the double value from this Object. The Object must be of type java.lang.Double
-    pub fn get_double(&self) -> PrimitiveResult<f64> {
-        assert_same_class!(self.class, Class::Double(self.env)?);
+    pub fn get_double(&self) -> Result<f64> {
+        assert_same_class!(self, Class::Double(self.env)?);
 
-        let method = self
-            .env
-            .get_method_id("java/lang/Double", "doubleValue", "()D")?;
-        let value = self.env.call_method_unchecked(
+        let value = Cache::call_method(
+            self.env,
             self.inner,
-            method,
-            JavaType::Primitive(Primitive::Double),
+            "java/lang/Double",
+            "doubleValue",
+            signature!("()D"),
             &[],
         )?;
         Ok(value.d()?)
     }
 
     /// Get the boolean value from this Object. The Object must be of type java.lang.Boolean
-    pub fn get_boolean(&self) -> PrimitiveResult<bool> {
-        assert_same_class!(self.class, Class::Boolean(self.env)?);
+    pub fn get_boolean(&self) -> Result<bool> {
+        assert_same_class!(self, Class::Boolean(self.env)?);
 
-        let method = self
-            .env
-            .get_method_id("java/lang/Boolean", "booleanValue", "()Z")?;
-        let value = self.env.call_method_unchecked(
+        let value = Cache::call_method(
+            self.env,
             self.inner,
-            method,
-            JavaType::Primitive(Primitive::Boolean),
+            "java/lang/Boolean",
+            "booleanValue",
+            signature!("()Z"),
             &[],
         )?;
         Ok(value.z()?)
@@ -343,32 +405,30 @@ impl<'a> Object<'a> {
 
     /// Get the char value from this Object. The Object must be of type java.lang.Character
     /// Note that a Java character is two bytes. Java uses Unicode
-    pub fn get_char(&self) -> PrimitiveResult<u16> {
-        assert_same_class!(self.class, Class::Character(self.env)?);
+    pub fn get_char(&self) -> Result<u16> {
+        assert_same_class!(self, Class::Character(self.env)?);
 
-        let method = self
-            .env
-            .get_method_id("java/lang/Character", "charValue", "()C")?;
-        let value = self.env.call_method_unchecked(
+        let value = Cache::call_method(
+            self.env,
             self.inner,
-            method,
-            JavaType::Primitive(Primitive::Char),
+            "java/lang/Character",
+            "charValue",
+            signature!("()C"),
             &[],
         )?;
         Ok(value.c()?)
     }
 
     /// Get the short value from this Object. The Object must be of type java.lang.Short
-    pub fn get_short(&self) -> PrimitiveResult<i16> {
-        assert_same_class!(self.class, Class::Short(self.env)?);
+    pub fn get_short(&self) -> Result<i16> {
+        assert_same_class!(self, Class::Short(self.env)?);
 
-        let method = self
-            .env
-            .get_method_id("java/lang/Short", "shortValue", "()S")?;
-        let value = self.env.call_method_unchecked(
+        let value = Cache::call_method(
+            self.env,
             self.inner,
-            method,
-            JavaType::Primitive(Primitive::Short),
+            "java/lang/Short",
+            "shortValue",
+            signature!("()S"),
             &[],
         )?;
         Ok(value.s()?)
@@ -376,32 +436,85 @@ impl<'a> Object<'a> {
 
     /// Call java.object.Object#getClass() on `obj`
     pub fn get_class(obj: &Object<'a>, env: &'a JNIEnv<'a>) -> Result<Class<'a>> {
-        let class_object = env
-            .call_method(obj.inner, "getClass", "()Ljava/lang/Class;", &[])?
-            .l()?;
+        let class_object = Cache::call_method(
+            env,
+            obj.inner,
+            "java/lang/Object",
+            "getClass",
+            signature!("()Ljava/lang/Class;"),
+            &[],
+        )?
+        .l()?;
         let class_name = Class::new(env, JClass::from(class_object)).get_name()?;
         Class::for_name(env, &class_name)
     }
 
     /// Check if the current Object is an instanceof the provided Class
     pub fn instance_of_class(&self, class: &Class) -> Result<bool> {
-        self.env.is_instance_of(self.inner, class.class)
+        Ok(self.env.is_instance_of(self.inner, class.class)?)
     }
 
     /// Check if the current Object is of the same instance as the other Object
     pub fn instance_of_same_object(&self, other: &Self) -> Result<bool> {
-        self.env.is_instance_of(self.inner, other.class.class)
+        Ok(self.env.is_instance_of(self.inner, other.class.class)?)
+    }
+
+    /// Enter the monitor of the Object, like `synchronized (object)` in Java, blocking until it is available.
+    /// The monitor is exited when the returned guard is dropped, see [MonitorGuard]
+    ///
+    /// ```ignore
+    /// let guard = map.inner.lock()?;
+    /// map.put(key, value)?;
+    /// guard.notify_all()?;
+    /// ```
+    pub fn lock(&self) -> Result<MonitorGuard<'_, 'a>> {
+        MonitorGuard::enter(self)
     }
 
     /// Check if the current Object is equal to another Object.
     pub fn equals(&self, other: &Object<'a>) -> Result<bool> {
-        let equals = self.env.call_method(
+        let equals = Cache::call_method(
+            self.env,
             self.inner,
+            "java/lang/Object",
             "equals",
-            "(Ljava/lang/Object;)Z",
+            signature!("(Ljava/lang/Object;)Z"),
             &[other.into()],
         )?;
-        equals.z()
+        Ok(equals.z()?)
+    }
+
+    /// Get the value of an instance field. The field may be declared by the Object's Class or any of its superclasses, regardless of its visibility.
+    /// Primitive fields are boxed before they are converted into `T`. Returns None if the field is `null`
+    pub fn get_field<T: FromJava<'a>>(&self, name: &str) -> Result<Option<T>> {
+        field::get_field(self, name)
+    }
+
+    /// Call a public instance method. The signature is built from the [JavaType::descriptor] of the arguments and of `R`,
+    /// e.g calling with `(i32, String)` and `R = bool` looks up `(ILjava/lang/String;)Z`. Use `()` as `R` for `void` methods.
+    /// Returns None if the method returns `null` or `void`
+    ///
+    /// [JavaType::descriptor]: crate::JavaType::descriptor
+    pub fn call<R: FromJava<'a>, A: IntoArgs<'a>>(&self, name: &str, args: A) -> Result<Option<R>> {
+        method::call(self, name, args, false)
+    }
+
+    /// Call a public instance method, picking the overload through `Class#getMethods()`.
+    /// Overloads whose parameters match the descriptors of the arguments exactly are preferred, followed by those taking
+    /// the primitive of a boxed argument. The most specific of the remaining overloads is called.
+    /// The return value is converted into `R` regardless of the declared return type of the method
+    pub fn call_resolved<R: FromJava<'a>, A: IntoArgs<'a>>(
+        &self,
+        name: &str,
+        args: A,
+    ) -> Result<Option<R>> {
+        method::call(self, name, args, true)
+    }
+
+    /// Set the value of an instance field. The field may be declared by the Object's Class or any of its superclasses, regardless of its visibility.
+    /// For primitive fields `value` must convert into the boxed type of the field, e.g `i32` for an `int` field
+    pub fn set_field<T: IntoJava<'a>>(&self, name: &str, value: T) -> Result<()> {
+        field::set_field(self, name, value)
     }
 }
 
@@ -410,9 +523,12 @@ mod test {
     #![allow(non_snake_case)]
 
     use super::*;
+    use crate::abstractions::{JavaString, List, Map, Set};
     use crate::class::Class;
+    use crate::native::ThrowableError;
     use crate::test::JVM;
     use jni::objects::JString;
+    use jni::sys::jobject;
 
     #[test]
     fn new_string() {
@@ -505,7 +621,7 @@ mod test {
             .unwrap()
             .z()
             .unwrap();
-        assert_eq!(true, jboolean);
+        assert!(jboolean);
 
         let jBoolean = Object::new_boolean_object(&env, false).unwrap();
         let jboolean = env
@@ -513,7 +629,7 @@ mod test {
             .unwrap()
             .z()
             .unwrap();
-        assert_eq!(false, jboolean);
+        assert!(!jboolean);
     }
 
     #[test]
@@ -544,6 +660,64 @@ mod test {
         assert_eq!(123, jshort);
     }
 
+    #[test]
+    fn checked() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let string: JObject = env.new_string("Foo").unwrap().into();
+        let object = Object::checked(&env, string, Class::CharSequence(&env).unwrap()).unwrap();
+        assert_eq!("java.lang.CharSequence", object.class.get_name().unwrap());
+
+        let null = Object::checked(&env, JObject::null(), Class::List(&env).unwrap()).unwrap();
+        assert!(null.inner.is_null());
+
+        let err = Object::checked(&env, string, Class::Integer(&env).unwrap())
+            .map(|_| ())
+            .unwrap_err();
+        match err {
+            Error::ClassMismatch { expected, found } => {
+                assert_eq!("java.lang.Integer", expected);
+                assert_eq!("java.lang.String", found);
+            }
+            _ => panic!("Expected a ClassMismatch error"),
+        }
+    }
+
+    #[test]
+    fn cast() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let map = Map::<String, i32>::hashmap(&env).unwrap();
+        map.put("Foo".to_string(), 1).unwrap();
+        let inner: jobject = map.into();
+        let object = Object::new(&env, inner.into(), Class::Object(&env).unwrap());
+
+        let map = object.cast::<Map<String, i32>>().unwrap();
+        assert_eq!("java.util.HashMap", map.inner.class.get_name().unwrap());
+        assert_eq!(Some(1), map.get("Foo".to_string()).unwrap());
+
+        let value = Object::new_integer_object(&env, 5).unwrap();
+        assert_eq!(5, value.cast::<i32>().unwrap());
+    }
+
+    #[test]
+    fn cast_class_cast() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let string = Object::new_string(&env, "Foo").unwrap();
+        let err = string.cast::<Set<String>>().map(|_| ()).unwrap_err();
+        assert!(matches!(err, Error::ClassMismatch { .. }));
+        assert_eq!("java/lang/ClassCastException", err.exception_class());
+
+        let null = Object::new(&env, JObject::null(), Class::Object(&env).unwrap());
+        let err = null.cast::<List<String>>().map(|_| ()).unwrap_err();
+        assert!(matches!(err, Error::NullPointer { .. }));
+        assert_eq!("java/lang/NullPointerException", err.exception_class());
+    }
+
     #[test]
     fn new_array() {
         let jvm = JVM.lock().unwrap();
@@ -570,7 +744,7 @@ mod test {
             .z()
             .unwrap();
 
-        assert_eq!(true, bool_value);
+        assert!(bool_value);
     }
 
     #[test]
@@ -604,12 +778,135 @@ mod test {
         assert!(array.is_err());
 
         let err = array.err().unwrap();
-        let is_correct_err = match err {
-            GetArrayError::Jni(_) => false,
-            GetArrayError::NotArray(_) => true,
+        assert!(matches!(err, Error::NotArray { ref class } if class == "java.lang.Boolean"));
+    }
+
+    #[test]
+    fn new_array_from_iter() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let strings = (0..3).map(|i| Object::new_string(&env, i.to_string()).unwrap());
+        let array = Object::new_array(&env, Class::String(&env).unwrap(), strings).unwrap();
+        assert_eq!("[Ljava.lang.String;", array.class.get_name().unwrap());
+
+        let strings: Vec<_> = array
+            .get_array()
+            .unwrap()
+            .into_iter()
+            .map(|s| JavaString::new(&env, s).into_rust().unwrap())
+            .collect();
+        assert_eq!(vec!["0", "1", "2"], strings);
+    }
+
+    #[test]
+    fn get_array_nested() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let string_array_class = Class::String(&env).unwrap().array_type(&env).unwrap();
+        let inner = Object::new_array(
+            &env,
+            Class::String(&env).unwrap(),
+            vec![Object::new_string(&env, "Foo").unwrap()],
+        )
+        .unwrap();
+        let outer = Object::new_array(&env, string_array_class, &[inner]).unwrap();
+        assert_eq!("[[Ljava.lang.String;", outer.class.get_name().unwrap());
+
+        let elements = outer.get_array().unwrap();
+        assert_eq!(1, elements.len());
+        assert_eq!("[Ljava.lang.String;", elements[0].class.get_name().unwrap());
+        assert_eq!(1, elements[0].get_array().unwrap().len());
+    }
+
+    #[test]
+    fn get_array_primitive() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let ints = Object::new(
+            &env,
+            env.new_int_array(3).unwrap().into(),
+            Class::Object(&env).unwrap(),
+        );
+        assert!(ints.is_array().unwrap());
+        assert!(matches!(
+            ints.get_array().map(|_| ()).unwrap_err(),
+            Error::PrimitiveArray { .. }
+        ));
+    }
+
+    #[test]
+    fn get_array_contents_primitive() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let booleans = env.new_boolean_array(2).unwrap();
+        env.set_boolean_array_region(booleans, 0, &[0, 1]).unwrap();
+        let booleans = Object::new(&env, booleans.into(), Class::Object(&env).unwrap());
+        assert!(matches!(
+            booleans.get_array_contents().unwrap(),
+            ArrayContents::Boolean(b) if b == vec![false, true]
+        ));
+
+        let doubles = env.new_double_array(2).unwrap();
+        env.set_double_array_region(doubles, 0, &[1.5, 2.5])
+            .unwrap();
+        let doubles = Object::new(&env, doubles.into(), Class::Object(&env).unwrap());
+        assert!(matches!(
+            doubles.get_array_contents().unwrap(),
+            ArrayContents::Double(d) if d == vec![1.5, 2.5]
+        ));
+    }
+
+    #[test]
+    fn get_array_contents_multi_dimensional() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let int_array_class = Class::int(&env).unwrap().array_type(&env).unwrap();
+        let rows: Vec<_> = (0..3)
+            .map(|row| {
+                let ints = env.new_int_array(2).unwrap();
+                env.set_int_array_region(ints, 0, &[row, row * 10]).unwrap();
+                Object::new(&env, ints.into(), int_array_class.clone())
+            })
+            .collect();
+        let mut rows = rows.into_iter().map(Some).collect::<Vec<_>>();
+        rows.push(None);
+        let null = Object::new(&env, JObject::null(), int_array_class.clone());
+        let matrix = Object::new_array(
+            &env,
+            int_array_class,
+            rows.iter().map(|row| row.as_ref().unwrap_or(&null)),
+        )
+        .unwrap();
+        assert_eq!("[[I", matrix.class.get_name().unwrap());
+
+        let contents = match matrix.get_array_contents().unwrap() {
+            ArrayContents::Array(contents) => contents,
+            _ => panic!("Expected an array of arrays"),
         };
+        assert_eq!(4, contents.len());
+        assert!(contents[3].is_none());
+        for (row, contents) in contents.iter().take(3).enumerate() {
+            assert!(
+                matches!(contents, Some(ArrayContents::Int(ints)) if ints == &vec![row as i32, row as i32 * 10])
+            );
+        }
+    }
+
+    #[test]
+    fn get_array_contents_not_array() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
 
-        assert!(is_correct_err);
+        let object = Object::new_string(&env, "Foo").unwrap();
+        assert!(matches!(
+            object.get_array_contents().map(|_| ()).unwrap_err(),
+            Error::NotArray { .. }
+        ));
     }
 
     #[test]
@@ -726,7 +1023,7 @@ mod test {
         let boolean = Object::new_boolean_object(&env, true).unwrap();
         let value = boolean.get_boolean().unwrap();
 
-        assert_eq!(true, value);
+        assert!(value);
     }
 
     #[test]
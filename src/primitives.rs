@@ -1,4 +1,4 @@
-use jni::errors::Result;
+use crate::error::{JniResultExt, Result};
 use jni::sys::{
     jbooleanArray, jbyteArray, jcharArray, jdoubleArray, jfloatArray, jintArray, jlongArray,
     jshortArray,
@@ -11,8 +11,8 @@ pub struct Primitive();
 impl Primitive {
     /// Create a new int[]
     pub fn new_int_array(env: &JNIEnv<'_>, ints: &[i32]) -> Result<jintArray> {
-        let arr = env.new_int_array(ints.len() as i32)?;
-        env.set_int_array_region(arr, 0, ints)?;
+        let arr = env.new_int_array(ints.len() as i32).catch(env)?;
+        env.set_int_array_region(arr, 0, ints).catch(env)?;
         Ok(arr)
     }
 
@@ -20,43 +20,43 @@ impl Primitive {
     pub fn new_byte_array(env: &JNIEnv<'_>, bytes: &[u8]) -> Result<jbyteArray> {
         // SAFETY: The compiler guarantees safety here.
         let bytes: &[i8] = unsafe { std::mem::transmute(bytes) };
-        let arr = env.new_byte_array(bytes.len() as i32)?;
-        env.set_byte_array_region(arr, 0, bytes)?;
+        let arr = env.new_byte_array(bytes.len() as i32).catch(env)?;
+        env.set_byte_array_region(arr, 0, bytes).catch(env)?;
         Ok(arr)
     }
 
     /// Create a new long[]
     pub fn new_long_array(env: &JNIEnv<'_>, longs: &[i64]) -> Result<jlongArray> {
-        let arr = env.new_long_array(longs.len() as i32)?;
-        env.set_long_array_region(arr, 0, longs)?;
+        let arr = env.new_long_array(longs.len() as i32).catch(env)?;
+        env.set_long_array_region(arr, 0, longs).catch(env)?;
         Ok(arr)
     }
 
     /// Create a new float[]
     pub fn new_float_array(env: &JNIEnv<'_>, floats: &[f32]) -> Result<jfloatArray> {
-        let arr = env.new_float_array(floats.len() as i32)?;
-        env.set_float_array_region(arr, 0, floats)?;
+        let arr = env.new_float_array(floats.len() as i32).catch(env)?;
+        env.set_float_array_region(arr, 0, floats).catch(env)?;
         Ok(arr)
     }
 
     /// Create a new double[]
     pub fn new_double_array(env: &JNIEnv<'_>, doubles: &[f64]) -> Result<jdoubleArray> {
-        let arr = env.new_double_array(doubles.len() as i32)?;
-        env.set_double_array_region(arr, 0, doubles)?;
+        let arr = env.new_double_array(doubles.len() as i32).catch(env)?;
+        env.set_double_array_region(arr, 0, doubles).catch(env)?;
         Ok(arr)
     }
 
     /// Create a new char[]
     pub fn new_char_array(env: &JNIEnv<'_>, chars: &[u16]) -> Result<jcharArray> {
-        let arr = env.new_char_array(chars.len() as i32)?;
-        env.set_char_array_region(arr, 0, chars)?;
+        let arr = env.new_char_array(chars.len() as i32).catch(env)?;
+        env.set_char_array_region(arr, 0, chars).catch(env)?;
         Ok(arr)
     }
 
     /// Create a new short[]
     pub fn new_short_array(env: &JNIEnv<'_>, shorts: &[i16]) -> Result<jshortArray> {
-        let arr = env.new_short_array(shorts.len() as i32)?;
-        env.set_short_array_region(arr, 0, shorts)?;
+        let arr = env.new_short_array(shorts.len() as i32).catch(env)?;
+        env.set_short_array_region(arr, 0, shorts).catch(env)?;
         Ok(arr)
     }
 
@@ -64,24 +64,24 @@ impl Primitive {
     pub fn new_boolean_array(env: &JNIEnv<'_>, booleans: &[bool]) -> Result<jbooleanArray> {
         // SAFETY: The compiler guarantees safety here.
         let booleans: &[u8] = unsafe { std::mem::transmute(booleans) };
-        let arr = env.new_boolean_array(booleans.len() as i32)?;
-        env.set_boolean_array_region(arr, 0, booleans)?;
+        let arr = env.new_boolean_array(booleans.len() as i32).catch(env)?;
+        env.set_boolean_array_region(arr, 0, booleans).catch(env)?;
         Ok(arr)
     }
 
     /// Copy an int[] to a Vec.
     pub fn get_int_array(env: &JNIEnv<'_>, ints: jintArray) -> Result<Vec<i32>> {
-        let len = env.get_array_length(ints)?;
+        let len = env.get_array_length(ints).catch(env)?;
         let mut buf = vec![0; len as usize];
-        env.get_int_array_region(ints, 0, &mut buf)?;
+        env.get_int_array_region(ints, 0, &mut buf).catch(env)?;
         Ok(buf)
     }
 
     /// Copy a byte[] to a Vec
     pub fn get_byte_array(env: &JNIEnv<'_>, bytes: jbyteArray) -> Result<Vec<u8>> {
-        let len = env.get_array_length(bytes)?;
+        let len = env.get_array_length(bytes).catch(env)?;
         let mut buf = vec![0; len as usize];
-        env.get_byte_array_region(bytes, 0, &mut buf)?;
+        env.get_byte_array_region(bytes, 0, &mut buf).catch(env)?;
 
         let buf: Vec<_> = buf.into_iter().map(|f| f as u8).collect();
 
@@ -90,49 +90,51 @@ impl Primitive {
 
     /// Copy a long[] to a Vec
     pub fn get_long_array(env: &JNIEnv<'_>, longs: jlongArray) -> Result<Vec<i64>> {
-        let len = env.get_array_length(longs)?;
+        let len = env.get_array_length(longs).catch(env)?;
         let mut buf = vec![0; len as usize];
-        env.get_long_array_region(longs, 0, &mut buf)?;
+        env.get_long_array_region(longs, 0, &mut buf).catch(env)?;
         Ok(buf)
     }
 
     /// Copy a float[] to a Vec
     pub fn get_float_array(env: &JNIEnv<'_>, floats: jfloatArray) -> Result<Vec<f32>> {
-        let len = env.get_array_length(floats)?;
+        let len = env.get_array_length(floats).catch(env)?;
         let mut buf = vec![0f32; len as usize];
-        env.get_float_array_region(floats, 0, &mut buf)?;
+        env.get_float_array_region(floats, 0, &mut buf).catch(env)?;
         Ok(buf)
     }
 
     /// Copy a double[] to a Vec
     pub fn get_double_array(env: &JNIEnv<'_>, doubles: jdoubleArray) -> Result<Vec<f64>> {
-        let len = env.get_array_length(doubles)?;
+        let len = env.get_array_length(doubles).catch(env)?;
         let mut buf = vec![0f64; len as usize];
-        env.get_double_array_region(doubles, 0, &mut buf)?;
+        env.get_double_array_region(doubles, 0, &mut buf)
+            .catch(env)?;
         Ok(buf)
     }
 
     /// Copy a char[] to a Vec
     pub fn get_char_array(env: &JNIEnv<'_>, chars: jcharArray) -> Result<Vec<u16>> {
-        let len = env.get_array_length(chars)?;
+        let len = env.get_array_length(chars).catch(env)?;
         let mut buf = vec![0; len as usize];
-        env.get_char_array_region(chars, 0, &mut buf)?;
+        env.get_char_array_region(chars, 0, &mut buf).catch(env)?;
         Ok(buf)
     }
 
     /// Copy a short[] to a Vec
     pub fn get_short_array(env: &JNIEnv<'_>, shorts: jshortArray) -> Result<Vec<i16>> {
-        let len = env.get_array_length(shorts)?;
+        let len = env.get_array_length(shorts).catch(env)?;
         let mut buf = vec![0; len as usize];
-        env.get_short_array_region(shorts, 0, &mut buf)?;
+        env.get_short_array_region(shorts, 0, &mut buf).catch(env)?;
         Ok(buf)
     }
 
     /// Copy a boolean[] to a Vec
     pub fn get_boolean_array(env: &JNIEnv<'_>, booleans: jbooleanArray) -> Result<Vec<bool>> {
-        let len = env.get_array_length(booleans)?;
+        let len = env.get_array_length(booleans).catch(env)?;
         let mut buf = vec![0; len as usize];
-        env.get_boolean_array_region(booleans, 0, &mut buf)?;
+        env.get_boolean_array_region(booleans, 0, &mut buf)
+            .catch(env)?;
 
         let buf: Vec<_> = buf.into_iter().map(|f| f == 1).collect();
 
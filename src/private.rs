@@ -0,0 +1,520 @@
+//! Support for the code generated by the [export](crate::export) macro, the `JavaType`, `IntoJava` and `FromJava` derives and `ejni-bindgen`. Not part of the public API
+
+pub use jni;
+
+use crate::abstractions::ClassLoader;
+use crate::cache::Cache;
+use crate::class::Class;
+use crate::error::{Error, JniResultExt, Result};
+use crate::java_type::{from_nullable, into_value, FromJava, IntoJava};
+use crate::method::Argument;
+use crate::native::ThrowableError;
+use crate::object::Object;
+use crate::signature::{Signature, TypeDescriptor};
+use jni::objects::{JMethodID, JObject, JStaticMethodID, JValue};
+use jni::signature::{JavaType as SignatureType, TypeSignature};
+use jni::sys::{jmethodID, jobject};
+use jni::JNIEnv;
+
+/// The error returned from the body of an exported function, thrown into Java by [throw_on_err](crate::throw_on_err)
+pub type Thrown<'a> = Box<dyn ThrowableError + 'a>;
+
+/// Box an error, so that the errors of the argument conversions and of the exported function can be returned from the same closure
+pub fn thrown<'a, E: ThrowableError + 'a>(e: E) -> Thrown<'a> {
+    Box::new(e)
+}
+
+/// Convert a non-null Object argument into `T`, checking its runtime Class like [Object::cast]
+pub fn from_arg<'a, T: FromJava<'a>>(env: &'a JNIEnv<'a>, obj: jobject) -> Result<T> {
+    Object::new(env, obj.into(), Class::Object(env)?).cast()
+}
+
+/// Convert a nullable Object argument into `T`
+pub fn from_nullable_arg<'a, T: FromJava<'a>>(
+    env: &'a JNIEnv<'a>,
+    obj: jobject,
+) -> Result<Option<T>> {
+    from_nullable(env, obj.into())
+}
+
+/// Convert a return value into the Object returned to Java
+pub fn into_return<'a, T: IntoJava<'a>>(env: &'a JNIEnv<'a>, value: T) -> Result<jobject> {
+    Ok(value.into_java(env)?.inner.into_inner())
+}
+
+/// Get the Class of a derived type by its Java name, e.g `com.acme.Order`, like [Class::for_name] so the default ClassLoader is honoured.
+/// Only classes found with `FindClass` are cached, the default ClassLoader may be replaced at any time
+pub fn class<'a>(env: &'a JNIEnv<'a>, name: &str) -> Result<Class<'a>> {
+    let name = name.replace('.', "/");
+    match ClassLoader::get_default(env)? {
+        Some(loader) => Class::for_name_in(env, &name, &loader),
+        None => Cache::class(env, &name),
+    }
+}
+
+/// Construct an instance of a class by its binary name for generated bindings. The class is resolved like [class]
+pub fn construct<'a>(
+    env: &'a JNIEnv<'a>,
+    class: &str,
+    sig: &str,
+    args: &[JValue],
+) -> Result<Object<'a>> {
+    let class = self::class(env, class)?;
+    let (id, _) = method_id(env, &class, "<init>", sig, false)?;
+    let object = env
+        .new_object_unchecked(class.class, JMethodID::from(id as jmethodID), args)
+        .catch(env)?;
+    Ok(Object::new(env, object, class))
+}
+
+/// Call an instance method of a class by its binary name for generated bindings. The class is resolved like [class]
+pub fn call_method<'a, O: Into<JObject<'a>>>(
+    env: &'a JNIEnv<'a>,
+    obj: O,
+    class: &str,
+    name: &str,
+    sig: &str,
+    args: &[JValue],
+) -> Result<JValue<'a>> {
+    let loader = match ClassLoader::get_default(env)? {
+        Some(loader) => loader,
+        None => return Cache::call_method(env, obj, class, name, sig, args),
+    };
+
+    let class = Class::for_name_in(env, class, &loader)?;
+    let result = method_id(env, &class, name, sig, false).and_then(|(id, ret)| {
+        env.call_method_unchecked(obj, JMethodID::from(id as jmethodID), ret, args)
+            .catch(env)
+    });
+    env.delete_local_ref(class.class.into()).catch(env)?;
+    result
+}
+
+/// Call a static method of a class by its binary name for generated bindings. The class is resolved like [class]
+pub fn call_static_method<'a>(
+    env: &'a JNIEnv<'a>,
+    class: &str,
+    name: &str,
+    sig: &str,
+    args: &[JValue],
+) -> Result<JValue<'a>> {
+    let loader = match ClassLoader::get_default(env)? {
+        Some(loader) => loader,
+        None => return Cache::call_static_method(env, class, name, sig, args),
+    };
+
+    let class = Class::for_name_in(env, class, &loader)?;
+    let result = method_id(env, &class, name, sig, true).and_then(|(id, ret)| {
+        let id = JStaticMethodID::from(id as jmethodID);
+        env.call_static_method_unchecked(class.class, id, ret, args)
+            .catch(env)
+    });
+    env.delete_local_ref(class.class.into()).catch(env)?;
+    result
+}
+
+/// Get the ID and return type of a method of `class`, cached by the identity of the Class
+fn method_id<'a>(
+    env: &'a JNIEnv<'a>,
+    class: &Class<'a>,
+    name: &str,
+    sig: &str,
+    is_static: bool,
+) -> Result<(usize, SignatureType)> {
+    let key = match is_static {
+        true => format!("static method id {}{}", name, sig),
+        false => format!("method id {}{}", name, sig),
+    };
+    let member = Cache::by_class(env, class, &key, || {
+        let id = match is_static {
+            true => env
+                .get_static_method_id(class.class, name, sig)
+                .map(|id| id.into_inner()),
+            false => env
+                .get_method_id(class.class, name, sig)
+                .map(|id| id.into_inner()),
+        };
+        Ok((id.catch(env)? as usize, TypeSignature::from_str(sig)?.ret))
+    })?;
+    Ok(member.as_ref().clone())
+}
+
+/// Check that `object` is an instance of `class`, before its members are read by a derived `FromJava`
+pub fn check_class<'a>(object: &Object<'a>, class: Class<'a>) -> Result<()> {
+    if !object.instance_of_class(&class)? {
+        return Err(Error::class_mismatch(&class, &object.class));
+    }
+    Ok(())
+}
+
+/// Create an instance of `class` through the constructor taking `args`, e.g the no-arg constructor of a POJO or the canonical constructor of a record
+pub fn new_object<'a>(
+    env: &'a JNIEnv<'a>,
+    class: Class<'a>,
+    args: Vec<Argument<'a>>,
+) -> Result<Object<'a>> {
+    let signature = Signature::new(
+        args.iter().map(|arg| arg.descriptor.clone()).collect(),
+        TypeDescriptor::Void,
+    );
+    let values = args
+        .iter()
+        .map(|arg| into_value(arg.object.clone(), &arg.descriptor))
+        .collect::<Result<Vec<_>>>()?;
+
+    let id = env
+        .get_method_id(class.class, "<init>", signature.to_string())
+        .catch(env)?;
+    let object = env
+        .new_object_unchecked(class.class, id, &values)
+        .catch(env)?;
+    Ok(Object::new(env, object, class))
+}
+
+/// Read a field for a derived `FromJava`
+pub fn get_field<'a, T: FromJava<'a>>(object: &Object<'a>, name: &str) -> Result<Option<T>> {
+    object.get_field(name)
+}
+
+/// Write a field for a derived `IntoJava`
+pub fn set_field<'a, T: IntoJava<'a>>(object: &Object<'a>, name: &str, value: T) -> Result<()> {
+    object.set_field(name, value)
+}
+
+/// Call a getter, or the accessor of a record component, for a derived `FromJava`.
+/// The method is resolved by its name, so its return type may differ from `T`, e.g a boxed `Double` read into `f64`
+pub fn call_getter<'a, T: FromJava<'a>>(object: &Object<'a>, name: &str) -> Result<Option<T>> {
+    object.call_resolved(name, ())
+}
+
+/// Call a setter for a derived `IntoJava`, resolving the overload taking `value`
+pub fn call_setter<'a, T: IntoJava<'a>>(object: &Object<'a>, name: &str, value: T) -> Result<()> {
+    object.call_resolved::<(), _>(name, (value,)).map(|_| ())
+}
+
+/// Unwrap the value of a member which is not an `Option` in Rust, reporting `null` as an [Error::NullMember]
+pub fn non_null<'a, T>(value: Option<T>, class: &Class<'a>, member: &str) -> Result<T> {
+    match value {
+        Some(value) => Ok(value),
+        None => Err(Error::NullMember {
+            class: class.get_name()?,
+            member: member.to_string(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::abstractions::List;
+    use crate::cache::Cache;
+    use crate::error::Error;
+    use crate::object::Object;
+    use crate::signature::{Signature, TypeDescriptor};
+    use crate::test::{test_class, JVM};
+    use crate::{signature, FromJava, IntoJava, JavaException, JavaType, ThrowableError};
+    use jni::objects::{JObject, JValue};
+    use jni::JNIEnv;
+    use std::ffi::c_void;
+
+    #[crate::export(class = "ejni.test.NativeTest")]
+    fn add(_: &JNIEnv<'_>, a: i32, b: i32) -> i32 {
+        a + b
+    }
+
+    #[crate::export(class = "ejni.test.NativeTest", name = "add", overloaded)]
+    fn add_long(_: &JNIEnv<'_>, a: i64, b: i64) -> i64 {
+        a + b
+    }
+
+    #[crate::export(class = "ejni.test.NativeTest")]
+    fn greet<'a>(env: &'a JNIEnv<'a>, this: Object<'a>, name: String) -> crate::Result<String> {
+        let prefix = Cache::call_method(
+            env,
+            this.inner,
+            "ejni/test/NativeTest",
+            "getPrefix",
+            signature!("()Ljava/lang/String;"),
+            &[],
+        )?
+        .l()?;
+        let prefix: String = env.get_string(prefix.into())?.into();
+        Ok(format!("{} {}", prefix, name))
+    }
+
+    #[crate::export(class = "ejni.test.NativeTest")]
+    fn size(_: &JNIEnv<'_>, list: List<'_, String>) -> crate::Result<i32> {
+        list.size()
+    }
+
+    #[crate::export(class = "ejni.test.NativeTest", name = "orDefault")]
+    fn or_default(_: &JNIEnv<'_>, value: Option<String>) -> Option<String> {
+        Some(value.unwrap_or_else(|| "default".to_string()))
+    }
+
+    fn register(env: &JNIEnv<'_>) {
+        let string = TypeDescriptor::object("java/lang/String");
+        test_class(env, "ejni/test/NativeTest")
+            .natives()
+            .method(
+                "add",
+                Signature::parse("(II)I").unwrap(),
+                Java_ejni_test_NativeTest_add as *mut c_void,
+            )
+            .method(
+                "add",
+                Signature::parse("(JJ)J").unwrap(),
+                Java_ejni_test_NativeTest_add__JJ as *mut c_void,
+            )
+            .method(
+                "greet",
+                Signature::new(vec![string.clone()], string.clone()),
+                Java_ejni_test_NativeTest_greet as *mut c_void,
+            )
+            .method(
+                "size",
+                Signature::parse("(Ljava/util/List;)I").unwrap(),
+                Java_ejni_test_NativeTest_size as *mut c_void,
+            )
+            .method(
+                "orDefault",
+                Signature::new(vec![string.clone()], string),
+                Java_ejni_test_NativeTest_orDefault as *mut c_void,
+            )
+            .register()
+            .unwrap();
+    }
+
+    #[test]
+    fn primitives() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        register(&env);
+        let class = test_class(&env, "ejni/test/NativeTest");
+
+        let sum = env
+            .call_static_method(
+                class.clone(),
+                "add",
+                "(II)I",
+                &[JValue::Int(2), JValue::Int(3)],
+            )
+            .unwrap();
+        assert_eq!(5, sum.i().unwrap());
+
+        let sum = env
+            .call_static_method(
+                class,
+                "add",
+                "(JJ)J",
+                &[JValue::Long(1 << 40), JValue::Long(1)],
+            )
+            .unwrap();
+        assert_eq!((1 << 40) + 1, sum.j().unwrap());
+    }
+
+    #[test]
+    fn objects() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        register(&env);
+        let class = test_class(&env, "ejni/test/NativeTest");
+
+        let prefix = Object::new_string(&env, "Hello").unwrap();
+        let object = env
+            .new_object(class.clone(), "(Ljava/lang/String;)V", &[prefix.into()])
+            .unwrap();
+        let name = Object::new_string(&env, "World").unwrap();
+        let greeting = env
+            .call_method(
+                object,
+                "greet",
+                "(Ljava/lang/String;)Ljava/lang/String;",
+                &[name.into()],
+            )
+            .unwrap()
+            .l()
+            .unwrap();
+        let greeting: String = env.get_string(greeting.into()).unwrap().into();
+        assert_eq!("Hello World", greeting);
+
+        let list = List::<String>::arraylist(&env).unwrap();
+        list.add("Foo".to_string()).unwrap();
+        let size = env
+            .call_static_method(
+                class.clone(),
+                "size",
+                "(Ljava/util/List;)I",
+                &[list.inner.clone().into()],
+            )
+            .unwrap();
+        assert_eq!(1, size.i().unwrap());
+
+        let value = env
+            .call_static_method(
+                class,
+                "orDefault",
+                "(Ljava/lang/String;)Ljava/lang/String;",
+                &[JObject::null().into()],
+            )
+            .unwrap()
+            .l()
+            .unwrap();
+        let value: String = env.get_string(value.into()).unwrap().into();
+        assert_eq!("default", value);
+    }
+
+    #[test]
+    fn null_argument() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        register(&env);
+        let class = test_class(&env, "ejni/test/NativeTest");
+
+        let result = env.call_static_method(
+            class,
+            "size",
+            "(Ljava/util/List;)I",
+            &[JObject::null().into()],
+        );
+        let exception = match result.map_err(Error::from) {
+            Err(Error::Jni(jni::errors::Error::JavaException)) => {
+                JavaException::catch(&env).unwrap().unwrap()
+            }
+            _ => panic!("Expected a Java exception"),
+        };
+        assert_eq!("java.lang.NullPointerException", exception.class);
+    }
+
+    #[derive(Debug, PartialEq, JavaType, IntoJava, FromJava)]
+    #[ejni(class = "ejni.test.Order")]
+    struct Order {
+        #[ejni(field = "orderId")]
+        id: i64,
+        #[ejni(getter = "getCustomer")]
+        customer: Option<String>,
+        #[ejni(getter = "isPaid", setter = "markPaid")]
+        paid: bool,
+        quantity: Option<i32>,
+    }
+
+    #[derive(Debug, PartialEq, JavaType, IntoJava, FromJava)]
+    #[ejni(class = "ejni/test/OrderLine", record)]
+    struct OrderLine {
+        product: String,
+        amount: i32,
+        #[ejni(field = "price")]
+        unit_price: Option<f64>,
+    }
+
+    #[test]
+    fn derive_pojo() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        test_class(&env, "ejni/test/Order");
+
+        let order = Order {
+            id: 42,
+            customer: Some("ACME".to_string()),
+            paid: true,
+            quantity: None,
+        };
+        let object = order.into_java(&env).unwrap();
+        assert_eq!("ejni.test.Order", object.class.get_name().unwrap());
+        assert_eq!(Some(42i64), object.get_field("orderId").unwrap());
+        assert_eq!(
+            Some("ACME".to_string()),
+            object.call::<String, _>("getCustomer", ()).unwrap()
+        );
+
+        object.set_field("quantity", Some(3)).unwrap();
+        let order = Order::from_java(object).unwrap();
+        assert_eq!(
+            Order {
+                id: 42,
+                customer: Some("ACME".to_string()),
+                paid: true,
+                quantity: Some(3),
+            },
+            order
+        );
+    }
+
+    #[test]
+    fn derive_record() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        test_class(&env, "ejni/test/OrderLine");
+
+        let line = OrderLine {
+            product: "Widget".to_string(),
+            amount: 3,
+            unit_price: Some(2.5),
+        };
+        let object = line.into_java(&env).unwrap();
+        assert!(object
+            .instance_of_class(&crate::Class::Record(&env).unwrap())
+            .unwrap());
+        assert_eq!(
+            Some("OrderLine[product=Widget, amount=3, price=2.5]".to_string()),
+            object.call::<String, _>("toString", ()).unwrap()
+        );
+
+        let line = OrderLine::from_java(object).unwrap();
+        assert_eq!(
+            OrderLine {
+                product: "Widget".to_string(),
+                amount: 3,
+                unit_price: Some(2.5),
+            },
+            line
+        );
+    }
+
+    #[test]
+    fn derive_errors() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        test_class(&env, "ejni/test/OrderLine");
+
+        let string = Object::new_string(&env, "Widget").unwrap();
+        match OrderLine::from_java(string) {
+            Err(Error::ClassMismatch { expected, found }) => {
+                assert_eq!("ejni.test.OrderLine", expected);
+                assert_eq!("java.lang.String", found);
+            }
+            _ => panic!("Expected a ClassMismatch"),
+        }
+
+        // The product is null
+        let object = crate::private::new_object(
+            &env,
+            OrderLine::class(&env).unwrap(),
+            vec![
+                crate::Argument::new(&env, None::<String>).unwrap(),
+                crate::Argument::new(&env, 1).unwrap(),
+                crate::Argument::new(&env, None::<f64>).unwrap(),
+            ],
+        )
+        .unwrap();
+        match OrderLine::from_java(object) {
+            Err(e @ Error::NullMember { .. }) => {
+                assert_eq!("product of ejni.test.OrderLine is null", e.to_string());
+                assert_eq!("java/lang/NullPointerException", e.exception_class());
+            }
+            _ => panic!("Expected a NullMember"),
+        }
+    }
+
+    #[test]
+    fn bindings() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        test_class(&env, "ejni/test/Order");
+
+        let order = crate::test::bindings::Order::create(&env).unwrap();
+        order.set_customer("Alice").unwrap();
+        order.mark_paid(true).unwrap();
+        assert_eq!(Some("Alice".to_string()), order.get_customer().unwrap());
+        assert!(order.is_paid().unwrap());
+    }
+}
@@ -0,0 +1,848 @@
+use crate::abstractions::JavaString;
+use crate::cache::Cache;
+use crate::class::Class;
+use crate::error::{Error, JavaException, JniResultExt, Result};
+use crate::java_type::{FromJava, JavaType};
+use crate::object::Object;
+use crate::signature;
+use crate::signature::{Signature, TypeDescriptor};
+use jni::objects::{GlobalRef, JClass, JObject, JValue};
+use jni::sys::{_jobject, jobjectArray};
+use jni::JNIEnv;
+use std::fmt::{Display, Formatter};
+
+/// The modifiers of a Class or member, as returned by `getModifiers()`.
+/// The flags have the values of the constants in `java.lang.reflect.Modifier`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Modifiers(pub i32);
+
+impl Modifiers {
+    /// `java.lang.reflect.Modifier#PUBLIC`
+    pub const PUBLIC: i32 = 0x0001;
+    /// `java.lang.reflect.Modifier#PRIVATE`
+    pub const PRIVATE: i32 = 0x0002;
+    /// `java.lang.reflect.Modifier#PROTECTED`
+    pub const PROTECTED: i32 = 0x0004;
+    /// `java.lang.reflect.Modifier#STATIC`
+    pub const STATIC: i32 = 0x0008;
+    /// `java.lang.reflect.Modifier#FINAL`
+    pub const FINAL: i32 = 0x0010;
+    /// `java.lang.reflect.Modifier#SYNCHRONIZED`
+    pub const SYNCHRONIZED: i32 = 0x0020;
+    /// `java.lang.reflect.Modifier#VOLATILE`
+    pub const VOLATILE: i32 = 0x0040;
+    /// `java.lang.reflect.Modifier#TRANSIENT`
+    pub const TRANSIENT: i32 = 0x0080;
+    /// `java.lang.reflect.Modifier#NATIVE`
+    pub const NATIVE: i32 = 0x0100;
+    /// `java.lang.reflect.Modifier#INTERFACE`
+    pub const INTERFACE: i32 = 0x0200;
+    /// `java.lang.reflect.Modifier#ABSTRACT`
+    pub const ABSTRACT: i32 = 0x0400;
+    /// `java.lang.reflect.Modifier#STRICT`
+    pub const STRICT: i32 = 0x0800;
+
+    /// Returns true if all bits of `flag` are set
+    pub fn contains(&self, flag: i32) -> bool {
+        self.0 & flag == flag
+    }
+
+    /// Returns true if the `public` modifier is set
+    pub fn is_public(&self) -> bool {
+        self.contains(Self::PUBLIC)
+    }
+
+    /// Returns true if the `private` modifier is set
+    pub fn is_private(&self) -> bool {
+        self.contains(Self::PRIVATE)
+    }
+
+    /// Returns true if the `protected` modifier is set
+    pub fn is_protected(&self) -> bool {
+        self.contains(Self::PROTECTED)
+    }
+
+    /// Returns true if the `static` modifier is set
+    pub fn is_static(&self) -> bool {
+        self.contains(Self::STATIC)
+    }
+
+    /// Returns true if the `final` modifier is set
+    pub fn is_final(&self) -> bool {
+        self.contains(Self::FINAL)
+    }
+
+    /// Returns true if the `synchronized` modifier is set
+    pub fn is_synchronized(&self) -> bool {
+        self.contains(Self::SYNCHRONIZED)
+    }
+
+    /// Returns true if the `volatile` modifier is set
+    pub fn is_volatile(&self) -> bool {
+        self.contains(Self::VOLATILE)
+    }
+
+    /// Returns true if the `transient` modifier is set
+    pub fn is_transient(&self) -> bool {
+        self.contains(Self::TRANSIENT)
+    }
+
+    /// Returns true if the `native` modifier is set
+    pub fn is_native(&self) -> bool {
+        self.contains(Self::NATIVE)
+    }
+
+    /// Returns true if the `interface` modifier is set
+    pub fn is_interface(&self) -> bool {
+        self.contains(Self::INTERFACE)
+    }
+
+    /// Returns true if the `abstract` modifier is set
+    pub fn is_abstract(&self) -> bool {
+        self.contains(Self::ABSTRACT)
+    }
+
+    /// Returns true if the `strictfp` modifier is set
+    pub fn is_strict(&self) -> bool {
+        self.contains(Self::STRICT)
+    }
+}
+
+impl Display for Modifiers {
+    /// Formats the modifiers like `Modifier#toString(int)`, e.g `public static final`
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // The canonical order of the Java Language Specification, as used by Modifier#toString(int)
+        let names = [
+            (Self::PUBLIC, "public"),
+            (Self::PROTECTED, "protected"),
+            (Self::PRIVATE, "private"),
+            (Self::ABSTRACT, "abstract"),
+            (Self::STATIC, "static"),
+            (Self::FINAL, "final"),
+            (Self::TRANSIENT, "transient"),
+            (Self::VOLATILE, "volatile"),
+            (Self::SYNCHRONIZED, "synchronized"),
+            (Self::NATIVE, "native"),
+            (Self::STRICT, "strictfp"),
+            (Self::INTERFACE, "interface"),
+        ];
+
+        let names = names
+            .iter()
+            .filter(|(flag, _)| self.contains(*flag))
+            .map(|(_, name)| *name)
+            .collect::<Vec<_>>();
+        write!(f, "{}", names.join(" "))
+    }
+}
+
+/// Wrapper around `java.lang.annotation.Annotation`
+pub struct Annotation<'a> {
+    /// The Annotation itself
+    pub inner: Object<'a>,
+    env: &'a JNIEnv<'a>,
+}
+
+#[allow(clippy::from_over_into)]
+impl<'a> Into<*mut _jobject> for Annotation<'a> {
+    fn into(self) -> *mut _jobject {
+        // The local reference is handed to the caller, so it must not be deleted by Drop
+        let this = std::mem::ManuallyDrop::new(self);
+        this.inner.inner.into_inner()
+    }
+}
+
+impl<'a> Drop for Annotation<'a> {
+    fn drop(&mut self) {
+        let _ = self.env.delete_local_ref(self.inner.inner);
+    }
+}
+
+impl<'a> Annotation<'a> {
+    /// Create a new Annotation wrapper. The caller must guarantee that the passed in Object implements Annotation and is not null
+    pub fn new(env: &'a JNIEnv<'a>, object: Object<'a>) -> Self {
+        Self { inner: object, env }
+    }
+
+    /// Returns the annotation interface of this annotation, e.g `java.lang.Deprecated`
+    pub fn annotation_type(&self) -> Result<Class<'a>> {
+        let class = Cache::call_method(
+            self.env,
+            self.inner.inner,
+            "java/lang/annotation/Annotation",
+            "annotationType",
+            signature!("()Ljava/lang/Class;"),
+            &[],
+        )?
+        .l()?;
+        Ok(Class::new(self.env, JClass::from(class)))
+    }
+
+    /// Get the value of the element `name` of this annotation, e.g `since` of `@Deprecated(since = "9")`
+    pub fn value<T: FromJava<'a>>(&self, name: &str) -> Result<Option<T>> {
+        self.inner.call::<T, _>(name, ())
+    }
+}
+
+/// Implements the wrapper boilerplate, and the accessors shared by all members through `java.lang.reflect.Member` and `AccessibleObject`
+macro_rules! impl_member {
+    ($name:ident, $class:ident, $java:literal) => {
+        #[allow(clippy::from_over_into)]
+        impl<'a> Into<*mut _jobject> for $name<'a> {
+            fn into(self) -> *mut _jobject {
+                // The local reference is handed to the caller, so it must not be deleted by Drop
+                let this = std::mem::ManuallyDrop::new(self);
+                this.inner.inner.into_inner()
+            }
+        }
+
+        impl<'a> Drop for $name<'a> {
+            fn drop(&mut self) {
+                let _ = self.env.delete_local_ref(self.inner.inner);
+            }
+        }
+
+        impl<'a> JavaType<'a> for $name<'a> {
+            fn class(env: &'a JNIEnv<'a>) -> Result<Class<'a>> {
+                Class::$class(env)
+            }
+        }
+
+        impl<'a> FromJava<'a> for $name<'a> {
+            fn from_java(object: Object<'a>) -> Result<Self> {
+                Ok(Self::new(object.env, object))
+            }
+        }
+
+        impl<'a> $name<'a> {
+            #[doc = concat!("Create a new ", stringify!($name), " wrapper. The caller must guarantee that the passed in Object is a `", $java, "` and is not null")]
+            pub fn new(env: &'a JNIEnv<'a>, object: Object<'a>) -> Self {
+                Self { inner: object, env }
+            }
+
+            /// Returns the simple name of the member. For a Constructor this is the name of the declaring Class
+            pub fn name(&self) -> Result<String> {
+                let name = self.call("getName", signature!("()Ljava/lang/String;"))?.l()?;
+                string(self.env, name)
+            }
+
+            /// Returns the Class which declares the member
+            pub fn declaring_class(&self) -> Result<Class<'a>> {
+                let class = self
+                    .call("getDeclaringClass", signature!("()Ljava/lang/Class;"))?
+                    .l()?;
+                Ok(Class::new(self.env, JClass::from(class)))
+            }
+
+            /// Returns the modifiers of the member
+            pub fn modifiers(&self) -> Result<Modifiers> {
+                Ok(Modifiers(self.call("getModifiers", signature!("()I"))?.i()?))
+            }
+
+            /// Returns the annotations present on the member. Only annotations with runtime retention are visible
+            pub fn annotations(&self) -> Result<Vec<Annotation<'a>>> {
+                let annotations = self
+                    .call(
+                        "getAnnotations",
+                        signature!("()[Ljava/lang/annotation/Annotation;"),
+                    )?
+                    .l()?;
+                elements(self.env, annotations)?
+                    .into_iter()
+                    .map(|annotation| {
+                        let class = Class::new(self.env, self.env.get_object_class(annotation).catch(self.env)?);
+                        Ok(Annotation::new(self.env, Object::new(self.env, annotation, class)))
+                    })
+                    .collect()
+            }
+
+            /// Returns the annotation of type `class`, if it is present on the member
+            pub fn annotation(&self, class: &Class<'a>) -> Result<Option<Annotation<'a>>> {
+                for annotation in self.annotations()? {
+                    let annotation_type = annotation.annotation_type()?;
+                    let is_type = self.env.is_same_object(annotation_type.class, class.class);
+                    self.env.delete_local_ref(annotation_type.class.into())?;
+                    if is_type? {
+                        return Ok(Some(annotation));
+                    }
+                }
+                Ok(None)
+            }
+
+            /// Suppress the Java language access checks, so non-public members can be used. Invokes `AccessibleObject#setAccessible(boolean)`
+            pub fn set_accessible(&self, accessible: bool) -> Result<()> {
+                Cache::call_method(
+                    self.env,
+                    self.inner.inner,
+                    "java/lang/reflect/AccessibleObject",
+                    "setAccessible",
+                    signature!("(Z)V"),
+                    &[JValue::Bool(accessible as u8)],
+                )?;
+                Ok(())
+            }
+
+            /// Call a method of the reflection object without arguments
+            fn call(&self, name: &str, sig: &str) -> Result<JValue<'a>> {
+                Cache::call_method(self.env, self.inner.inner, $java, name, sig, &[])
+            }
+        }
+    };
+}
+
+/// Wrapper around `java.lang.reflect.Method`
+pub struct Method<'a> {
+    /// The Method itself
+    pub inner: Object<'a>,
+    env: &'a JNIEnv<'a>,
+}
+
+impl_member!(Method, Method, "java/lang/reflect/Method");
+
+impl<'a> Method<'a> {
+    /// Returns the types of the parameters, in declaration order
+    pub fn parameter_types(&self) -> Result<Vec<Class<'a>>> {
+        let types = self
+            .call("getParameterTypes", signature!("()[Ljava/lang/Class;"))?
+            .l()?;
+        classes(self.env, types)
+    }
+
+    /// Returns the return type. This is [Class::void] for `void` methods
+    pub fn return_type(&self) -> Result<Class<'a>> {
+        let class = self
+            .call("getReturnType", signature!("()Ljava/lang/Class;"))?
+            .l()?;
+        Ok(Class::new(self.env, JClass::from(class)))
+    }
+
+    /// Returns the JNI signature of the method, e.g `(ILjava/lang/Object;)V`
+    pub fn signature(&self) -> Result<Signature> {
+        let params = self
+            .parameter_types()?
+            .into_iter()
+            .map(|class| descriptor(self.env, class))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Signature::new(
+            params,
+            descriptor(self.env, self.return_type()?)?,
+        ))
+    }
+
+    /// Invoke the method on `receiver`, which must be None for static methods. Invokes `Method#invoke(Object, Object...)`.
+    ///
+    /// Arguments for primitive parameters must be boxed, e.g with [Object::new_integer_object].
+    /// Primitive return values are boxed, and None is returned for `void` methods or a `null` return value.
+    /// If the method throws, the exception is returned rather than the `InvocationTargetException` wrapping it
+    pub fn invoke(
+        &self,
+        receiver: Option<&Object<'a>>,
+        args: &[Object<'a>],
+    ) -> Result<Option<Object<'a>>> {
+        let receiver = receiver.map(|r| r.inner).unwrap_or_else(JObject::null);
+        let args = object_array(self.env, args)?;
+        let value = Cache::call_method(
+            self.env,
+            self.inner.inner,
+            "java/lang/reflect/Method",
+            "invoke",
+            signature!("(Ljava/lang/Object;[Ljava/lang/Object;)Ljava/lang/Object;"),
+            &[receiver.into(), JObject::from(args).into()],
+        );
+        self.env.delete_local_ref(args.into()).catch(self.env)?;
+        nullable(
+            self.env,
+            value.map_err(|e| invocation_target(self.env, e))?.l()?,
+        )
+    }
+}
+
+/// Wrapper around `java.lang.reflect.Field`
+pub struct Field<'a> {
+    /// The Field itself
+    pub inner: Object<'a>,
+    env: &'a JNIEnv<'a>,
+}
+
+impl_member!(Field, Field, "java/lang/reflect/Field");
+
+impl<'a> Field<'a> {
+    /// Returns the declared type of the field
+    pub fn field_type(&self) -> Result<Class<'a>> {
+        let class = self
+            .call("getType", signature!("()Ljava/lang/Class;"))?
+            .l()?;
+        Ok(Class::new(self.env, JClass::from(class)))
+    }
+
+    /// Returns the JNI type descriptor of the field, e.g `I` or `Ljava/lang/String;`
+    pub fn descriptor(&self) -> Result<TypeDescriptor> {
+        self.field_type()?.descriptor()
+    }
+
+    /// Get the value of the field of `receiver`, which must be None for static fields. Invokes `Field#get(Object)`.
+    /// Primitive values are boxed. Returns None if the value is `null`
+    pub fn get(&self, receiver: Option<&Object<'a>>) -> Result<Option<Object<'a>>> {
+        let receiver = receiver.map(|r| r.inner).unwrap_or_else(JObject::null);
+        let value = Cache::call_method(
+            self.env,
+            self.inner.inner,
+            "java/lang/reflect/Field",
+            "get",
+            signature!("(Ljava/lang/Object;)Ljava/lang/Object;"),
+            &[receiver.into()],
+        )?
+        .l()?;
+        nullable(self.env, value)
+    }
+
+    /// Set the value of the field of `receiver`, which must be None for static fields. Invokes `Field#set(Object, Object)`.
+    /// Values for primitive fields must be boxed
+    pub fn set(&self, receiver: Option<&Object<'a>>, value: &Object<'a>) -> Result<()> {
+        let receiver = receiver.map(|r| r.inner).unwrap_or_else(JObject::null);
+        Cache::call_method(
+            self.env,
+            self.inner.inner,
+            "java/lang/reflect/Field",
+            "set",
+            signature!("(Ljava/lang/Object;Ljava/lang/Object;)V"),
+            &[receiver.into(), value.into()],
+        )?;
+        Ok(())
+    }
+}
+
+/// Wrapper around `java.lang.reflect.Constructor`
+pub struct Constructor<'a> {
+    /// The Constructor itself
+    pub inner: Object<'a>,
+    env: &'a JNIEnv<'a>,
+}
+
+impl_member!(Constructor, Constructor, "java/lang/reflect/Constructor");
+
+impl<'a> Constructor<'a> {
+    /// Returns the types of the parameters, in declaration order
+    pub fn parameter_types(&self) -> Result<Vec<Class<'a>>> {
+        let types = self
+            .call("getParameterTypes", signature!("()[Ljava/lang/Class;"))?
+            .l()?;
+        classes(self.env, types)
+    }
+
+    /// Returns the JNI signature of the constructor, e.g `(I)V`
+    pub fn signature(&self) -> Result<Signature> {
+        let params = self
+            .parameter_types()?
+            .into_iter()
+            .map(|class| descriptor(self.env, class))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Signature::new(params, TypeDescriptor::Void))
+    }
+
+    /// Create a new instance of the declaring Class. Invokes `Constructor#newInstance(Object...)`.
+    ///
+    /// Arguments for primitive parameters must be boxed.
+    /// If the constructor throws, the exception is returned rather than the `InvocationTargetException` wrapping it
+    pub fn new_instance(&self, args: &[Object<'a>]) -> Result<Object<'a>> {
+        let args = object_array(self.env, args)?;
+        let value = Cache::call_method(
+            self.env,
+            self.inner.inner,
+            "java/lang/reflect/Constructor",
+            "newInstance",
+            signature!("([Ljava/lang/Object;)Ljava/lang/Object;"),
+            &[JObject::from(args).into()],
+        );
+        self.env.delete_local_ref(args.into()).catch(self.env)?;
+
+        let object = value.map_err(|e| invocation_target(self.env, e))?.l()?;
+        let class = Class::new(self.env, self.env.get_object_class(object).catch(self.env)?);
+        Ok(Object::new(self.env, object, class))
+    }
+}
+
+/// Get the members returned by one of the reflection methods of `java.lang.Class`, e.g `getMethods`
+pub(crate) fn members<'a, T: JavaType<'a>>(
+    env: &'a JNIEnv<'a>,
+    class: &Class<'a>,
+    method: &str,
+    sig: &str,
+    wrap: fn(&'a JNIEnv<'a>, Object<'a>) -> T,
+) -> Result<Vec<T>> {
+    let array = Cache::call_method(env, class.class, "java/lang/Class", method, sig, &[])?.l()?;
+    let member_class = T::class(env)?;
+    Ok(elements(env, array)?
+        .into_iter()
+        .map(|member| wrap(env, Object::new(env, member, member_class.clone())))
+        .collect())
+}
+
+/// Get the elements of an Object array, and delete the local reference to the array
+fn elements<'a>(env: &'a JNIEnv<'a>, array: JObject<'a>) -> Result<Vec<JObject<'a>>> {
+    let len = env.get_array_length(array.into_inner()).catch(env)?;
+    // Every element is returned as a new local reference
+    env.ensure_local_capacity(len).catch(env)?;
+    let elements = (0..len)
+        .map(|i| {
+            env.get_object_array_element(array.into_inner(), i)
+                .catch(env)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    env.delete_local_ref(array).catch(env)?;
+    Ok(elements)
+}
+
+/// Get the Classes in a `Class[]`
+fn classes<'a>(env: &'a JNIEnv<'a>, array: JObject<'a>) -> Result<Vec<Class<'a>>> {
+    Ok(elements(env, array)?
+        .into_iter()
+        .map(|class| Class::new(env, JClass::from(class)))
+        .collect())
+}
+
+/// Create an `Object[]` holding `objects`
+fn object_array<'a>(env: &'a JNIEnv<'a>, objects: &[Object<'a>]) -> Result<jobjectArray> {
+    let array = env
+        .new_object_array(objects.len() as i32, Class::Object(env)?, JObject::null())
+        .catch(env)?;
+    for (i, object) in objects.iter().enumerate() {
+        env.set_object_array_element(array, i as i32, object.inner)
+            .catch(env)?;
+    }
+    Ok(array)
+}
+
+/// Get the descriptor of `class`, and delete the local reference to it
+fn descriptor<'a>(env: &'a JNIEnv<'a>, class: Class<'a>) -> Result<TypeDescriptor> {
+    let descriptor = class.descriptor()?;
+    env.delete_local_ref(class.class.into()).catch(env)?;
+    Ok(descriptor)
+}
+
+/// Wrap a JObject with its runtime Class. Returns None if it is null
+fn nullable<'a>(env: &'a JNIEnv<'a>, object: JObject<'a>) -> Result<Option<Object<'a>>> {
+    if object.is_null() {
+        return Ok(None);
+    }
+
+    let class = Class::new(env, env.get_object_class(object).catch(env)?);
+    Ok(Some(Object::new(env, object, class)))
+}
+
+/// Convert a `java.lang.String` into a Rust String, and delete the local reference to it
+fn string<'a>(env: &'a JNIEnv<'a>, string: JObject<'a>) -> Result<String> {
+    let rust = JavaString::new(env, Object::new(env, string, Class::String(env)?)).into_rust()?;
+    env.delete_local_ref(string).catch(env)?;
+    Ok(rust)
+}
+
+/// Replace an `InvocationTargetException` with the exception thrown by the invoked method.
+/// The target is captured with its Throwable, so it can be rethrown as is
+fn invocation_target(env: &JNIEnv<'_>, e: Error) -> Error {
+    match e {
+        Error::JavaException(e) if e.class == "java.lang.reflect.InvocationTargetException" => {
+            let target = e
+                .throwable
+                .as_ref()
+                .map(|throwable| target_exception(env, throwable));
+            match target {
+                Some(Ok(Some(target))) => return Error::JavaException(target),
+                Some(Err(err)) => return err,
+                _ => {}
+            }
+            match e.cause {
+                Some(cause) => Error::JavaException(*cause),
+                None => Error::JavaException(e),
+            }
+        }
+        e => e,
+    }
+}
+
+/// Capture the cause of a Throwable, including the cause itself. Returns None if it has no cause
+fn target_exception(env: &JNIEnv<'_>, throwable: &GlobalRef) -> Result<Option<JavaException>> {
+    let cause = env
+        .call_method(
+            throwable.as_obj(),
+            "getCause",
+            signature!("()Ljava/lang/Throwable;"),
+            &[],
+        )
+        .catch(env)?
+        .l()?;
+    if cause.is_null() {
+        return Ok(None);
+    }
+
+    let target = JavaException::from_throwable(env, cause.into());
+    env.delete_local_ref(cause).catch(env)?;
+    target.map(Some)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::JVM;
+    use crate::MapEntry;
+
+    #[test]
+    fn modifiers() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let modifier = Class::for_name(&env, "java.lang.reflect.Modifier").unwrap();
+        for (name, flag) in [
+            ("PUBLIC", Modifiers::PUBLIC),
+            ("PRIVATE", Modifiers::PRIVATE),
+            ("STATIC", Modifiers::STATIC),
+            ("FINAL", Modifiers::FINAL),
+            ("VOLATILE", Modifiers::VOLATILE),
+            ("NATIVE", Modifiers::NATIVE),
+            ("ABSTRACT", Modifiers::ABSTRACT),
+        ] {
+            let value = modifier.get_static_field::<i32>(name).unwrap();
+            assert_eq!(Some(flag), value, "{}", name);
+        }
+
+        let modifiers = Modifiers(Modifiers::FINAL | Modifiers::STATIC | Modifiers::PUBLIC);
+        assert!(modifiers.is_public() && modifiers.is_static() && modifiers.is_final());
+        assert!(!modifiers.is_private());
+        assert_eq!("public static final", modifiers.to_string());
+
+        let class = Class::Integer(&env).unwrap().modifiers().unwrap();
+        assert!(class.is_public() && class.is_final());
+    }
+
+    #[test]
+    fn declared_methods() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let methods = Class::for_name(&env, "java.lang.Thread")
+            .unwrap()
+            .declared_methods()
+            .unwrap();
+        let suspend = methods
+            .iter()
+            .find(|m| m.name().unwrap() == "suspend")
+            .unwrap();
+
+        assert_eq!("()V", suspend.signature().unwrap().to_string());
+        assert_eq!(
+            "java.lang.Thread",
+            suspend.declaring_class().unwrap().get_name().unwrap()
+        );
+        let modifiers = suspend.modifiers().unwrap();
+        assert!(modifiers.is_public() && modifiers.is_final() && !modifiers.is_static());
+
+        let deprecated = Class::for_name(&env, "java.lang.Deprecated").unwrap();
+        let annotation = suspend.annotation(&deprecated).unwrap().unwrap();
+        assert_eq!(
+            "java.lang.Deprecated",
+            annotation.annotation_type().unwrap().get_name().unwrap()
+        );
+        assert_eq!(
+            Some("1.2".to_string()),
+            annotation.value::<String>("since").unwrap()
+        );
+        assert_eq!(Some(true), annotation.value::<bool>("forRemoval").unwrap());
+    }
+
+    #[test]
+    fn methods() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let methods = Class::Integer(&env).unwrap().methods().unwrap();
+        let parse_int = methods
+            .iter()
+            .find(|m| m.name().unwrap() == "parseInt" && m.parameter_types().unwrap().len() == 1)
+            .unwrap();
+
+        assert!(parse_int.modifiers().unwrap().is_static());
+        assert_eq!(
+            "java.lang.String",
+            parse_int.parameter_types().unwrap()[0].get_name().unwrap()
+        );
+        assert_eq!("int", parse_int.return_type().unwrap().get_name().unwrap());
+        assert!(parse_int.annotations().unwrap().is_empty());
+        // Inherited from java.lang.Object
+        assert!(methods.iter().any(|m| m.name().unwrap() == "wait"));
+    }
+
+    #[test]
+    fn invoke() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let string = Class::String(&env).unwrap();
+        let to_upper_case = string
+            .methods()
+            .unwrap()
+            .into_iter()
+            .find(|m| {
+                m.name().unwrap() == "toUpperCase"
+                    && m.signature().unwrap().to_string() == "()Ljava/lang/String;"
+            })
+            .unwrap();
+        let foo = Object::new_string(&env, "foo").unwrap();
+        let upper = to_upper_case.invoke(Some(&foo), &[]).unwrap().unwrap();
+        assert_eq!("FOO", JavaString::new(&env, upper).into_rust().unwrap());
+
+        let parse_int = Class::Integer(&env)
+            .unwrap()
+            .methods()
+            .unwrap()
+            .into_iter()
+            .find(|m| {
+                m.name().unwrap() == "parseInt"
+                    && m.signature().unwrap().to_string() == "(Ljava/lang/String;)I"
+            })
+            .unwrap();
+        let value = parse_int
+            .invoke(None, &[Object::new_string(&env, "42").unwrap()])
+            .unwrap()
+            .unwrap();
+        assert_eq!(42, value.get_integer().unwrap());
+
+        let err = parse_int
+            .invoke(None, &[Object::new_string(&env, "foo").unwrap()])
+            .map(|_| ())
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::JavaException(e) if e.class == "java.lang.NumberFormatException")
+        );
+    }
+
+    #[test]
+    fn invoke_rethrow() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let parse_int = Class::Integer(&env)
+            .unwrap()
+            .methods()
+            .unwrap()
+            .into_iter()
+            .find(|m| {
+                m.name().unwrap() == "parseInt"
+                    && m.signature().unwrap().to_string() == "(Ljava/lang/String;)I"
+            })
+            .unwrap();
+        let err = parse_int
+            .invoke(None, &[Object::new_string(&env, "foo").unwrap()])
+            .map(|_| ())
+            .unwrap_err();
+        let throwable = match &err {
+            Error::JavaException(e) => e.throwable.clone().unwrap(),
+            e => panic!("Expected a Java exception, got {}", e),
+        };
+
+        let _: () = crate::throw_on_err(&env, || Err(err));
+        let rethrown = env.exception_occurred().unwrap();
+        env.exception_clear().unwrap();
+        assert!(env.is_same_object(rethrown, throwable.as_obj()).unwrap());
+
+        // The original exception, thrown by Integer#parseInt rather than created by throw_on_err
+        let exception = JavaException::from_throwable(&env, rethrown).unwrap();
+        assert_eq!("java.lang.NumberFormatException", exception.class);
+        assert!(exception.stack_trace.iter().any(|e| e.method == "parseInt"));
+    }
+
+    #[test]
+    fn fields() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let fields = Class::Integer(&env).unwrap().fields().unwrap();
+        let max_value = fields
+            .iter()
+            .find(|f| f.name().unwrap() == "MAX_VALUE")
+            .unwrap();
+        assert_eq!(TypeDescriptor::Int, max_value.descriptor().unwrap());
+        assert_eq!(
+            "public static final",
+            max_value.modifiers().unwrap().to_string()
+        );
+        let value = max_value.get(None).unwrap().unwrap();
+        assert_eq!(i32::MAX, value.get_integer().unwrap());
+
+        // Final fields cannot be set
+        let err = max_value
+            .set(None, &Object::new_integer_object(&env, 0).unwrap())
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::JavaException(e) if e.class == "java.lang.IllegalAccessException")
+        );
+
+        let declared = Class::for_name(&env, "java.util.AbstractMap$SimpleEntry")
+            .unwrap()
+            .declared_fields()
+            .unwrap();
+        let key = declared
+            .iter()
+            .find(|f| f.name().unwrap() == "key")
+            .unwrap();
+        assert!(key.modifiers().unwrap().is_private());
+    }
+
+    #[test]
+    fn field_set() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let point_class = Class::for_name(&env, "java.awt.Point").unwrap();
+        let point = point_class
+            .constructors()
+            .unwrap()
+            .into_iter()
+            .find(|c| c.parameter_types().unwrap().is_empty())
+            .unwrap()
+            .new_instance(&[])
+            .unwrap();
+        let x = point_class
+            .fields()
+            .unwrap()
+            .into_iter()
+            .find(|f| f.name().unwrap() == "x")
+            .unwrap();
+
+        x.set(Some(&point), &Object::new_integer_object(&env, 10).unwrap())
+            .unwrap();
+        let value = x.get(Some(&point)).unwrap().unwrap();
+        assert_eq!(10, value.get_integer().unwrap());
+    }
+
+    #[test]
+    fn constructors() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let class = Class::for_name(&env, "java.util.AbstractMap$SimpleEntry").unwrap();
+        let constructors = class.constructors().unwrap();
+        assert_eq!(2, constructors.len());
+        let constructor = constructors
+            .iter()
+            .find(|c| c.parameter_types().unwrap().len() == 2)
+            .unwrap();
+        assert_eq!(
+            "(Ljava/lang/Object;Ljava/lang/Object;)V",
+            constructor.signature().unwrap().to_string()
+        );
+        assert_eq!(
+            "java.util.AbstractMap$SimpleEntry",
+            constructor.name().unwrap()
+        );
+
+        let entry = constructor
+            .new_instance(&[
+                Object::new_string(&env, "Foo").unwrap(),
+                Object::new_integer_object(&env, 10).unwrap(),
+            ])
+            .unwrap();
+        let entry = MapEntry::<String, i32>::new(&env, entry);
+        assert_eq!(Some("Foo".to_string()), entry.get_key().unwrap());
+        assert_eq!(Some(10), entry.get_value().unwrap());
+    }
+}
@@ -0,0 +1,260 @@
+use crate::class::Class;
+use crate::error::{Error, JniResultExt, Result};
+use crate::signature::Signature;
+use jni::JNIEnv;
+use jni::NativeMethod;
+use std::ffi::c_void;
+
+/// A native method added to a [NativeRegistry]
+struct Registration {
+    name: String,
+    signature: Signature,
+    fn_ptr: *mut c_void,
+}
+
+/// Builder binding Rust functions to the native methods of a Class through `RegisterNatives`, see [Class::natives].
+///
+/// Unlike exported `Java_<class>_<method>` symbols, registered functions are not linked by name,
+/// so renaming the Java package or Class only requires updating the Class lookup.
+/// Every method is validated against the methods declared by the Class before anything is registered,
+/// a registration which does not match a declared native method fails instead of being silently unlinked.
+///
+/// Functions receive the JNIEnv, then the Class for static methods or the receiver for instance methods, followed by the parameters:
+/// ```ignore
+/// extern "system" fn add(_: JNIEnv<'_>, _: JClass<'_>, a: jint, b: jint) -> jint {
+///     a + b
+/// }
+///
+/// #[no_mangle]
+/// pub extern "system" fn JNI_OnLoad(vm: JavaVM, _: *mut c_void) -> jint {
+///     let env = vm.get_env().unwrap();
+///     Class::for_name(&env, "com/example/Calculator")
+///         .unwrap()
+///         .natives()
+///         .method(
+///             "add",
+///             Signature::builder()
+///                 .param(TypeDescriptor::Int)
+///                 .param(TypeDescriptor::Int)
+///                 .returns(TypeDescriptor::Int),
+///             add as *mut c_void,
+///         )
+///         .register()
+///         .unwrap();
+///
+///     JNI_VERSION_1_8
+/// }
+/// ```
+pub struct NativeRegistry<'a> {
+    env: &'a JNIEnv<'a>,
+    class: Class<'a>,
+    methods: Vec<Registration>,
+}
+
+impl<'a> NativeRegistry<'a> {
+    /// Create an empty registry for the native methods of `class`
+    pub fn new(env: &'a JNIEnv<'a>, class: Class<'a>) -> Self {
+        Self {
+            env,
+            class,
+            methods: Vec::new(),
+        }
+    }
+
+    /// Bind `fn_ptr` to the native method `name` with the signature `signature`.
+    /// `fn_ptr` must point to an `extern "system"` function matching the signature, as described on [NativeRegistry]
+    pub fn method<S: AsRef<str>>(
+        mut self,
+        name: S,
+        signature: Signature,
+        fn_ptr: *mut c_void,
+    ) -> Self {
+        self.methods.push(Registration {
+            name: name.as_ref().to_string(),
+            signature,
+            fn_ptr,
+        });
+        self
+    }
+
+    /// Validate all methods against the methods declared by the Class, and register them with `RegisterNatives`.
+    /// Nothing is registered if any of the methods is invalid.
+    ///
+    /// Fails with [Error::NotDeclared] if the Class does not declare a method with the name and signature,
+    /// and with [Error::NotNative] if the declared method is not `native`
+    pub fn register(self) -> Result<()> {
+        self.validate()?;
+
+        let methods: Vec<_> = self
+            .methods
+            .iter()
+            .map(|method| NativeMethod {
+                name: method.name.as_str().into(),
+                sig: method.signature.to_string().into(),
+                fn_ptr: method.fn_ptr,
+            })
+            .collect();
+        self.env
+            .register_native_methods(self.class.clone(), &methods)
+            .catch(self.env)?;
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<()> {
+        let declared = self.class.declared_methods()?;
+        let mut resolved = Vec::with_capacity(declared.len());
+        for method in &declared {
+            resolved.push((
+                method.name()?,
+                method.signature()?,
+                method.modifiers()?.is_native(),
+            ));
+        }
+
+        for method in &self.methods {
+            let found = resolved.iter().find(|(name, signature, _)| {
+                name == &method.name && signature == &method.signature
+            });
+            match found {
+                Some((_, _, true)) => {}
+                Some((_, _, false)) => {
+                    return Err(Error::NotNative {
+                        class: self.class.get_name()?,
+                        name: method.name.clone(),
+                        signature: method.signature.clone(),
+                    })
+                }
+                None => {
+                    return Err(Error::NotDeclared {
+                        class: self.class.get_name()?,
+                        name: method.name.clone(),
+                        signature: method.signature.clone(),
+                    })
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::signature::TypeDescriptor;
+    use crate::test::{test_class, JVM};
+    use crate::Object;
+    use jni::objects::{JClass, JObject, JString, JValue};
+    use jni::sys::{jint, jstring};
+
+    extern "system" fn add(_: JNIEnv<'_>, _: JClass<'_>, a: jint, b: jint) -> jint {
+        a + b
+    }
+
+    extern "system" fn greet(env: JNIEnv<'_>, this: JObject<'_>, name: JString<'_>) -> jstring {
+        let prefix: String = env
+            .get_string(
+                env.call_method(this, "getPrefix", "()Ljava/lang/String;", &[])
+                    .unwrap()
+                    .l()
+                    .unwrap()
+                    .into(),
+            )
+            .unwrap()
+            .into();
+        let name: String = env.get_string(name).unwrap().into();
+        env.new_string(format!("{} {}", prefix, name))
+            .unwrap()
+            .into_inner()
+    }
+
+    fn int_signature(params: usize) -> Signature {
+        (0..params)
+            .fold(Signature::builder(), |sig, _| {
+                sig.param(TypeDescriptor::Int)
+            })
+            .returns(TypeDescriptor::Int)
+    }
+
+    #[test]
+    fn register() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        let class = test_class(&env, "ejni/test/NativeTest");
+
+        let string = TypeDescriptor::object("java/lang/String");
+        class
+            .natives()
+            .method("add", int_signature(2), add as *mut c_void)
+            .method(
+                "greet",
+                Signature::new(vec![string.clone()], string),
+                greet as *mut c_void,
+            )
+            .register()
+            .unwrap();
+
+        let sum = env
+            .call_static_method(
+                class.clone(),
+                "add",
+                "(II)I",
+                &[JValue::Int(2), JValue::Int(3)],
+            )
+            .unwrap();
+        assert_eq!(5, sum.i().unwrap());
+
+        let prefix = Object::new_string(&env, "Hello").unwrap();
+        let object = env
+            .new_object(class, "(Ljava/lang/String;)V", &[prefix.into()])
+            .unwrap();
+        let name = Object::new_string(&env, "World").unwrap();
+        let greeting = env
+            .call_method(
+                object,
+                "greet",
+                "(Ljava/lang/String;)Ljava/lang/String;",
+                &[name.into()],
+            )
+            .unwrap()
+            .l()
+            .unwrap();
+        let greeting: String = env.get_string(greeting.into()).unwrap().into();
+        assert_eq!("Hello World", greeting);
+    }
+
+    #[test]
+    fn no_such_method() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        let class = test_class(&env, "ejni/test/NativeTest");
+
+        // The signature does not match the declared `add(II)I`
+        let err = class
+            .natives()
+            .method("add", int_signature(3), add as *mut c_void)
+            .register()
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::NotDeclared { ref class, ref name, .. } if class == "ejni.test.NativeTest" && name == "add")
+        );
+        assert_eq!(
+            "No method `add(III)I` in ejni.test.NativeTest",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn not_native() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        let class = test_class(&env, "ejni/test/NativeTest");
+
+        let err = class
+            .natives()
+            .method("notNative", int_signature(1), add as *mut c_void)
+            .register()
+            .unwrap_err();
+        assert!(matches!(err, Error::NotNative { ref name, .. } if name == "notNative"));
+    }
+}
@@ -0,0 +1,900 @@
+use crate::cache::Cache;
+use crate::class::Class;
+use crate::error::{Error, JavaException, JniResultExt, Result};
+use crate::native::{ThrowableError, RUNTIME_EXCEPTION};
+use crate::object::Object;
+use crate::signature;
+use jni::objects::{GlobalRef, JObject, JValue};
+use jni::JNIEnv;
+use serde::ser::{self, Serialize};
+use std::convert::TryFrom;
+use std::fmt::Display;
+use thiserror::Error;
+
+/// Describes the errors that can occur when serializing a Rust value into Java objects
+#[derive(Debug, Error)]
+pub enum SerializeError {
+    /// JNI Error
+    #[error("JNI Error: {0}")]
+    Jni(#[from] jni::errors::Error),
+    /// A Java exception was thrown
+    #[error("Java exception: {0}")]
+    JavaException(#[from] JavaException),
+    /// A map key could not be converted into a `java.lang.String`
+    #[error("Map keys must be strings, characters, integers or unit variants")]
+    KeyMustBeString,
+    /// Error raised by the `Serialize` implementation of the value
+    #[error("{0}")]
+    Custom(String),
+    /// Any other eJNI error, e.g a value out of range of its Java type
+    #[error(transparent)]
+    Ejni(Error),
+}
+
+impl From<Error> for SerializeError {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::Jni(e) => Self::Jni(e),
+            Error::JavaException(e) => Self::JavaException(e),
+            e => Self::Ejni(e),
+        }
+    }
+}
+
+impl ser::Error for SerializeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
+
+impl ThrowableError for SerializeError {
+    fn exception_class(&self) -> String {
+        match self {
+            Self::JavaException(e) => e.class.replace('.', "/"),
+            Self::KeyMustBeString => "java/lang/IllegalArgumentException".to_string(),
+            Self::Ejni(e) => e.exception_class(),
+            _ => RUNTIME_EXCEPTION.to_string(),
+        }
+    }
+
+    fn exception_message(&self) -> String {
+        match self {
+            Self::JavaException(e) => e.message.clone().unwrap_or_default(),
+            Self::Ejni(e) => e.exception_message(),
+            _ => self.to_string(),
+        }
+    }
+
+    fn throwable(&self) -> Option<&GlobalRef> {
+        match self {
+            Self::JavaException(e) => e.throwable.as_ref(),
+            Self::Ejni(e) => e.throwable(),
+            _ => None,
+        }
+    }
+}
+
+/// Result returned from [to_java] and [Serializer]
+pub type SerializeResult<T> = std::result::Result<T, SerializeError>;
+
+/// Serialize `value` into Java objects, see [Serializer]
+///
+/// ```ignore
+/// #[derive(Serialize)]
+/// struct Config {
+///     name: String,
+///     threads: i32,
+///     tags: Vec<String>,
+/// }
+///
+/// // A LinkedHashMap of {name=..., threads=..., tags=[...]}
+/// let config = ejni::to_java(&env, &config)?;
+/// ```
+pub fn to_java<'a, T: Serialize + ?Sized>(
+    env: &'a JNIEnv<'a>,
+    value: &T,
+) -> SerializeResult<Object<'a>> {
+    value.serialize(Serializer::new(env))
+}
+
+/// A `serde::Serializer` building Java objects from Rust values. `null` is returned as an Object whose reference is null.
+///
+/// | Rust                                           | Java                                   |
+/// |------------------------------------------------|----------------------------------------|
+/// | `bool`                                         | `java.lang.Boolean`                    |
+/// | `i8`, `i16`, `i32`, `i64`                      | `Byte`, `Short`, `Integer`, `Long`     |
+/// | `u8`, `u16`, `u32`                             | `Short`, `Integer`, `Long`, which fit all values |
+/// | `u64`                                          | `Long`, or `java.math.BigInteger` if it exceeds `Long.MAX_VALUE` |
+/// | `i128`, `u128`                                 | `java.math.BigInteger`                 |
+/// | `f32`, `f64`                                   | `Float`, `Double`                      |
+/// | `char`                                         | `Character`, or `String` outside of the Basic Multilingual Plane |
+/// | `str`, unit variants                           | `java.lang.String`                     |
+/// | bytes, e.g `serde_bytes`                       | `byte[]`                               |
+/// | `None`, `()`, unit structs                     | `null`                                 |
+/// | sequences and tuples                           | `java.util.ArrayList`                  |
+/// | structs and maps                               | `java.util.LinkedHashMap<String, Object>`, map keys are converted to Strings |
+/// | other enum variants                            | a `LinkedHashMap` of the variant name to its content |
+#[derive(Clone, Copy)]
+pub struct Serializer<'a> {
+    env: &'a JNIEnv<'a>,
+}
+
+impl<'a> Serializer<'a> {
+    /// Create a Serializer creating objects with `env`
+    pub fn new(env: &'a JNIEnv<'a>) -> Self {
+        Self { env }
+    }
+
+    fn null(self) -> SerializeResult<Object<'a>> {
+        Ok(Object::new(
+            self.env,
+            JObject::null(),
+            Class::Object(self.env)?,
+        ))
+    }
+
+    fn big_integer<T: Display>(self, v: T) -> SerializeResult<Object<'a>> {
+        let digits = Object::new_string(self.env, v.to_string())?;
+        let object = Cache::new_object(
+            self.env,
+            "java/math/BigInteger",
+            signature!("(Ljava/lang/String;)V"),
+            &[(&digits).into()],
+        )?;
+        self.env.delete_local_ref(digits.inner)?;
+        Ok(Object::new(self.env, object, Class::BigInteger(self.env)?))
+    }
+
+    /// Wrap the content of an enum variant in a map of the variant name to the content
+    fn variant(self, variant: &'static str, content: Object<'a>) -> SerializeResult<Object<'a>> {
+        let map = linked_hash_map(self.env)?;
+        put(
+            self.env,
+            &map,
+            Object::new_string(self.env, variant)?,
+            content,
+        )?;
+        Ok(map)
+    }
+}
+
+fn linked_hash_map<'a>(env: &'a JNIEnv<'a>) -> Result<Object<'a>> {
+    let map = Cache::new_object(env, "java/util/LinkedHashMap", signature!("()V"), &[])?;
+    Ok(Object::new(env, map, Class::LinkedHashMap(env)?))
+}
+
+/// Put `key` and `value` into `map`, deleting their local references afterwards
+fn put<'a>(
+    env: &'a JNIEnv<'a>,
+    map: &Object<'a>,
+    key: Object<'a>,
+    value: Object<'a>,
+) -> Result<()> {
+    let previous = Cache::call_method(
+        env,
+        map.inner,
+        "java/util/Map",
+        "put",
+        signature!("(Ljava/lang/Object;Ljava/lang/Object;)Ljava/lang/Object;"),
+        &[(&key).into(), (&value).into()],
+    )?
+    .l()?;
+    delete(env, previous)?;
+    delete(env, key.inner)?;
+    delete(env, value.inner)
+}
+
+/// Add `value` to `list`, deleting its local reference afterwards
+fn add<'a>(env: &'a JNIEnv<'a>, list: &Object<'a>, value: Object<'a>) -> Result<()> {
+    Cache::call_method(
+        env,
+        list.inner,
+        "java/util/List",
+        "add",
+        signature!("(Ljava/lang/Object;)Z"),
+        &[(&value).into()],
+    )?;
+    delete(env, value.inner)
+}
+
+fn delete<'a>(env: &'a JNIEnv<'a>, obj: JObject<'a>) -> Result<()> {
+    if !obj.is_null() {
+        env.delete_local_ref(obj)?;
+    }
+    Ok(())
+}
+
+impl<'a> ser::Serializer for Serializer<'a> {
+    type Ok = Object<'a>;
+    type Error = SerializeError;
+    type SerializeSeq = SeqSerializer<'a>;
+    type SerializeTuple = SeqSerializer<'a>;
+    type SerializeTupleStruct = SeqSerializer<'a>;
+    type SerializeTupleVariant = SeqSerializer<'a>;
+    type SerializeMap = MapSerializer<'a>;
+    type SerializeStruct = MapSerializer<'a>;
+    type SerializeStructVariant = MapSerializer<'a>;
+
+    fn serialize_bool(self, v: bool) -> SerializeResult<Object<'a>> {
+        Ok(Object::new_boolean_object(self.env, v)?)
+    }
+
+    fn serialize_i8(self, v: i8) -> SerializeResult<Object<'a>> {
+        Ok(Object::new_byte_object(self.env, v)?)
+    }
+
+    fn serialize_i16(self, v: i16) -> SerializeResult<Object<'a>> {
+        Ok(Object::new_short_object(self.env, v)?)
+    }
+
+    fn serialize_i32(self, v: i32) -> SerializeResult<Object<'a>> {
+        Ok(Object::new_integer_object(self.env, v)?)
+    }
+
+    fn serialize_i64(self, v: i64) -> SerializeResult<Object<'a>> {
+        Ok(Object::new_long_object(self.env, v)?)
+    }
+
+    fn serialize_i128(self, v: i128) -> SerializeResult<Object<'a>> {
+        self.big_integer(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> SerializeResult<Object<'a>> {
+        self.serialize_i16(v as i16)
+    }
+
+    fn serialize_u16(self, v: u16) -> SerializeResult<Object<'a>> {
+        self.serialize_i32(v as i32)
+    }
+
+    fn serialize_u32(self, v: u32) -> SerializeResult<Object<'a>> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_u64(self, v: u64) -> SerializeResult<Object<'a>> {
+        match i64::try_from(v) {
+            Ok(v) => self.serialize_i64(v),
+            Err(_) => self.big_integer(v),
+        }
+    }
+
+    fn serialize_u128(self, v: u128) -> SerializeResult<Object<'a>> {
+        self.big_integer(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> SerializeResult<Object<'a>> {
+        Ok(Object::new_float_object(self.env, v)?)
+    }
+
+    fn serialize_f64(self, v: f64) -> SerializeResult<Object<'a>> {
+        Ok(Object::new_double_object(self.env, v)?)
+    }
+
+    fn serialize_char(self, v: char) -> SerializeResult<Object<'a>> {
+        let mut units = [0; 2];
+        match v.encode_utf16(&mut units) {
+            [unit] => Ok(Object::new_character_object(self.env, *unit)?),
+            _ => self.serialize_str(v.encode_utf8(&mut [0; 4])),
+        }
+    }
+
+    fn serialize_str(self, v: &str) -> SerializeResult<Object<'a>> {
+        Ok(Object::new_string(self.env, v)?)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> SerializeResult<Object<'a>> {
+        let array = self.env.byte_array_from_slice(v).catch(self.env)?;
+        Ok(Object::new(
+            self.env,
+            array.into(),
+            Cache::class(self.env, "[B")?,
+        ))
+    }
+
+    fn serialize_none(self) -> SerializeResult<Object<'a>> {
+        self.null()
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> SerializeResult<Object<'a>> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> SerializeResult<Object<'a>> {
+        self.null()
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> SerializeResult<Object<'a>> {
+        self.null()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> SerializeResult<Object<'a>> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> SerializeResult<Object<'a>> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> SerializeResult<Object<'a>> {
+        let content = value.serialize(self)?;
+        self.variant(variant, content)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> SerializeResult<SeqSerializer<'a>> {
+        SeqSerializer::new(self, len, None)
+    }
+
+    fn serialize_tuple(self, len: usize) -> SerializeResult<SeqSerializer<'a>> {
+        SeqSerializer::new(self, Some(len), None)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        len: usize,
+    ) -> SerializeResult<SeqSerializer<'a>> {
+        SeqSerializer::new(self, Some(len), None)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> SerializeResult<SeqSerializer<'a>> {
+        SeqSerializer::new(self, Some(len), Some(variant))
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> SerializeResult<MapSerializer<'a>> {
+        MapSerializer::new(self, None)
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> SerializeResult<MapSerializer<'a>> {
+        MapSerializer::new(self, None)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: usize,
+    ) -> SerializeResult<MapSerializer<'a>> {
+        MapSerializer::new(self, Some(variant))
+    }
+}
+
+/// Serializes sequences, tuples and tuple variants into a `java.util.ArrayList`
+pub struct SeqSerializer<'a> {
+    serializer: Serializer<'a>,
+    list: Object<'a>,
+    variant: Option<&'static str>,
+}
+
+impl<'a> SeqSerializer<'a> {
+    fn new(
+        serializer: Serializer<'a>,
+        len: Option<usize>,
+        variant: Option<&'static str>,
+    ) -> SerializeResult<Self> {
+        let env = serializer.env;
+        let capacity = len.and_then(|len| i32::try_from(len).ok()).unwrap_or(10);
+        let list = Cache::new_object(
+            env,
+            "java/util/ArrayList",
+            signature!("(I)V"),
+            &[JValue::Int(capacity)],
+        )?;
+        Ok(Self {
+            serializer,
+            list: Object::new(env, list, Class::ArrayList(env)?),
+            variant,
+        })
+    }
+
+    fn element<T: Serialize + ?Sized>(&mut self, value: &T) -> SerializeResult<()> {
+        let value = value.serialize(self.serializer)?;
+        Ok(add(self.serializer.env, &self.list, value)?)
+    }
+
+    fn finish(self) -> SerializeResult<Object<'a>> {
+        match self.variant {
+            Some(variant) => self.serializer.variant(variant, self.list),
+            None => Ok(self.list),
+        }
+    }
+}
+
+impl<'a> ser::SerializeSeq for SeqSerializer<'a> {
+    type Ok = Object<'a>;
+    type Error = SerializeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> SerializeResult<()> {
+        self.element(value)
+    }
+
+    fn end(self) -> SerializeResult<Object<'a>> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeTuple for SeqSerializer<'a> {
+    type Ok = Object<'a>;
+    type Error = SerializeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> SerializeResult<()> {
+        self.element(value)
+    }
+
+    fn end(self) -> SerializeResult<Object<'a>> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeTupleStruct for SeqSerializer<'a> {
+    type Ok = Object<'a>;
+    type Error = SerializeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> SerializeResult<()> {
+        self.element(value)
+    }
+
+    fn end(self) -> SerializeResult<Object<'a>> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeTupleVariant for SeqSerializer<'a> {
+    type Ok = Object<'a>;
+    type Error = SerializeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> SerializeResult<()> {
+        self.element(value)
+    }
+
+    fn end(self) -> SerializeResult<Object<'a>> {
+        self.finish()
+    }
+}
+
+/// Serializes maps, structs and struct variants into a `java.util.LinkedHashMap<String, Object>`
+pub struct MapSerializer<'a> {
+    serializer: Serializer<'a>,
+    map: Object<'a>,
+    key: Option<Object<'a>>,
+    variant: Option<&'static str>,
+}
+
+impl<'a> MapSerializer<'a> {
+    fn new(serializer: Serializer<'a>, variant: Option<&'static str>) -> SerializeResult<Self> {
+        Ok(Self {
+            serializer,
+            map: linked_hash_map(serializer.env)?,
+            key: None,
+            variant,
+        })
+    }
+
+    fn entry<T: Serialize + ?Sized>(&mut self, key: Object<'a>, value: &T) -> SerializeResult<()> {
+        let value = value.serialize(self.serializer)?;
+        Ok(put(self.serializer.env, &self.map, key, value)?)
+    }
+
+    fn finish(self) -> SerializeResult<Object<'a>> {
+        match self.variant {
+            Some(variant) => self.serializer.variant(variant, self.map),
+            None => Ok(self.map),
+        }
+    }
+}
+
+impl<'a> ser::SerializeMap for MapSerializer<'a> {
+    type Ok = Object<'a>;
+    type Error = SerializeError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> SerializeResult<()> {
+        self.key = Some(key.serialize(KeySerializer(self.serializer))?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> SerializeResult<()> {
+        let key = self.key.take().ok_or_else(|| {
+            SerializeError::Custom("serialize_value called before serialize_key".to_string())
+        })?;
+        self.entry(key, value)
+    }
+
+    fn end(self) -> SerializeResult<Object<'a>> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeStruct for MapSerializer<'a> {
+    type Ok = Object<'a>;
+    type Error = SerializeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> SerializeResult<()> {
+        let key = Object::new_string(self.serializer.env, key)?;
+        self.entry(key, value)
+    }
+
+    fn end(self) -> SerializeResult<Object<'a>> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeStructVariant for MapSerializer<'a> {
+    type Ok = Object<'a>;
+    type Error = SerializeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> SerializeResult<()> {
+        let key = Object::new_string(self.serializer.env, key)?;
+        self.entry(key, value)
+    }
+
+    fn end(self) -> SerializeResult<Object<'a>> {
+        self.finish()
+    }
+}
+
+/// Serializes map keys into a `java.lang.String`
+struct KeySerializer<'a>(Serializer<'a>);
+
+impl<'a> KeySerializer<'a> {
+    fn string<T: Display>(self, v: T) -> SerializeResult<Object<'a>> {
+        Ok(Object::new_string(self.0.env, v.to_string())?)
+    }
+}
+
+impl<'a> ser::Serializer for KeySerializer<'a> {
+    type Ok = Object<'a>;
+    type Error = SerializeError;
+    type SerializeSeq = ser::Impossible<Object<'a>, SerializeError>;
+    type SerializeTuple = ser::Impossible<Object<'a>, SerializeError>;
+    type SerializeTupleStruct = ser::Impossible<Object<'a>, SerializeError>;
+    type SerializeTupleVariant = ser::Impossible<Object<'a>, SerializeError>;
+    type SerializeMap = ser::Impossible<Object<'a>, SerializeError>;
+    type SerializeStruct = ser::Impossible<Object<'a>, SerializeError>;
+    type SerializeStructVariant = ser::Impossible<Object<'a>, SerializeError>;
+
+    fn serialize_bool(self, _: bool) -> SerializeResult<Object<'a>> {
+        Err(SerializeError::KeyMustBeString)
+    }
+
+    fn serialize_i8(self, v: i8) -> SerializeResult<Object<'a>> {
+        self.string(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> SerializeResult<Object<'a>> {
+        self.string(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> SerializeResult<Object<'a>> {
+        self.string(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> SerializeResult<Object<'a>> {
+        self.string(v)
+    }
+
+    fn serialize_i128(self, v: i128) -> SerializeResult<Object<'a>> {
+        self.string(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> SerializeResult<Object<'a>> {
+        self.string(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> SerializeResult<Object<'a>> {
+        self.string(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> SerializeResult<Object<'a>> {
+        self.string(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> SerializeResult<Object<'a>> {
+        self.string(v)
+    }
+
+    fn serialize_u128(self, v: u128) -> SerializeResult<Object<'a>> {
+        self.string(v)
+    }
+
+    fn serialize_f32(self, _: f32) -> SerializeResult<Object<'a>> {
+        Err(SerializeError::KeyMustBeString)
+    }
+
+    fn serialize_f64(self, _: f64) -> SerializeResult<Object<'a>> {
+        Err(SerializeError::KeyMustBeString)
+    }
+
+    fn serialize_char(self, v: char) -> SerializeResult<Object<'a>> {
+        self.string(v)
+    }
+
+    fn serialize_str(self, v: &str) -> SerializeResult<Object<'a>> {
+        self.string(v)
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> SerializeResult<Object<'a>> {
+        Err(SerializeError::KeyMustBeString)
+    }
+
+    fn serialize_none(self) -> SerializeResult<Object<'a>> {
+        Err(SerializeError::KeyMustBeString)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, _: &T) -> SerializeResult<Object<'a>> {
+        Err(SerializeError::KeyMustBeString)
+    }
+
+    fn serialize_unit(self) -> SerializeResult<Object<'a>> {
+        Err(SerializeError::KeyMustBeString)
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> SerializeResult<Object<'a>> {
+        Err(SerializeError::KeyMustBeString)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> SerializeResult<Object<'a>> {
+        self.string(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> SerializeResult<Object<'a>> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> SerializeResult<Object<'a>> {
+        Err(SerializeError::KeyMustBeString)
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> SerializeResult<Self::SerializeSeq> {
+        Err(SerializeError::KeyMustBeString)
+    }
+
+    fn serialize_tuple(self, _: usize) -> SerializeResult<Self::SerializeTuple> {
+        Err(SerializeError::KeyMustBeString)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> SerializeResult<Self::SerializeTupleStruct> {
+        Err(SerializeError::KeyMustBeString)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> SerializeResult<Self::SerializeTupleVariant> {
+        Err(SerializeError::KeyMustBeString)
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> SerializeResult<Self::SerializeMap> {
+        Err(SerializeError::KeyMustBeString)
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> SerializeResult<Self::SerializeStruct> {
+        Err(SerializeError::KeyMustBeString)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> SerializeResult<Self::SerializeStructVariant> {
+        Err(SerializeError::KeyMustBeString)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::JVM;
+    use crate::{List, Map};
+    use serde::ser::Serializer as _;
+    use serde::Serialize;
+    use std::collections::BTreeMap;
+
+    fn java_string(object: &Object<'_>) -> String {
+        object.call("toString", ()).unwrap().unwrap()
+    }
+
+    #[derive(Serialize)]
+    struct Config {
+        name: String,
+        threads: u16,
+        tags: Vec<String>,
+        parent: Option<String>,
+        ratio: f64,
+    }
+
+    #[derive(Serialize)]
+    enum Shape {
+        Empty,
+        Circle(i32),
+        Rect { width: i32, height: i32 },
+        Line(i32, i32),
+    }
+
+    #[test]
+    fn primitives() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let object = to_java(&env, &5i32).unwrap();
+        assert_eq!("java.lang.Integer", object.class.get_name().unwrap());
+        assert_eq!(5, object.get_integer().unwrap());
+
+        let object = to_java(&env, &200u8).unwrap();
+        assert_eq!("java.lang.Short", object.class.get_name().unwrap());
+        assert_eq!(200, object.get_short().unwrap());
+
+        let object = to_java(&env, &u64::MAX).unwrap();
+        assert_eq!("java.math.BigInteger", object.class.get_name().unwrap());
+        assert_eq!(u64::MAX.to_string(), java_string(&object));
+
+        let object = to_java(&env, &true).unwrap();
+        assert!(object.get_boolean().unwrap());
+
+        let object = to_java(&env, &'é').unwrap();
+        assert_eq!("java.lang.Character", object.class.get_name().unwrap());
+        let object = to_java(&env, &'😀').unwrap();
+        assert_eq!("😀", java_string(&object));
+
+        let object = to_java(&env, "Hello").unwrap();
+        assert_eq!("Hello", java_string(&object));
+
+        let object = to_java(&env, &None::<i32>).unwrap();
+        assert!(object.inner.is_null());
+    }
+
+    #[test]
+    fn bytes() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let object = Serializer::new(&env).serialize_bytes(&[1, 2, 255]).unwrap();
+        assert_eq!("[B", object.class.get_name().unwrap());
+        let bytes = env.convert_byte_array(object.inner.into_inner()).unwrap();
+        assert_eq!(vec![1, 2, 255], bytes);
+    }
+
+    #[test]
+    fn structs() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let config = Config {
+            name: "ejni".to_string(),
+            threads: 4,
+            tags: vec!["a".to_string(), "b".to_string()],
+            parent: None,
+            ratio: 0.5,
+        };
+        let object = to_java(&env, &config).unwrap();
+        assert_eq!("java.util.LinkedHashMap", object.class.get_name().unwrap());
+        assert_eq!(
+            "{name=ejni, threads=4, tags=[a, b], parent=null, ratio=0.5}",
+            java_string(&object)
+        );
+
+        let map = Map::<String, Object>::new(&env, object);
+        let tags = map.get("tags".to_string()).unwrap().unwrap();
+        assert_eq!("java.util.ArrayList", tags.class.get_name().unwrap());
+        let tags = List::<String>::new(&env, tags);
+        assert_eq!(vec!["a", "b"], tags.to_vec().unwrap());
+    }
+
+    #[test]
+    fn enums() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let shapes = vec![
+            Shape::Empty,
+            Shape::Circle(2),
+            Shape::Rect {
+                width: 3,
+                height: 4,
+            },
+            Shape::Line(5, 6),
+        ];
+        let object = to_java(&env, &shapes).unwrap();
+        assert_eq!(
+            "[Empty, {Circle=2}, {Rect={width=3, height=4}}, {Line=[5, 6]}]",
+            java_string(&object)
+        );
+    }
+
+    #[test]
+    fn map_keys() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        let mut map = BTreeMap::new();
+        map.insert(1, true);
+        map.insert(2, false);
+        let object = to_java(&env, &map).unwrap();
+        let map = Map::<String, bool>::new(&env, object);
+        assert_eq!(Some(true), map.get("1".to_string()).unwrap());
+        assert_eq!(Some(false), map.get("2".to_string()).unwrap());
+
+        let mut map = BTreeMap::new();
+        map.insert((1, 2), 3);
+        let err = to_java(&env, &map).map(|_| ()).unwrap_err();
+        assert!(matches!(err, SerializeError::KeyMustBeString));
+    }
+
+    #[test]
+    fn long_sequence() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        // Elements are deleted once they are added, so this does not exhaust the local reference capacity
+        let values: Vec<String> = (0..1000).map(|i| i.to_string()).collect();
+        let object = to_java(&env, &values).unwrap();
+        let list = List::<String>::new(&env, object);
+        assert_eq!(1000, list.size().unwrap());
+        assert_eq!(Some("999".to_string()), list.get(999).unwrap());
+    }
+
+    #[test]
+    fn ejni_error() {
+        let err = SerializeError::from(Error::Conversion {
+            from: "usize",
+            to: "int",
+        });
+        assert!(matches!(
+            err,
+            SerializeError::Ejni(Error::Conversion { .. })
+        ));
+        assert_eq!("java/lang/IllegalArgumentException", err.exception_class());
+    }
+}
@@ -0,0 +1,490 @@
+use crate::class::Class;
+use crate::error::Result;
+use jni::signature::{JavaType as SignatureType, Primitive};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Describes the errors that can occur when parsing a type descriptor or method signature
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SignatureError {
+    /// The type descriptor is not valid
+    #[error("Invalid type descriptor `{0}`")]
+    InvalidDescriptor(String),
+    /// The method signature is not valid
+    #[error("Invalid method signature `{0}`")]
+    InvalidSignature(String),
+}
+
+/// A JNI type descriptor, e.g `I`, `Ljava/lang/String;` or `[J`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TypeDescriptor {
+    /// `boolean`, `Z`
+    Boolean,
+    /// `byte`, `B`
+    Byte,
+    /// `char`, `C`
+    Char,
+    /// `short`, `S`
+    Short,
+    /// `int`, `I`
+    Int,
+    /// `long`, `J`
+    Long,
+    /// `float`, `F`
+    Float,
+    /// `double`, `D`
+    Double,
+    /// `void`, `V`. Only valid as the return type of a method
+    Void,
+    /// A Class, holding its binary name in internal form, e.g `java/lang/String`
+    Object(String),
+    /// An array of the component type
+    Array(Box<TypeDescriptor>),
+}
+
+impl TypeDescriptor {
+    /// A Class by its name. Can be in the format:
+    /// - `java/lang/String`
+    /// - `java.lang.String`
+    pub fn object<S: AsRef<str>>(name: S) -> Self {
+        Self::Object(name.as_ref().replace('.', "/"))
+    }
+
+    /// An array of this type. E.g `I` results in `[I`
+    pub fn array(self) -> Self {
+        Self::Array(Box::new(self))
+    }
+
+    /// The descriptor of a Class
+    pub fn from_class(class: &Class<'_>) -> Result<Self> {
+        class.descriptor()
+    }
+
+    /// Parse a descriptor, e.g `[Ljava/lang/String;`
+    pub fn parse(descriptor: &str) -> std::result::Result<Self, SignatureError> {
+        let invalid = || SignatureError::InvalidDescriptor(descriptor.to_string());
+        if descriptor == "V" {
+            return Ok(Self::Void);
+        }
+
+        match Self::parse_at(descriptor, 0) {
+            Some((ty, end)) if end == descriptor.len() => Ok(ty),
+            _ => Err(invalid()),
+        }
+    }
+
+    /// Parse a single field descriptor starting at byte `start`. Returns the descriptor and the index after it
+    fn parse_at(s: &str, start: usize) -> Option<(Self, usize)> {
+        let end = field_end(s.as_bytes(), start)?;
+        let mut dimensions = 0;
+        while s.as_bytes()[start + dimensions] == b'[' {
+            dimensions += 1;
+        }
+
+        let component = match s.as_bytes()[start + dimensions] {
+            b'Z' => Self::Boolean,
+            b'B' => Self::Byte,
+            b'C' => Self::Char,
+            b'S' => Self::Short,
+            b'I' => Self::Int,
+            b'J' => Self::Long,
+            b'F' => Self::Float,
+            b'D' => Self::Double,
+            _ => Self::Object(s[start + dimensions + 1..end - 1].to_string()),
+        };
+
+        let ty = (0..dimensions).fold(component, |ty, _| ty.array());
+        Some((ty, end))
+    }
+
+    /// Returns true for the Java primitives. `void` is not a primitive
+    pub fn is_primitive(&self) -> bool {
+        !matches!(self, Self::Void | Self::Object(_) | Self::Array(_))
+    }
+
+    /// The Class name in the form accepted by `FindClass`, e.g `java/lang/String` or `[I`. None for primitives and `void`
+    pub fn class_name(&self) -> Option<String> {
+        match self {
+            Self::Object(name) => Some(name.clone()),
+            Self::Array(_) => Some(self.to_string()),
+            _ => None,
+        }
+    }
+
+    /// The descriptor of the boxed Class of a primitive, e.g `Ljava/lang/Integer;` for `I`
+    pub fn boxed(&self) -> Option<Self> {
+        let name = match self {
+            Self::Boolean => "java/lang/Boolean",
+            Self::Byte => "java/lang/Byte",
+            Self::Char => "java/lang/Character",
+            Self::Short => "java/lang/Short",
+            Self::Int => "java/lang/Integer",
+            Self::Long => "java/lang/Long",
+            Self::Float => "java/lang/Float",
+            Self::Double => "java/lang/Double",
+            _ => return None,
+        };
+        Some(Self::Object(name.to_string()))
+    }
+
+    /// Returns true if `descriptor` is a valid type descriptor. Usable in constant expressions, see [descriptor!](crate::descriptor!)
+    pub const fn is_valid(descriptor: &str) -> bool {
+        let bytes = descriptor.as_bytes();
+        if bytes.len() == 1 && bytes[0] == b'V' {
+            return true;
+        }
+
+        matches!(field_end(bytes, 0), Some(end) if end == bytes.len())
+    }
+}
+
+impl Display for TypeDescriptor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Boolean => write!(f, "Z"),
+            Self::Byte => write!(f, "B"),
+            Self::Char => write!(f, "C"),
+            Self::Short => write!(f, "S"),
+            Self::Int => write!(f, "I"),
+            Self::Long => write!(f, "J"),
+            Self::Float => write!(f, "F"),
+            Self::Double => write!(f, "D"),
+            Self::Void => write!(f, "V"),
+            Self::Object(name) => write!(f, "L{};", name),
+            Self::Array(component) => write!(f, "[{}", component),
+        }
+    }
+}
+
+impl FromStr for TypeDescriptor {
+    type Err = SignatureError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl From<&TypeDescriptor> for SignatureType {
+    fn from(ty: &TypeDescriptor) -> Self {
+        match ty {
+            TypeDescriptor::Boolean => Self::Primitive(Primitive::Boolean),
+            TypeDescriptor::Byte => Self::Primitive(Primitive::Byte),
+            TypeDescriptor::Char => Self::Primitive(Primitive::Char),
+            TypeDescriptor::Short => Self::Primitive(Primitive::Short),
+            TypeDescriptor::Int => Self::Primitive(Primitive::Int),
+            TypeDescriptor::Long => Self::Primitive(Primitive::Long),
+            TypeDescriptor::Float => Self::Primitive(Primitive::Float),
+            TypeDescriptor::Double => Self::Primitive(Primitive::Double),
+            TypeDescriptor::Void => Self::Primitive(Primitive::Void),
+            TypeDescriptor::Object(name) => Self::Object(name.clone()),
+            TypeDescriptor::Array(component) => Self::Array(Box::new(component.as_ref().into())),
+        }
+    }
+}
+
+/// A JNI method signature, e.g `(ILjava/lang/Object;)V`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Signature {
+    /// The types of the parameters
+    pub params: Vec<TypeDescriptor>,
+    /// The return type
+    pub ret: TypeDescriptor,
+}
+
+impl Signature {
+    /// Create a Signature from it's parameters and return type
+    pub fn new(params: Vec<TypeDescriptor>, ret: TypeDescriptor) -> Self {
+        Self { params, ret }
+    }
+
+    /// Create a Signature without parameters, returning `void`. Parameters and the return type can be added with
+    /// [Signature::param] and [Signature::returns]
+    pub fn builder() -> Self {
+        Self::new(Vec::new(), TypeDescriptor::Void)
+    }
+
+    /// Add a parameter
+    pub fn param(mut self, ty: TypeDescriptor) -> Self {
+        self.params.push(ty);
+        self
+    }
+
+    /// Set the return type
+    pub fn returns(mut self, ty: TypeDescriptor) -> Self {
+        self.ret = ty;
+        self
+    }
+
+    /// Parse a method signature, e.g `(ILjava/lang/Object;)V`
+    pub fn parse(signature: &str) -> std::result::Result<Self, SignatureError> {
+        let invalid = || SignatureError::InvalidSignature(signature.to_string());
+        if !Self::is_valid(signature) {
+            return Err(invalid());
+        }
+
+        let mut params = Vec::new();
+        let mut i = 1;
+        while signature.as_bytes()[i] != b')' {
+            let (ty, end) = TypeDescriptor::parse_at(signature, i).ok_or_else(invalid)?;
+            params.push(ty);
+            i = end;
+        }
+
+        let ret = TypeDescriptor::parse(&signature[i + 1..]).map_err(|_| invalid())?;
+        Ok(Self { params, ret })
+    }
+
+    /// Returns true if `signature` is a valid method signature. Usable in constant expressions, see [signature!](crate::signature!)
+    pub const fn is_valid(signature: &str) -> bool {
+        let bytes = signature.as_bytes();
+        if bytes.is_empty() || bytes[0] != b'(' {
+            return false;
+        }
+
+        let mut i = 1;
+        while i < bytes.len() && bytes[i] != b')' {
+            i = match field_end(bytes, i) {
+                Some(end) => end,
+                None => return false,
+            };
+        }
+        if i >= bytes.len() {
+            return false;
+        }
+
+        // The return type
+        i += 1;
+        if i + 1 == bytes.len() && bytes[i] == b'V' {
+            return true;
+        }
+        matches!(field_end(bytes, i), Some(end) if end == bytes.len())
+    }
+}
+
+impl Display for Signature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(")?;
+        for param in &self.params {
+            write!(f, "{}", param)?;
+        }
+        write!(f, "){}", self.ret)
+    }
+}
+
+impl FromStr for Signature {
+    type Err = SignatureError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// The maximum number of array dimensions allowed by the JVM
+const MAX_ARRAY_DIMENSIONS: usize = 255;
+
+/// Find the end of the field descriptor starting at `start`. Returns None if there is no valid field descriptor at `start`
+const fn field_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut i = start;
+    while i < bytes.len() && bytes[i] == b'[' {
+        i += 1;
+    }
+    if i - start > MAX_ARRAY_DIMENSIONS || i >= bytes.len() {
+        return None;
+    }
+
+    match bytes[i] {
+        b'Z' | b'B' | b'C' | b'S' | b'I' | b'J' | b'F' | b'D' => Some(i + 1),
+        b'L' => {
+            let name_start = i + 1;
+            i = name_start;
+            while i < bytes.len() && bytes[i] != b';' {
+                let segment_start = i == name_start || bytes[i - 1] == b'/';
+                match bytes[i] {
+                    b'.' | b'[' | b'(' | b')' => return None,
+                    b'/' if segment_start => return None,
+                    _ => {}
+                }
+                i += 1;
+            }
+            if i >= bytes.len() || i == name_start || bytes[i - 1] == b'/' {
+                return None;
+            }
+            Some(i + 1)
+        }
+        _ => None,
+    }
+}
+
+/// A method signature, validated at compile time. Expands to a `&'static str`
+///
+/// ```
+/// const SIG: &str = ejni::signature!("(ILjava/lang/Object;)V");
+/// ```
+///
+/// ```compile_fail
+/// const SIG: &str = ejni::signature!("(Ljava/lang/Object)V");
+/// ```
+#[macro_export]
+macro_rules! signature {
+    ($signature:literal) => {{
+        const SIGNATURE: &str = $signature;
+        const _: () = assert!(
+            $crate::Signature::is_valid(SIGNATURE),
+            "invalid JNI method signature"
+        );
+        SIGNATURE
+    }};
+}
+
+/// A type descriptor, validated at compile time. Expands to a `&'static str`
+///
+/// ```
+/// const DESCRIPTOR: &str = ejni::descriptor!("[Ljava/lang/String;");
+/// ```
+///
+/// ```compile_fail
+/// const DESCRIPTOR: &str = ejni::descriptor!("java/lang/String");
+/// ```
+#[macro_export]
+macro_rules! descriptor {
+    ($descriptor:literal) => {{
+        const DESCRIPTOR: &str = $descriptor;
+        const _: () = assert!(
+            $crate::TypeDescriptor::is_valid(DESCRIPTOR),
+            "invalid JNI type descriptor"
+        );
+        DESCRIPTOR
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::JVM;
+
+    #[test]
+    fn parse_descriptor() {
+        assert_eq!(TypeDescriptor::Int, "I".parse().unwrap());
+        assert_eq!(TypeDescriptor::Void, "V".parse().unwrap());
+        assert_eq!(
+            TypeDescriptor::object("java.lang.String"),
+            "Ljava/lang/String;".parse().unwrap()
+        );
+        assert_eq!(
+            TypeDescriptor::object("java/lang/String").array().array(),
+            "[[Ljava/lang/String;".parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn invalid_descriptor() {
+        for descriptor in [
+            "",
+            "X",
+            "II",
+            "[",
+            "[V",
+            "Ljava/lang/String",
+            "L;",
+            "Ljava.lang.String;",
+            "Ljava//String;",
+            "Ljava/String/;",
+        ] {
+            assert!(!TypeDescriptor::is_valid(descriptor), "{}", descriptor);
+            assert_eq!(
+                Err(SignatureError::InvalidDescriptor(descriptor.to_string())),
+                TypeDescriptor::parse(descriptor)
+            );
+        }
+    }
+
+    #[test]
+    fn render_descriptor() {
+        assert_eq!("[J", TypeDescriptor::Long.array().to_string());
+        assert_eq!(
+            "Ljava/util/Map$Entry;",
+            TypeDescriptor::object("java.util.Map$Entry").to_string()
+        );
+    }
+
+    #[test]
+    fn parse_signature() {
+        let signature = Signature::parse("(I[JLjava/lang/Object;)V").unwrap();
+        assert_eq!(
+            vec![
+                TypeDescriptor::Int,
+                TypeDescriptor::Long.array(),
+                TypeDescriptor::object("java/lang/Object")
+            ],
+            signature.params
+        );
+        assert_eq!(TypeDescriptor::Void, signature.ret);
+        assert_eq!("(I[JLjava/lang/Object;)V", signature.to_string());
+
+        assert_eq!(
+            Signature::builder().returns(TypeDescriptor::Boolean),
+            "()Z".parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn invalid_signature() {
+        for signature in [
+            "",
+            "()",
+            "I",
+            "(I",
+            "(V)V",
+            "()VV",
+            "()Ljava/lang/Object",
+            "(Ljava/lang/Object)V",
+        ] {
+            assert!(!Signature::is_valid(signature), "{}", signature);
+            assert!(Signature::parse(signature).is_err());
+        }
+    }
+
+    #[test]
+    fn builder() {
+        let signature = Signature::builder()
+            .param(TypeDescriptor::Int)
+            .param(TypeDescriptor::object("java.lang.Object"))
+            .returns(TypeDescriptor::Void);
+        assert_eq!("(ILjava/lang/Object;)V", signature.to_string());
+    }
+
+    #[test]
+    fn macros() {
+        assert_eq!(
+            "(ILjava/lang/Object;)V",
+            signature!("(ILjava/lang/Object;)V")
+        );
+        assert_eq!("[I", descriptor!("[I"));
+    }
+
+    #[test]
+    fn from_class() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+
+        assert_eq!(
+            TypeDescriptor::object("java/lang/String").array(),
+            TypeDescriptor::from_class(&Class::String(&env).unwrap().array_type(&env).unwrap())
+                .unwrap()
+        );
+        assert_eq!(
+            TypeDescriptor::Int,
+            TypeDescriptor::from_class(&Class::int(&env).unwrap()).unwrap()
+        );
+    }
+
+    #[test]
+    fn signature_type() {
+        let ty: SignatureType = (&TypeDescriptor::Int.array()).into();
+        assert_eq!(
+            SignatureType::from_str("[I").unwrap().to_string(),
+            ty.to_string()
+        );
+    }
+}
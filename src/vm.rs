@@ -0,0 +1,348 @@
+use crate::error::Result;
+use crate::frame::FrameGuard;
+use jni::errors::jni_error_code_to_result;
+use jni::sys::{self, JavaVMAttachArgs};
+use jni::{JNIEnv, JavaVM};
+use std::cell::RefCell;
+use std::ffi::{c_void, CString};
+use std::os::raw::c_char;
+use std::sync::Arc;
+
+/// How [Vm::with_env] attaches a thread which is not attached to the JVM yet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Attach {
+    /// Attach the thread for the duration of the call, and detach it afterwards
+    Scoped,
+    /// Keep the thread attached until it exits. Preferable for threads calling into Java repeatedly, e.g the workers of a thread pool
+    Permanent,
+}
+
+/// The number of local references the closure passed to [Vm::with_env] can create before the JVM has to grow the local frame
+const FRAME_CAPACITY: i32 = 16;
+
+thread_local! {
+    /// Detaches a thread attached with [Attach::Permanent] when it exits
+    static DETACH_ON_EXIT: RefCell<Option<Detach>> = const { RefCell::new(None) };
+}
+
+/// Detaches the current thread when dropped
+struct Detach {
+    vm: *mut sys::JavaVM,
+}
+
+impl Drop for Detach {
+    fn drop(&mut self) {
+        // Safety: `vm` is the JavaVM the current thread was attached to by `Vm::attach_current_thread`
+        unsafe {
+            if let Some(detach_current_thread) = (**self.vm).DetachCurrentThread {
+                detach_current_thread(self.vm);
+            }
+        }
+    }
+}
+
+/// A handle to the JavaVM, giving threads which do not have a `JNIEnv` access to Java.
+/// It is cheap to clone, and can be shared between threads, e.g stored in a static from `JNI_OnLoad`.
+///
+/// [Vm::with_env] attaches the current thread if needed. How the thread is attached is configured on the handle:
+/// ```ignore
+/// let vm = Vm::new(java_vm)
+///     .attach(Attach::Permanent)
+///     .daemon(true)
+///     .thread_name("worker");
+///
+/// pool.spawn(move || {
+///     vm.with_env(|env| {
+///         let list = List::<String>::arraylist(env)?;
+///         list.add("Hello".to_string())
+///     })
+/// });
+/// ```
+#[derive(Clone)]
+pub struct Vm {
+    vm: Arc<JavaVM>,
+    attach: Attach,
+    daemon: bool,
+    thread_name: Option<String>,
+}
+
+impl Vm {
+    /// Create a handle to `vm`. Threads are attached with [Attach::Scoped] by default, as non-daemon threads without a name
+    pub fn new(vm: JavaVM) -> Self {
+        Self {
+            vm: Arc::new(vm),
+            attach: Attach::Scoped,
+            daemon: false,
+            thread_name: None,
+        }
+    }
+
+    /// Create a handle to the JavaVM of `env`
+    pub fn from_env(env: &JNIEnv<'_>) -> Result<Self> {
+        Ok(Self::new(env.get_java_vm()?))
+    }
+
+    /// Set how threads which are not attached yet are attached. Defaults to [Attach::Scoped]
+    pub fn attach(mut self, attach: Attach) -> Self {
+        self.attach = attach;
+        self
+    }
+
+    /// Attach threads as daemon threads, which do not prevent the JVM from shutting down. Defaults to false
+    pub fn daemon(mut self, daemon: bool) -> Self {
+        self.daemon = daemon;
+        self
+    }
+
+    /// The name of the `java.lang.Thread` of attached threads. Without a name, the JVM picks one, e.g `Thread-3`
+    pub fn thread_name<S: Into<String>>(mut self, name: S) -> Self {
+        self.thread_name = Some(name.into());
+        self
+    }
+
+    /// The JavaVM itself
+    pub fn java_vm(&self) -> &JavaVM {
+        &self.vm
+    }
+
+    /// Returns true if the current thread is attached to the JVM
+    pub fn is_attached(&self) -> bool {
+        self.vm.get_env().is_ok()
+    }
+
+    /// Call `f` with the `JNIEnv` of the current thread, attaching the thread first if it is not attached yet.
+    /// `f` runs in a new local reference frame, so local references it creates do not pile up on threads which stay attached.
+    ///
+    /// A thread attached with [Attach::Scoped] is detached when `f` returns, even if it panics.
+    /// With [Attach::Permanent] it stays attached, and is detached when the thread exits.
+    /// Threads which were attached already, e.g because they were called from Java, stay attached regardless
+    pub fn with_env<F, R>(&self, f: F) -> Result<R>
+    where
+        F: for<'a> FnOnce(&'a JNIEnv<'a>) -> R,
+    {
+        let (env, detach) = match self.vm.get_env() {
+            Ok(env) => (env, None),
+            Err(_) => {
+                let env = self.attach_current_thread()?;
+                let detach = Detach {
+                    vm: self.vm.get_java_vm_pointer(),
+                };
+                match self.attach {
+                    Attach::Scoped => (env, Some(detach)),
+                    Attach::Permanent => {
+                        let previous = DETACH_ON_EXIT.with(|d| d.borrow_mut().replace(detach));
+                        // The thread was detached by other means since, dropping would detach it again
+                        std::mem::forget(previous);
+                        (env, None)
+                    }
+                }
+            }
+        };
+
+        let result = {
+            let env = &env;
+            let _frame = FrameGuard::push(env, FRAME_CAPACITY)?;
+            f(env)
+        };
+
+        // The frame has been popped, so the thread can be detached
+        drop(detach);
+        Ok(result)
+    }
+
+    fn attach_current_thread(&self) -> Result<JNIEnv<'_>> {
+        // Thread names are C strings, so they end at the first NUL character
+        let name = self.thread_name.as_ref().map(|name| {
+            CString::new(name.split('\0').next().unwrap_or_default()).unwrap_or_default()
+        });
+        let mut args = JavaVMAttachArgs {
+            version: sys::JNI_VERSION_1_8,
+            name: name
+                .as_ref()
+                .map_or(std::ptr::null_mut(), |name| name.as_ptr() as *mut c_char),
+            group: std::ptr::null_mut(),
+        };
+
+        let raw = self.vm.get_java_vm_pointer();
+        let mut env = std::ptr::null_mut();
+        // Safety: `raw` is a valid JavaVM, and `args` outlives the call
+        let status = unsafe {
+            let attach =
+                match self.daemon {
+                    true => (**raw).AttachCurrentThreadAsDaemon.ok_or(
+                        jni::errors::Error::JavaVMMethodNotFound("AttachCurrentThreadAsDaemon"),
+                    )?,
+                    false => (**raw).AttachCurrentThread.ok_or(
+                        jni::errors::Error::JavaVMMethodNotFound("AttachCurrentThread"),
+                    )?,
+                };
+            attach(
+                raw,
+                &mut env,
+                &mut args as *mut JavaVMAttachArgs as *mut c_void,
+            )
+        };
+        jni_error_code_to_result(status)?;
+
+        // Safety: AttachCurrentThread succeeded, so `env` is the valid JNIEnv of the current thread
+        Ok(unsafe { JNIEnv::from_raw(env as *mut sys::JNIEnv)? })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::JVM;
+    use crate::List;
+    use jni::objects::JObject;
+
+    /// Returns the name of the current Java thread, and whether it is a daemon thread
+    fn current_thread(env: &JNIEnv<'_>) -> (String, bool) {
+        let thread = env
+            .call_static_method(
+                "java/lang/Thread",
+                "currentThread",
+                "()Ljava/lang/Thread;",
+                &[],
+            )
+            .unwrap()
+            .l()
+            .unwrap();
+        let name = env
+            .call_method(thread, "getName", "()Ljava/lang/String;", &[])
+            .unwrap()
+            .l()
+            .unwrap();
+        let daemon = env
+            .call_method(thread, "isDaemon", "()Z", &[])
+            .unwrap()
+            .z()
+            .unwrap();
+        (env.get_string(name.into()).unwrap().into(), daemon)
+    }
+
+    /// Returns true if a live Java thread is named `name`
+    fn thread_alive(env: &JNIEnv<'_>, name: &str) -> bool {
+        env.push_local_frame(16).unwrap();
+        let threads = env
+            .call_static_method(
+                "java/lang/Thread",
+                "getAllStackTraces",
+                "()Ljava/util/Map;",
+                &[],
+            )
+            .unwrap()
+            .l()
+            .unwrap();
+        let threads = env
+            .call_method(threads, "keySet", "()Ljava/util/Set;", &[])
+            .unwrap()
+            .l()
+            .unwrap();
+        let threads = env
+            .call_method(threads, "toArray", "()[Ljava/lang/Object;", &[])
+            .unwrap()
+            .l()
+            .unwrap();
+
+        let len = env.get_array_length(threads.into_inner()).unwrap();
+        let alive = (0..len).any(|i| {
+            let thread = env
+                .get_object_array_element(threads.into_inner(), i)
+                .unwrap();
+            let thread_name: JObject = env
+                .call_method(thread, "getName", "()Ljava/lang/String;", &[])
+                .unwrap()
+                .l()
+                .unwrap();
+            let found = String::from(env.get_string(thread_name.into()).unwrap()) == name;
+            env.delete_local_ref(thread_name).unwrap();
+            env.delete_local_ref(thread).unwrap();
+            found
+        });
+        env.pop_local_frame(JObject::null()).unwrap();
+        alive
+    }
+
+    #[test]
+    fn attached() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        let vm = Vm::from_env(&env).unwrap();
+
+        let size = vm
+            .with_env(|env| {
+                let list = List::<String>::arraylist(env).unwrap();
+                list.add("Hello".to_string()).unwrap();
+                list.size().unwrap()
+            })
+            .unwrap();
+        assert_eq!(1, size);
+        assert!(vm.is_attached());
+    }
+
+    #[test]
+    fn scoped() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        let vm = Vm::from_env(&env)
+            .unwrap()
+            .daemon(true)
+            .thread_name("ejni-scoped");
+
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                assert!(!vm.is_attached());
+                let (name, daemon) = vm.with_env(current_thread).unwrap();
+                assert_eq!("ejni-scoped", name);
+                assert!(daemon);
+                assert!(!vm.is_attached());
+            });
+        });
+    }
+
+    #[test]
+    fn permanent() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        let vm = Vm::from_env(&env)
+            .unwrap()
+            .attach(Attach::Permanent)
+            .thread_name("ejni-permanent");
+
+        let thread_vm = vm.clone();
+        let thread = std::thread::spawn(move || {
+            let (name, daemon) = thread_vm.with_env(current_thread).unwrap();
+            assert_eq!("ejni-permanent", name);
+            assert!(!daemon);
+            assert!(thread_vm.is_attached());
+
+            // The thread is still attached, so it is the same Java thread
+            let alive = thread_vm
+                .with_env(|env| thread_alive(env, "ejni-permanent"))
+                .unwrap();
+            assert!(alive);
+        });
+        // Unlike a scoped thread, joining waits until the thread-local destructors have run, which detach the thread
+        thread.join().unwrap();
+
+        assert!(!thread_alive(&env, "ejni-permanent"));
+    }
+
+    #[test]
+    fn scoped_panic() {
+        let jvm = JVM.lock().unwrap();
+        let env = jvm.attach_current_thread().unwrap();
+        let vm = Vm::from_env(&env).unwrap();
+
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                let result = std::panic::catch_unwind(|| {
+                    vm.with_env(|_| panic!("Panic while attached")).unwrap();
+                });
+                assert!(result.is_err());
+                assert!(!vm.is_attached());
+            });
+        });
+    }
+}
@@ -0,0 +1,170 @@
+// Generated by ejni-bindgen. Do not edit
+
+/// Wrapper around `ejni.test.Order`
+pub struct Order<'a> {
+    /// The Order itself
+    pub inner: ::ejni::Object<'a>,
+    env: &'a ::ejni::private::jni::JNIEnv<'a>,
+}
+
+impl<'a> ::ejni::JavaType<'a> for Order<'a> {
+    fn class(env: &'a ::ejni::private::jni::JNIEnv<'a>) -> ::ejni::Result<::ejni::Class<'a>> {
+        ::ejni::private::class(env, "ejni/test/Order")
+    }
+}
+
+impl<'a> ::ejni::IntoJava<'a> for Order<'a> {
+    fn into_java(self, _: &'a ::ejni::private::jni::JNIEnv<'a>) -> ::ejni::Result<::ejni::Object<'a>> {
+        Ok(self.inner)
+    }
+}
+
+impl<'a> ::ejni::FromJava<'a> for Order<'a> {
+    fn from_java(object: ::ejni::Object<'a>) -> ::ejni::Result<Self> {
+        Ok(Self::new(object.env, object))
+    }
+}
+
+impl<'a> Order<'a> {
+    /// Create a new Order wrapper. The caller must guarantee that the passed in Object is an instance of `ejni.test.Order`
+    pub fn new(env: &'a ::ejni::private::jni::JNIEnv<'a>, object: ::ejni::Object<'a>) -> Self {
+        Self { inner: object, env }
+    }
+
+    /// Invokes the constructor `Order()`
+    pub fn create(env: &'a ::ejni::private::jni::JNIEnv<'a>) -> ::ejni::Result<Self> {
+        let object = ::ejni::private::construct(
+            env,
+            "ejni/test/Order",
+            ::ejni::signature!("()V"),
+            &[],
+        )?;
+        Ok(Self::new(env, object))
+    }
+
+    /// Invokes `Order#getCustomer()`
+    pub fn get_customer(&self) -> ::ejni::Result<Option<String>> {
+        let value = ::ejni::private::call_method(
+            self.env,
+            self.inner.inner,
+            "ejni/test/Order",
+            "getCustomer",
+            ::ejni::signature!("()Ljava/lang/String;"),
+            &[],
+        )?;
+        let value = value.l()?;
+        if value.is_null() {
+            return Ok(None);
+        }
+
+        let value = ::ejni::JavaString::new(
+            self.env,
+            ::ejni::Object::new(self.env, value, ::ejni::Class::String(self.env)?),
+        );
+        Ok(Some(value.into_rust()?))
+    }
+
+    /// Invokes `Order#isPaid()`
+    pub fn is_paid(&self) -> ::ejni::Result<bool> {
+        let value = ::ejni::private::call_method(
+            self.env,
+            self.inner.inner,
+            "ejni/test/Order",
+            "isPaid",
+            ::ejni::signature!("()Z"),
+            &[],
+        )?;
+        Ok(value.z()?)
+    }
+
+    /// Invokes `Order#markPaid(boolean)`
+    pub fn mark_paid(&self, arg0: bool) -> ::ejni::Result<()> {
+        ::ejni::private::call_method(
+            self.env,
+            self.inner.inner,
+            "ejni/test/Order",
+            "markPaid",
+            ::ejni::signature!("(Z)V"),
+            &[::ejni::private::jni::objects::JValue::Bool(arg0 as u8)],
+        )?;
+        Ok(())
+    }
+
+    /// Invokes `Order#setCustomer(String)`
+    pub fn set_customer(&self, arg0: &str) -> ::ejni::Result<()> {
+        let arg0 = ::ejni::JavaString::from_rust(self.env, arg0)?;
+        ::ejni::private::call_method(
+            self.env,
+            self.inner.inner,
+            "ejni/test/Order",
+            "setCustomer",
+            ::ejni::signature!("(Ljava/lang/String;)V"),
+            &[arg0.into()],
+        )?;
+        Ok(())
+    }
+}
+
+/// Wrapper around `ejni.test.Plugin`
+pub struct Plugin<'a> {
+    /// The Plugin itself
+    pub inner: ::ejni::Object<'a>,
+    env: &'a ::ejni::private::jni::JNIEnv<'a>,
+}
+
+impl<'a> ::ejni::JavaType<'a> for Plugin<'a> {
+    fn class(env: &'a ::ejni::private::jni::JNIEnv<'a>) -> ::ejni::Result<::ejni::Class<'a>> {
+        ::ejni::private::class(env, "ejni/test/Plugin")
+    }
+}
+
+impl<'a> ::ejni::IntoJava<'a> for Plugin<'a> {
+    fn into_java(self, _: &'a ::ejni::private::jni::JNIEnv<'a>) -> ::ejni::Result<::ejni::Object<'a>> {
+        Ok(self.inner)
+    }
+}
+
+impl<'a> ::ejni::FromJava<'a> for Plugin<'a> {
+    fn from_java(object: ::ejni::Object<'a>) -> ::ejni::Result<Self> {
+        Ok(Self::new(object.env, object))
+    }
+}
+
+impl<'a> Plugin<'a> {
+    /// Create a new Plugin wrapper. The caller must guarantee that the passed in Object is an instance of `ejni.test.Plugin`
+    pub fn new(env: &'a ::ejni::private::jni::JNIEnv<'a>, object: ::ejni::Object<'a>) -> Self {
+        Self { inner: object, env }
+    }
+
+    /// Invokes the constructor `Plugin()`
+    pub fn create(env: &'a ::ejni::private::jni::JNIEnv<'a>) -> ::ejni::Result<Self> {
+        let object = ::ejni::private::construct(
+            env,
+            "ejni/test/Plugin",
+            ::ejni::signature!("()V"),
+            &[],
+        )?;
+        Ok(Self::new(env, object))
+    }
+
+    /// Invokes `Plugin#name()`
+    pub fn name(env: &'a ::ejni::private::jni::JNIEnv<'a>) -> ::ejni::Result<Option<String>> {
+        let value = ::ejni::private::call_static_method(
+            env,
+            "ejni/test/Plugin",
+            "name",
+            ::ejni::signature!("()Ljava/lang/String;"),
+            &[],
+        )?;
+        let value = value.l()?;
+        if value.is_null() {
+            return Ok(None);
+        }
+
+        let value = ::ejni::JavaString::new(
+            env,
+            ::ejni::Object::new(env, value, ::ejni::Class::String(env)?),
+        );
+        Ok(Some(value.into_rust()?))
+    }
+}
@@ -0,0 +1,23 @@
+package ejni.test;
+
+/**
+ * An exception which fails to describe itself, captured by the eJNI tests
+ */
+public class FaultyException extends RuntimeException {
+    public FaultyException() {
+        super();
+    }
+
+    @Override
+    public String getMessage() {
+        throw new IllegalStateException("No message");
+    }
+
+    /**
+     * Reports itself as its cause
+     */
+    @Override
+    public synchronized Throwable getCause() {
+        return this;
+    }
+}
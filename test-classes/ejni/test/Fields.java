@@ -0,0 +1,13 @@
+package ejni.test;
+
+/**
+ * Fields read and written by the eJNI field tests
+ */
+public class Fields extends FieldsBase {
+    public static long counter = 1;
+    public static Runnable task;
+
+    private int count = 10;
+    private String name = "Foo";
+    private Object value;
+}
@@ -0,0 +1,8 @@
+package ejni.test;
+
+/**
+ * Superclass of {@link Fields}, declaring a field inherited by it
+ */
+public class FieldsBase {
+    private int inherited = 5;
+}
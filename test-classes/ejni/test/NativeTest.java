@@ -0,0 +1,30 @@
+package ejni.test;
+
+/**
+ * Native methods registered by the eJNI tests
+ */
+public class NativeTest {
+    private final String prefix;
+
+    public NativeTest(String prefix) {
+        this.prefix = prefix;
+    }
+
+    public static native int add(int a, int b);
+
+    public static native long add(long a, long b);
+
+    public static native int size(java.util.List<?> list);
+
+    public static native String orDefault(String value);
+
+    public native String greet(String name);
+
+    public static int notNative(int a) {
+        return a;
+    }
+
+    public String getPrefix() {
+        return prefix;
+    }
+}
@@ -0,0 +1,29 @@
+package ejni.test;
+
+/**
+ * A POJO mapped onto a Rust struct by the `IntoJava` and `FromJava` derives
+ */
+public class Order {
+    private long orderId;
+    private String customer;
+    private boolean paid;
+    public Integer quantity;
+
+    public Order() {}
+
+    public String getCustomer() {
+        return customer;
+    }
+
+    public void setCustomer(String customer) {
+        this.customer = customer;
+    }
+
+    public boolean isPaid() {
+        return paid;
+    }
+
+    public void markPaid(boolean paid) {
+        this.paid = paid;
+    }
+}
@@ -0,0 +1,6 @@
+package ejni.test;
+
+/**
+ * A record mapped onto a Rust struct by the `IntoJava` and `FromJava` derives. Compiled with `javac --release 16`, as records require it
+ */
+public record OrderLine(String product, int amount, Double price) {}
@@ -0,0 +1,10 @@
+package ejni.test;
+
+/**
+ * Defined by the eJNI tests in a class loader of their own, never in the bootstrap class loader
+ */
+public class Plugin {
+    public static String name() {
+        return "plugin";
+    }
+}